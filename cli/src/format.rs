@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use rkyv::{
+    api::high::{HighDeserializer, HighSerializer, HighValidator},
+    bytecheck::CheckBytes,
+    rancor,
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+    Archive,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+    Rkyv,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|a| a.to_str()) {
+            Some("yml" | "yaml") => Ok(Self::Yaml),
+            Some("json") => Ok(Self::Json),
+            Some("rkyv" | "bin") => Ok(Self::Rkyv),
+            _ => Err(eyre!(
+                "Cannot tell the format of {} from its extension, expected .yml, .json or .rkyv",
+                path.display()
+            )),
+        }
+    }
+}
+
+pub fn read<T>(path: &Path) -> Result<T>
+where
+    T: DeserializeOwned + Archive,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, rancor::Error>>
+        + rkyv::Deserialize<T, HighDeserializer<rancor::Error>>,
+{
+    let format = Format::from_path(path)?;
+    let bytes = std::fs::read(path).wrap_err_with(|| format!("Reading {}", path.display()))?;
+    let value: Result<T> = match format {
+        Format::Yaml => serde_yaml::from_slice(&bytes).map_err(Into::into),
+        Format::Json => serde_json::from_slice(&bytes).map_err(Into::into),
+        Format::Rkyv => {
+            let mut aligned = AlignedVec::<16>::with_capacity(bytes.len());
+            aligned.extend_from_slice(&bytes);
+            rkyv::from_bytes::<T, rancor::Error>(&aligned).map_err(Into::into)
+        }
+    };
+    value.wrap_err_with(|| format!("Parsing {}", path.display()))
+}
+
+pub fn write<T>(path: &Path, value: &T) -> Result<()>
+where
+    T: Serialize
+        + for<'a> rkyv::Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, rancor::Error>>,
+{
+    let bytes = match Format::from_path(path)? {
+        Format::Yaml => serde_yaml::to_string(value)?.into_bytes(),
+        Format::Json => serde_json::to_vec_pretty(value)?,
+        Format::Rkyv => rkyv::to_bytes::<rancor::Error>(value)?.into_vec(),
+    };
+    std::fs::write(path, bytes).wrap_err_with(|| format!("Writing {}", path.display()))
+}
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use air_traffic_simulator::{engine::save, Config, Engine, WorldData};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::Result;
+use format::Format;
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+mod format;
+mod simulate;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Start the simulation server
+    Run {
+        #[command(flatten)]
+        input: Input,
+        /// JS file to build the client with
+        #[arg(long)]
+        client_config: Option<PathBuf>,
+        /// Token that clients connect with to rewind the simulation, reload the world and change
+        /// the config. The admin events are disabled without one.
+        #[arg(long)]
+        admin_token: Option<String>,
+    },
+    /// Check that the world file loads and is consistent
+    Validate {
+        /// World data file
+        #[arg(short, long)]
+        world: PathBuf,
+    },
+    /// Run the simulation as fast as possible without the server and print a summary
+    Simulate {
+        #[command(flatten)]
+        input: Input,
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        ticks: u64,
+        /// Overrides the seed in the config
+        #[arg(long)]
+        seed: Option<u64>,
+        /// File to write the final state to. An .rkyv file is written as a save that the engine
+        /// can be started from.
+        #[arg(long)]
+        dump: Option<PathBuf>,
+    },
+    /// Convert between YAML, JSON and rkyv, picked by file extension
+    Convert {
+        #[arg(long, value_enum, default_value_t = Kind::World)]
+        kind: Kind,
+        input: PathBuf,
+        output: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// World data file
+    #[arg(short, long)]
+    world: PathBuf,
+    /// Engine config file. Defaults are used if unset.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+}
+
+impl Input {
+    fn load(&self) -> Result<(WorldData, Config)> {
+        let world = format::read(&self.world)?;
+        let config = self
+            .config
+            .as_deref()
+            .map(format::read)
+            .transpose()?
+            .unwrap_or_default();
+        Ok((world, config))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Kind {
+    World,
+    Config,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+    tracing_subscriber::registry()
+        .with(EnvFilter::from_env("RUST_LOG"))
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .try_init()?;
+
+    match Cli::parse().command {
+        Command::Run {
+            input,
+            client_config,
+            admin_token,
+        } => {
+            let (world, config) = input.load()?;
+            let client_config = client_config.map(std::fs::read_to_string).transpose()?;
+            air_traffic_simulator::run_server(
+                Engine::new(world, config)?,
+                client_config.as_deref(),
+                admin_token.as_deref(),
+            )
+            .await?;
+        }
+        Command::Validate { world } => {
+            let world: WorldData = format::read(&world)?;
+            world.validate()?;
+            println!(
+                "OK: {} airports, {} waypoints, {} plane models, {} flights",
+                world.airports.len(),
+                world.waypoints.len(),
+                world.planes.len(),
+                world.flights.as_ref().map_or(0, |a| a.len())
+            );
+        }
+        Command::Simulate {
+            input,
+            ticks,
+            seed,
+            dump,
+        } => {
+            let (world, mut config) = input.load()?;
+            config.seed = seed.or(config.seed);
+            let mut engine = Engine::new(world, config)?;
+            println!("{}", simulate::run(&mut engine, ticks));
+            if let Some(path) = dump {
+                if Format::from_path(&path)? == Format::Rkyv {
+                    std::fs::write(path, save::to_bytes(&engine)?)?;
+                } else {
+                    format::write(&path, &engine.state)?;
+                }
+            }
+        }
+        Command::Convert {
+            kind,
+            input,
+            output,
+        } => match kind {
+            Kind::World => format::write(&output, &format::read::<WorldData>(&input)?)?,
+            Kind::Config => format::write(&output, &format::read::<Config>(&input)?)?,
+        },
+    }
+    Ok(())
+}
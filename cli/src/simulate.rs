@@ -0,0 +1,94 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use air_traffic_simulator::{engine::journal::Record, Engine};
+use derive_more::Display;
+
+#[derive(Debug, Display)]
+#[display(
+    "Simulated {ticks} ticks ({sim_time:.0}s) in {wall_time:?} with seed {seed}\n\
+     Planes spawned: {spawned}, landed: {landed}, still flying: {flying}, go-arounds: {go_arounds}, taxi holds: {taxi_holds}\n\
+     Turnarounds: {turnarounds}, mean gate utilisation: {gate_utilisation:.0}%\n\
+     Departures: {departures}, mean departure delay: {departure_delay:.0}s\n\
+     Re-routes around weather and airspace: {reroutes}, airspace infringements: {infringements}, terrain warnings: {terrain_warnings}\n\
+     Pairs of planes that lost separation: {separation_lost}, resolution advisories: {advisories}"
+)]
+pub struct Summary {
+    pub ticks: u64,
+    pub sim_time: f64,
+    pub wall_time: Duration,
+    pub seed: u64,
+    pub spawned: usize,
+    pub landed: usize,
+    pub flying: usize,
+    pub go_arounds: u64,
+    pub taxi_holds: u64,
+    pub turnarounds: u64,
+    /// Percentage of gates occupied, averaged over the airports with gates and the ticks
+    pub gate_utilisation: f32,
+    pub departures: u64,
+    /// Seconds departures waited for takeoff clearance, on average
+    pub departure_delay: f64,
+    pub reroutes: u64,
+    pub infringements: u64,
+    pub terrain_warnings: u64,
+    pub separation_lost: usize,
+    pub advisories: u64,
+}
+
+pub fn run(engine: &mut Engine, ticks: u64) -> Summary {
+    let start = Instant::now();
+    let start_time = engine.state.clock.elapsed;
+    let start_stats = engine.state.stats;
+    let mut spawned = 0;
+    let mut landed = 0;
+    let mut lost = HashSet::new();
+    let mut utilisation = (0.0, 0);
+    for _ in 0..ticks {
+        let (_, _, journal) = engine.tick_journaled();
+        for entry in journal {
+            match entry.record {
+                Record::Spawn { .. } => spawned += 1,
+                Record::Landed { .. } => landed += 1,
+                _ => {}
+            }
+        }
+        for airport in &engine.state.airports {
+            if let Some(u) = airport.gate_utilisation() {
+                utilisation = (utilisation.0 + u, utilisation.1 + 1);
+            }
+        }
+        lost.extend(
+            engine
+                .state
+                .conflicts
+                .iter()
+                .filter(|a| a.current)
+                .map(|a| a.planes),
+        );
+    }
+    let departures = engine.state.stats.departures - start_stats.departures;
+    Summary {
+        ticks,
+        sim_time: engine.state.clock.elapsed - start_time,
+        wall_time: start.elapsed(),
+        seed: engine.config.seed.unwrap_or_default(),
+        spawned,
+        landed,
+        flying: engine.state.planes.len(),
+        go_arounds: engine.state.stats.go_arounds - start_stats.go_arounds,
+        taxi_holds: engine.state.stats.taxi_holds - start_stats.taxi_holds,
+        turnarounds: engine.state.stats.turnarounds - start_stats.turnarounds,
+        gate_utilisation: 100.0 * utilisation.0 / utilisation.1.max(1) as f32,
+        departures,
+        departure_delay: (engine.state.stats.departure_delay - start_stats.departure_delay)
+            / departures.max(1) as f64,
+        reroutes: engine.state.stats.reroutes - start_stats.reroutes,
+        infringements: engine.state.stats.infringements - start_stats.infringements,
+        terrain_warnings: engine.state.stats.terrain_warnings - start_stats.terrain_warnings,
+        separation_lost: lost.len(),
+        advisories: engine.state.stats.advisories - start_stats.advisories,
+    }
+}
@@ -14,6 +14,7 @@ export interface AirportState {
 
 export const airportStates = reactive(new Map<string, AirportState>());
 export const selectedAirport = ref<string>();
+const airportLayer = markRaw(L.layerGroup());
 
 export function airportCoords(airport: AirportData): [number, number] {
   return airport.runways
@@ -49,6 +50,8 @@ export async function getAirportInfo(id: string, force?: boolean) {
 
 export async function drawAirports() {
   const wd = await getWorldData();
+  airportLayer.clearLayers().addTo(rawMap());
+  airportStates.clear();
   for (const airport of wd.airports) {
     for (const runway of airport.runways) {
       L.polyline(
@@ -64,7 +67,37 @@ export async function drawAirports() {
         )
         .on("popupopen", () => selectAirport(airport.code))
         .on("popupclose", () => deselectAirport())
-        .addTo(rawMap());
+        .addTo(airportLayer);
+    }
+
+    for (const taxiway of airport.taxiways) {
+      for (const name of taxiway.connections) {
+        // each connection once
+        if (name < taxiway.name) continue;
+        const other = airport.taxiways.find((a) => a.name === name);
+        if (other === undefined) continue;
+        L.polyline(
+          [config.world2map(taxiway.pos), config.world2map(other.pos)],
+          {
+            color: "orange",
+            weight: 3,
+            opacity: 0.5,
+            interactive: false,
+          },
+        ).addTo(airportLayer);
+      }
+    }
+    for (const gate of airport.gates) {
+      L.circleMarker(config.world2map(gate.pos), {
+        radius: 4,
+        color: "orange",
+      })
+        .bindPopup(
+          `${escape(airport.name)} (${escape(airport.code)})<br>Gate ${escape(gate.name)}`,
+        )
+        .on("popupopen", () => selectAirport(airport.code))
+        .on("popupclose", () => deselectAirport())
+        .addTo(airportLayer);
     }
 
     const marker = markRaw(
@@ -75,7 +108,7 @@ export async function drawAirports() {
         .bindPopup(`${escape(airport.name)} (${airport.code})`)
         .on("popupopen", () => selectAirport(airport.code))
         .on("popupclose", () => deselectAirport())
-        .addTo(rawMap()),
+        .addTo(airportLayer),
     );
     airportStates.set(airport.code, { marker });
   }
@@ -0,0 +1,37 @@
+import { markRaw } from "vue";
+import type { AirspaceKind } from "./bindings/AirspaceKind";
+import { escape } from "./util";
+import { getWorldData } from "./staticData";
+import config from "./config";
+import { outline } from "./weather";
+import { rawMap } from "@/map.ts";
+
+const airspaceLayer = markRaw(L.layerGroup());
+
+const colours: Record<AirspaceKind, string> = {
+  Prohibited: "red",
+  Military: "orange",
+  Temporary: "yellow",
+};
+
+export async function drawAirspace() {
+  const wd = await getWorldData();
+  airspaceLayer.clearLayers().addTo(rawMap());
+  for (const airspace of wd.airspace) {
+    let schedule = airspace.schedule
+      .map((a) => `${a.start}s to ${a.end}s`)
+      .join(", ");
+    if (schedule === "") schedule = "always";
+    else if (airspace.period !== null) schedule += ` every ${airspace.period}s`;
+    L.polygon(outline(airspace.shape).map((a) => config.world2map(a)), {
+      color: colours[airspace.kind],
+      weight: 2,
+      dashArray: "6 4",
+      fillOpacity: 0.1,
+    })
+      .bindPopup(
+        `${escape(airspace.kind)} area ${escape(airspace.name)}<br>Altitude: ${escape(airspace.floor.toString())} to ${escape(airspace.ceiling.toString())}<br>Active: ${escape(schedule)}`,
+      )
+      .addTo(airspaceLayer);
+  }
+}
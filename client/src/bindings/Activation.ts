@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stretch of time for which an [`Airspace`] is active, in seconds into the simulation, or into
+ * the period if the schedule repeats.
+ */
+export interface Activation {
+  start: number;
+  end: number;
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CellShape } from "./CellShape";
+
+/**
+ * A weather cell as it is at some moment.
+ */
+export interface ActiveCell {
+  name: string;
+  shape: CellShape;
+  floor: number;
+  ceiling: number;
+}
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Sense } from "./Sense";
+
+export interface Advisory {
+  /**
+   * The plane this one is avoiding
+   */
+  against: string;
+  sense: Sense;
+}
@@ -1,9 +1,22 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AirportData } from "./AirportData";
 import type { AirportEvent } from "./AirportEvent";
+import type { GateState } from "./GateState";
+import type { Holding } from "./Holding";
+import type { RunwayState } from "./RunwayState";
 
 export interface Airport {
   id: string;
   airport: AirportData;
   events: AirportEvent[];
+  runways: RunwayState[];
+  /**
+   * Runway requests waiting for a runway to become free, first come first served
+   */
+  queue: AirportEvent[];
+  /**
+   * Queued planes sent to hold
+   */
+  holding: Holding[];
+  gates: GateState[];
 }
@@ -1,8 +1,32 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Gate } from "./Gate";
+import type { HoldingFix } from "./HoldingFix";
+import type { Procedure } from "./Procedure";
 import type { Runway } from "./Runway";
+import type { Waypoint } from "./Waypoint";
 
 export interface AirportData {
   name: string;
   code: string;
   runways: Runway[];
+  /**
+   * Where arrivals wait for a runway. Planes hold at the start of the first runway's approach if
+   * there are none.
+   */
+  holding_fixes: HoldingFix[];
+  /**
+   * Nodes of the taxiway network, joined by their connections. Planes spawn on and vanish from
+   * the runways if there are none.
+   */
+  taxiways: Waypoint[];
+  gates: Gate[];
+  /**
+   * Departures from the runways to the airways
+   */
+  sids: Procedure[];
+  /**
+   * Arrivals from the airways to the runways. Arrivals leave the airways at the start of one if
+   * there are any.
+   */
+  stars: Procedure[];
 }
@@ -3,5 +3,6 @@ import type { AirportEventPayload } from "./AirportEventPayload";
 
 export interface AirportEvent {
   from: string;
+  time: number;
   payload: AirportEventPayload;
 }
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GoAroundReason } from "./GoAroundReason";
 
-export type AirportEventPayload = "RequestRunway";
+export type AirportEventPayload =
+  | "RequestRunway"
+  | { RequestDeparture: { runway: string } }
+  | { GoAround: { runway: string; reason: GoAroundReason } };
@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Activation } from "./Activation";
+import type { AirspaceKind } from "./AirspaceKind";
+import type { CellShape } from "./CellShape";
+
+/**
+ * A volume of airspace planes must keep out of while it is active.
+ */
+export interface Airspace {
+  name: string;
+  kind: AirspaceKind;
+  /**
+   * Outline as seen from above
+   */
+  shape: CellShape;
+  /**
+   * Lowest altitude the volume reaches
+   */
+  floor: number;
+  /**
+   * Highest altitude the volume reaches
+   */
+  ceiling: number;
+  /**
+   * Times the volume is active. It is always active if there are none.
+   */
+  schedule: Activation[];
+  /**
+   * Seconds after which the schedule starts over, e.g. 86400 for one that is the same every
+   * day. The schedule is only followed once if unset.
+   */
+  period: number | null;
+}
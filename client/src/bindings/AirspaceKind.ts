@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the airspace is set aside for. Planes keep out of every kind alike, but clients may show
+ * them differently.
+ */
+export type AirspaceKind = "Prohibited" | "Military" | "Temporary";
@@ -4,5 +4,122 @@ export interface Config {
   tick_duration: number;
   plane_spawn_chance: number;
   max_planes: number | null;
-  cruising_altitude: number;
+  cruising_altitude_plus: number;
+  cruising_altitude_minus: number;
+  ns_before_ew: boolean;
+  /**
+   * Seed for every random decision in the engine. A random seed is picked if unset.
+   */
+  seed: bigint | null;
+  /**
+   * Start of the simulation clock, in seconds since the Unix epoch. Defaults to the current time.
+   */
+  epoch: bigint | null;
+  save_path: string | null;
+  /**
+   * File to append a journal of the session to, for [`crate::engine::Engine::replay`].
+   */
+  journal_path: string | null;
+  /**
+   * Number of past states kept for rewinding. Rewinding is disabled if zero.
+   */
+  history_len: number;
+  /**
+   * Only keep a state every this many ticks.
+   */
+  history_interval: number;
+  /**
+   * Horizontal distance airborne planes must keep from each other
+   */
+  separation_horizontal: number;
+  /**
+   * Vertical distance airborne planes must keep from each other
+   */
+  separation_vertical: number;
+  /**
+   * Seconds ahead to predict losses of separation
+   */
+  conflict_look_ahead: number;
+  /**
+   * Whether planes are ordered to climb or descend out of conflicts
+   */
+  collision_avoidance: boolean;
+  /**
+   * Seconds before the closest approach of a conflict that collision avoidance steps in
+   */
+  advisory_time: number;
+  /**
+   * Seconds a runway is kept free after a plane leaves it
+   */
+  runway_spacing_time: number;
+  /**
+   * Distance the last plane to use a runway must be from it before the next is cleared onto it
+   */
+  runway_spacing_distance: number;
+  /**
+   * Altitude of the lowest level of a holding stack. Each level above it is
+   * [`Config::separation_vertical`] higher.
+   */
+  holding_altitude: number;
+  /**
+   * Distance before the runway from which a landing plane must be low and slow enough to make
+   * the touchdown point, or go around
+   */
+  stable_approach_distance: number;
+  /**
+   * Fastest a plane may fly within [`Config::stable_approach_distance`], as a fraction of its
+   * top speed
+   */
+  stable_approach_speed: number;
+  /**
+   * Highest a plane may be within [`Config::stable_approach_distance`], as height above the
+   * runway per distance left to the touchdown point
+   */
+  stable_approach_slope: number;
+  /**
+   * Height above the runway to climb to when going around, for runways without a missed
+   * approach procedure
+   */
+  missed_approach_height: number;
+  /**
+   * Speed planes taxi at
+   */
+  taxi_speed: number;
+  /**
+   * Radius of the turns taxiing planes make at taxiway corners
+   */
+  taxi_turning_radius: number;
+  /**
+   * Distance a taxiing plane waits behind the plane ahead of it
+   */
+  taxi_separation: number;
+  /**
+   * Seconds between departures from a runway behind a plane of a larger class
+   */
+  departure_wake_spacing: number;
+  /**
+   * Seconds between departures from a runway to the same airport
+   */
+  departure_route_spacing: number;
+  /**
+   * Seconds a plane stays parked at its gate before leaving as its next flight
+   */
+  turnaround_time: number;
+  /**
+   * Headwind a runway may have less of than the one most into the wind and still be used
+   */
+  runway_headwind_margin: number;
+  /**
+   * Height above the ground planes cruise and descend no lower than
+   */
+  terrain_clearance: number;
+  /**
+   * Highest altitude planes climb to over high ground. Ground needing more than this is flown
+   * around.
+   */
+  max_altitude: number;
+  /**
+   * Height above the ground within which airborne planes are warned of it
+   */
+  terrain_warning_height: number;
 }
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Two planes that have lost, or are predicted to lose, separation.
+ */
+export interface Conflict {
+  planes: [string, string];
+  /**
+   * Seconds until the planes are closest while in conflict
+   */
+  time_to_closest: number;
+  /**
+   * Horizontal distance between the planes when they are closest
+   */
+  min_distance: number;
+  /**
+   * Vertical distance between the planes when they are closest
+   */
+  vertical_distance: number;
+  /**
+   * Whether separation is already lost, rather than predicted to be
+   */
+  current: boolean;
+}
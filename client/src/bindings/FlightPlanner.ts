@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Procedure } from "./Procedure";
 import type { Waypoint } from "./Waypoint";
 
 export interface FlightPlanner {
@@ -8,4 +9,8 @@ export interface FlightPlanner {
   past_instructions: null;
   past_route: Waypoint[];
   past_pos: [number, number, number][];
+  /**
+   * SID or STAR whose fixes are in the route, flown within their constraints
+   */
+  procedure: Procedure | null;
 }
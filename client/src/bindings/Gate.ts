@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface Gate {
+  name: string;
+  pos: [number, number];
+  /**
+   * Taxiway node planes are pushed back onto, and enter the gate from
+   */
+  taxiway: string;
+  /**
+   * Largest class of plane the gate fits
+   */
+  class: string;
+  /**
+   * Airlines whose planes are parked here before anywhere else
+   */
+  airlines: string[];
+}
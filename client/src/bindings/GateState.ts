@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface GateState {
+  name: string;
+  /**
+   * Plane parked at the gate, taxiing to it, or being pushed back from it
+   */
+  occupant: string | null;
+}
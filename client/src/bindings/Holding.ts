@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HoldingFix } from "./HoldingFix";
+
+export interface Holding {
+  plane: string;
+  fix: HoldingFix;
+  /**
+   * Level in the stack at `fix`, 0 being the lowest
+   */
+  level: number;
+}
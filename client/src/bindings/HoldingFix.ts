@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface HoldingFix {
+  name: string;
+  /**
+   * Start of the inbound leg
+   */
+  start: [number, number];
+  /**
+   * The fix itself, at the end of the inbound leg
+   */
+  end: [number, number];
+  /**
+   * Turn left at each end of the racetrack instead of right
+   */
+  left_turns: boolean;
+}
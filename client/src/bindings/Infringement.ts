@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AirspaceKind } from "./AirspaceKind";
+
+/**
+ * A plane inside a volume of active airspace.
+ */
+export interface Infringement {
+  plane: string;
+  airspace: string;
+  kind: AirspaceKind;
+}
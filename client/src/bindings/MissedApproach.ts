@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface MissedApproach {
+  /**
+   * Points to fly through after the end of the runway, in order
+   */
+  path: Array<[number, number]>;
+  /**
+   * Height above the runway to climb to
+   */
+  height: number;
+}
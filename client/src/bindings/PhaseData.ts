@@ -1,8 +1,18 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Gate } from "./Gate";
+import type { HoldingFix } from "./HoldingFix";
 import type { Runway } from "./Runway";
 
 export type PhaseData =
+  | { Pushback: { gate: Gate; runway: Runway } }
+  | { TaxiOut: { runway: Runway } }
+  | { HoldingPoint: { runway: Runway } }
   | { Takeoff: { runway: Runway } }
   | "Cruise"
   | "Descent"
-  | { Landing: { runway: Runway } };
+  | { Holding: { fix: HoldingFix; altitude: number } }
+  | { Landing: { runway: Runway } }
+  | { GoAround: { runway: Runway } }
+  | { MissedApproach: { runway: Runway } }
+  | { TaxiIn: { gate: Gate } }
+  | { Parked: { gate: Gate } };
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Advisory } from "./Advisory";
 import type { Flight } from "./Flight";
 import type { PhaseData } from "./PhaseData";
 import type { PlaneData } from "./PlaneData";
@@ -12,5 +13,14 @@ export interface Plane {
   flight: Flight;
   phase: PhaseData;
   events: PlaneEvent[];
-  start_time: bigint;
+  start_time: number;
+  phase_start: number;
+  /**
+   * Manoeuvre ordered by collision avoidance, until clear of conflict
+   */
+  advisory: Advisory | null;
+  /**
+   * Plane ahead that this one has stopped behind while taxiing
+   */
+  taxi_hold: string | null;
 }
@@ -7,5 +7,8 @@ export interface PlaneData {
   manufacturer: string;
   class: string;
   motion: ModelMotion;
+  min_takeoff_distance: number;
+  min_landing_distance: number;
+  max_crosswind: number | null;
   icon: string | null;
 }
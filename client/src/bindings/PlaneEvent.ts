@@ -3,5 +3,6 @@ import type { PlaneEventPayload } from "./PlaneEventPayload";
 
 export interface PlaneEvent {
   from: string;
+  time: number;
   payload: PlaneEventPayload;
 }
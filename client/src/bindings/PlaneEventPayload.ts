@@ -1,6 +1,13 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AirportData } from "./AirportData";
+import type { GoAroundReason } from "./GoAroundReason";
+import type { HoldingFix } from "./HoldingFix";
 import type { Runway } from "./Runway";
 
-export interface PlaneEventPayload {
-  ClearForLanding: Runway;
-}
+export type PlaneEventPayload =
+  | { ClearForLanding: Runway }
+  | { ClearForTakeoff: Runway }
+  | { Hold: { fix: HoldingFix; altitude: number } }
+  | "Release"
+  | { GoAround: GoAroundReason }
+  | { Divert: AirportData };
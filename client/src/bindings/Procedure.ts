@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProcedureFix } from "./ProcedureFix";
+
+/**
+ * A standard instrument departure (SID) or standard terminal arrival route (STAR): a fixed path
+ * between the runways it serves and a waypoint of the airways.
+ */
+export interface Procedure {
+  name: string;
+  /**
+   * Names of the runways the procedure is flown from or to
+   */
+  runways: string[];
+  /**
+   * Waypoint a departure joins the airways at, or an arrival leaves them at
+   */
+  waypoint: string;
+  /**
+   * Points flown through between the runway and the waypoint, in the order they are flown
+   */
+  fixes: ProcedureFix[];
+}
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface ProcedureFix {
+  name: string;
+  pos: [number, number];
+  /**
+   * Lowest altitude to fly towards the fix at
+   */
+  min_altitude: number | null;
+  /**
+   * Highest altitude to fly towards the fix at
+   */
+  max_altitude: number | null;
+  /**
+   * Fastest to fly towards the fix at
+   */
+  max_speed: number | null;
+}
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MissedApproach } from "./MissedApproach";
 
 export interface Runway {
   name: string;
@@ -6,4 +7,9 @@ export interface Runway {
   end: [number, number];
   altitude: number;
   class: string;
+  /**
+   * Flown by planes that go around. Planes climb out straight ahead for one runway length if
+   * unset.
+   */
+  missed_approach: MissedApproach | null;
 }
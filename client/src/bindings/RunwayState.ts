@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LastDeparture } from "./LastDeparture";
+
+export interface RunwayState {
+  name: string;
+  /**
+   * Plane cleared to land on or take off from the runway, until it has left it
+   */
+  occupant: string | null;
+  /**
+   * The last plane to leave the runway, and when
+   */
+  vacated: [string, number] | null;
+  /**
+   * The last plane cleared to take off from the runway, which the next departure is spaced
+   * behind
+   */
+  last_departure: LastDeparture | null;
+}
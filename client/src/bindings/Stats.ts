@@ -0,0 +1,44 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Running counts of how the simulation went. They are kept with the state, so saves and rewinds
+ * restore them too.
+ */
+export interface Stats {
+  /**
+   * Resolution advisories issued by collision avoidance
+   */
+  advisories: number;
+  /**
+   * Landings abandoned by going around
+   */
+  go_arounds: number;
+  /**
+   * Times a taxiing plane stopped to wait for the plane ahead of it
+   */
+  taxi_holds: number;
+  /**
+   * Planes that left their gate as their next flight
+   */
+  turnarounds: number;
+  /**
+   * Planes cleared to take off
+   */
+  departures: number;
+  /**
+   * Seconds departures waited at the holding point for takeoff clearance, in total
+   */
+  departure_delay: number;
+  /**
+   * Times a plane was re-routed around weather or active airspace
+   */
+  reroutes: number;
+  /**
+   * Times a plane entered active airspace
+   */
+  infringements: number;
+  /**
+   * Times an airborne plane came too close to the ground
+   */
+  terrain_warnings: number;
+}
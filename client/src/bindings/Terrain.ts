@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TerrainArea } from "./TerrainArea";
+import type { TerrainGrid } from "./TerrainGrid";
+
+/**
+ * Height of the ground, which planes keep [`Config::terrain_clearance`] above. Flat at zero
+ * everywhere if unset.
+ */
+export interface Terrain {
+  grid: TerrainGrid | null;
+  /**
+   * Areas of high ground, e.g. ridges or masts, raising the ground under them to their
+   * elevation
+   */
+  areas: TerrainArea[];
+}
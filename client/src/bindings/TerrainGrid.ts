@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Elevations at points spaced evenly over a rectangle, in rows from `origin` along the y axis,
+ * each of `columns` points along the x axis.
+ */
+export interface TerrainGrid {
+  origin: [number, number];
+  /**
+   * Distance between neighbouring points
+   */
+  spacing: number;
+  columns: number;
+  elevations: number[];
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An airborne plane closer to the ground than [`Config::terrain_warning_height`].
+ */
+export interface TerrainWarning {
+  plane: string;
+  /**
+   * Height of the plane above the ground under it
+   */
+  height: number;
+}
@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CellShape } from "./CellShape";
+
+/**
+ * A storm that planes route around. It forms at `start`, grows for `growth` seconds, stays at
+ * full size for `mature` seconds and dies away over `decay` seconds, drifting at `velocity` all
+ * the while.
+ */
+export interface WeatherCell {
+  name: string;
+  /**
+   * Outline at full size, where the cell forms
+   */
+  shape: CellShape;
+  /**
+   * Lowest altitude the cell reaches
+   */
+  floor: number;
+  /**
+   * Highest altitude the cell reaches
+   */
+  ceiling: number;
+  velocity: [number, number];
+  /**
+   * Seconds into the simulation the cell forms
+   */
+  start: number;
+  growth: number;
+  mature: number;
+  decay: number;
+}
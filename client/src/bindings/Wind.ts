@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WindGrid } from "./WindGrid";
+import type { WindLayer } from "./WindLayer";
+import type { WindVariation } from "./WindVariation";
+
+/**
+ * Velocity of the air, which carries airborne planes along with it. Calm everywhere if unset.
+ */
+export interface Wind {
+  /**
+   * Wind by altitude, from the lowest layer up. It is interpolated between layers, and the same
+   * as the nearest layer above the highest or below the lowest.
+   */
+  layers: WindLayer[];
+  /**
+   * Local wind, added to that of the layers
+   */
+  grid: WindGrid | null;
+  /**
+   * How the whole field changes over time
+   */
+  variation: WindVariation | null;
+}
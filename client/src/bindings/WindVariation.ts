@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Makes the wind veer back and forth and strengthen and weaken, over and over.
+ */
+export interface WindVariation {
+  /**
+   * Seconds before the wind is back to how it started
+   */
+  period: number;
+  /**
+   * Furthest the wind turns either way, in radians
+   */
+  veer: number;
+  /**
+   * Furthest the wind speed strays either way, as a fraction of it
+   */
+  gust: number;
+}
@@ -1,8 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AirportData } from "./AirportData";
+import type { Airspace } from "./Airspace";
 import type { Flight } from "./Flight";
 import type { PlaneData } from "./PlaneData";
+import type { Terrain } from "./Terrain";
 import type { Waypoint } from "./Waypoint";
+import type { WeatherCell } from "./WeatherCell";
+import type { Wind } from "./Wind";
 
 export interface WorldData {
   classes: string[][];
@@ -10,4 +14,11 @@ export interface WorldData {
   flights: Flight[] | null;
   planes: PlaneData[];
   waypoints: Waypoint[];
+  wind: Wind;
+  weather: WeatherCell[];
+  /**
+   * Prohibited, military and temporarily restricted areas that planes keep out of
+   */
+  airspace: Airspace[];
+  terrain: Terrain;
 }
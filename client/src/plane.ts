@@ -3,6 +3,7 @@ import "Leaflet.MultiOptionsPolyline";
 import { stringify as uuidStringify } from "uuid";
 import { markRaw, reactive, ref } from "vue";
 import type { Plane } from "./bindings/Plane";
+import type { Clock } from "./bindings/Clock";
 import socket from "./socket";
 import { escape } from "./util";
 import { rawMap } from "@/map";
@@ -24,6 +25,12 @@ export interface PlaneState {
 
 export const planeStates = reactive(new Map<string, PlaneState>());
 export const selectedPlane = ref<SelectedPlane>();
+export const clock = ref<Clock>();
+
+export function simTime() {
+  const c = clock.value;
+  return c === undefined ? new Date().valueOf() / 1000 : c.epoch + c.elapsed;
+}
 
 export function updateSelectPlane(latLng: L.LatLng) {
   selectedPlane.value?.path?.setLatLngs(
@@ -98,7 +105,8 @@ export async function selectPlane(id: string, e: L.PopupEvent) {
 }
 
 export function handleStateUpdates() {
-  socket.value.on("state", (removed, bin) => {
+  socket.value.on("state", (removed, bin, newClock) => {
+    clock.value = newClock;
     for (const remove of removed) {
       planeStates.get(remove)?.marker.remove();
       planeStates.delete(remove);
@@ -4,10 +4,21 @@ import type { Plane } from "./bindings/Plane";
 import type { Airport } from "./bindings/Airport";
 import type { WorldData } from "./bindings/WorldData";
 import type { Config } from "./bindings/Config";
+import type { Clock } from "./bindings/Clock";
+import type { Conflict } from "./bindings/Conflict";
+import type { Stats } from "./bindings/Stats";
+import type { ActiveCell } from "./bindings/ActiveCell";
+import type { Infringement } from "./bindings/Infringement";
+import type { TerrainWarning } from "./bindings/TerrainWarning";
 import config from "./config";
 
 interface ServerToClientEvents {
-  state: (removed: string[], bin: ArrayBuffer) => void;
+  state: (removed: string[], bin: ArrayBuffer, clock: Clock) => void;
+  world_data_changed: () => void;
+  conflicts: (conflicts: Conflict[]) => void;
+  weather: (cells: ActiveCell[]) => void;
+  infringements: (infringements: Infringement[]) => void;
+  terrain_warnings: (warnings: TerrainWarning[]) => void;
 }
 
 interface ClientToServerEvents {
@@ -17,6 +28,13 @@ interface ClientToServerEvents {
   config: (cb: (a: Config) => void) => void;
   airport_departures: (code: string, cb: (a: string[]) => void) => void;
   airport_arrivals: (code: string, cb: (a: string[]) => void) => void;
+  history: (cb: (a: [number[], number]) => void) => void;
+  rewind: (tick: number, cb: (a: number | null) => void) => void;
+  conflicts: (cb: (a: Conflict[]) => void) => void;
+  stats: (cb: (a: Stats) => void) => void;
+  weather: (cb: (a: ActiveCell[]) => void) => void;
+  infringements: (cb: (a: Infringement[]) => void) => void;
+  terrain_warnings: (cb: (a: TerrainWarning[]) => void) => void;
 }
 
 export default ref(
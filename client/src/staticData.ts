@@ -9,6 +9,13 @@ export async function getWorldData() {
   return worldData!;
 }
 
+export function handleWorldDataChanges(redraw: () => void) {
+  socket.value.on("world_data_changed", () => {
+    worldData = undefined;
+    redraw();
+  });
+}
+
 let config: Config | undefined;
 export async function getEngineConfig() {
   if (config !== undefined) return config;
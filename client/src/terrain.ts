@@ -0,0 +1,24 @@
+import { markRaw } from "vue";
+import { escape } from "./util";
+import { getWorldData } from "./staticData";
+import config from "./config";
+import { outline } from "./weather";
+import { rawMap } from "@/map.ts";
+
+const terrainLayer = markRaw(L.layerGroup());
+
+export async function drawTerrain() {
+  const wd = await getWorldData();
+  terrainLayer.clearLayers().addTo(rawMap());
+  for (const area of wd.terrain.areas) {
+    L.polygon(outline(area.shape).map((a) => config.world2map(a)), {
+      color: "saddlebrown",
+      weight: 1,
+      fillOpacity: 0.2,
+    })
+      .bindPopup(
+        `Terrain ${escape(area.name)}<br>Elevation: ${escape(area.elevation.toString())}`,
+      )
+      .addTo(terrainLayer);
+  }
+}
@@ -0,0 +1,42 @@
+import { markRaw } from "vue";
+import type { ActiveCell } from "./bindings/ActiveCell";
+import type { CellShape } from "./bindings/CellShape";
+import socket from "./socket";
+import { escape } from "./util";
+import config from "./config";
+import { rawMap } from "@/map.ts";
+
+const weatherLayer = markRaw(L.layerGroup());
+
+export function outline(shape: CellShape): [number, number][] {
+  if ("Polygon" in shape) return shape.Polygon.points;
+  const {
+    centre: [x, y],
+    radius,
+  } = shape.Circle;
+  // circles are drawn in world coordinates, so that they scale like everything else
+  return Array.from({ length: 32 }, (_, i) => {
+    const angle = (i / 32) * 2 * Math.PI;
+    return [x + radius * Math.cos(angle), y + radius * Math.sin(angle)];
+  });
+}
+
+function drawWeather(cells: ActiveCell[]) {
+  weatherLayer.clearLayers().addTo(rawMap());
+  for (const cell of cells) {
+    L.polygon(outline(cell.shape).map((a) => config.world2map(a)), {
+      color: "purple",
+      weight: 2,
+      fillOpacity: 0.3,
+    })
+      .bindPopup(
+        `Weather ${escape(cell.name)}<br>Altitude: ${escape(cell.floor.toString())} to ${escape(cell.ceiling.toString())}`,
+      )
+      .addTo(weatherLayer);
+  }
+}
+
+export async function handleWeatherUpdates() {
+  drawWeather(await socket.value.timeout(5000).emitWithAck("weather"));
+  socket.value.on("weather", drawWeather);
+}
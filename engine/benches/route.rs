@@ -0,0 +1,80 @@
+use std::{hint::black_box, sync::Arc};
+
+use air_traffic_simulator_engine::{
+    route::WaypointGraph,
+    util::{angle::Angle, pos::Pos2Angle, Pos2, WaypointId},
+    world_data::{AirportData, ModelMotion, Runway, Waypoint, WorldData},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// A `side` by `side` grid of waypoints 1000 apart, each connected to its four neighbours.
+fn grid(side: usize) -> Arc<[Arc<Waypoint>]> {
+    let name = |x: usize, y: usize| WaypointId::from(format!("{x}-{y}"));
+    (0..side * side)
+        .map(|i| {
+            let (x, y) = (i % side, i / side);
+            Arc::new(Waypoint {
+                name: name(x, y),
+                pos: Pos2::new(x as f32, y as f32) * 1000.0,
+                connections: [
+                    (x > 0).then(|| name(x - 1, y)),
+                    (x + 1 < side).then(|| name(x + 1, y)),
+                    (y > 0).then(|| name(x, y - 1)),
+                    (y + 1 < side).then(|| name(x, y + 1)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            })
+        })
+        .collect()
+}
+
+fn route(c: &mut Criterion) {
+    let mut group = c.benchmark_group("route");
+    group.sample_size(20);
+    for side in [100, 200] {
+        let waypoints = grid(side);
+        let n = waypoints.len();
+
+        group.bench_with_input(BenchmarkId::new("build_graph", n), &waypoints, |b, w| {
+            b.iter(|| WaypointGraph::new(black_box(w)));
+        });
+
+        let graph = WaypointGraph::new(&waypoints);
+        group.bench_with_input(BenchmarkId::new("shortest_path", n), &graph, |b, g| {
+            b.iter(|| g.shortest_path(black_box(0), black_box(n - 1)));
+        });
+
+        let world = WorldData {
+            waypoints: Arc::clone(&waypoints),
+            ..WorldData::default()
+        };
+        let far = side as f32 * 1000.0;
+        let airport = AirportData {
+            runways: Arc::new([Arc::new(Runway {
+                start: Pos2::new(far, far),
+                end: Pos2::new(far + 2000.0, far),
+                ..Runway::default()
+            })]),
+            ..AirportData::default()
+        };
+        let motion = ModelMotion {
+            turning_radius: 500.0,
+            ..ModelMotion::default()
+        };
+        group.bench_with_input(BenchmarkId::new("cached_route", n), &world, |b, wd| {
+            b.iter(|| {
+                wd.find_route(
+                    black_box(Pos2Angle(Pos2::ZERO, Angle(0.0))),
+                    &motion,
+                    black_box(&airport),
+                )
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, route);
+criterion_main!(benches);
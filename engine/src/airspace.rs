@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use ts_rs::TS;
+
+use crate::{
+    config::Config,
+    terrain::Terrain,
+    util::{Pos2, Pos3},
+    weather::{ActiveCell, CellShape},
+    world_data::{empty, WorldData},
+};
+
+/// A volume of airspace planes must keep out of while it is active.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct Airspace {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    pub kind: AirspaceKind,
+    /// Outline as seen from above
+    pub shape: CellShape,
+    /// Lowest altitude the volume reaches
+    pub floor: f32,
+    /// Highest altitude the volume reaches
+    pub ceiling: f32,
+    /// Times the volume is active. It is always active if there are none.
+    #[serde(default = "empty")]
+    pub schedule: Arc<[Activation]>,
+    /// Seconds after which the schedule starts over, e.g. 86400 for one that is the same every
+    /// day. The schedule is only followed once if unset.
+    #[serde(default)]
+    pub period: Option<f32>,
+}
+
+/// What the airspace is set aside for. Planes keep out of every kind alike, but clients may show
+/// them differently.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+    Eq,
+)]
+#[ts(export)]
+pub enum AirspaceKind {
+    Prohibited,
+    Military,
+    Temporary,
+}
+
+/// A stretch of time for which an [`Airspace`] is active, in seconds into the simulation, or into
+/// the period if the schedule repeats.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct Activation {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Airspace {
+    /// Whether the volume is active `time` seconds into the simulation.
+    #[must_use]
+    pub fn active(&self, time: f64) -> bool {
+        if self.schedule.is_empty() {
+            return true;
+        }
+        let time = self
+            .period
+            .map_or(time, |period| time.rem_euclid(f64::from(period)));
+        self.schedule
+            .iter()
+            .any(|a| (f64::from(a.start)..f64::from(a.end)).contains(&time))
+    }
+    /// Whether `pos` is inside the volume.
+    #[must_use]
+    pub fn contains(&self, pos: Pos3) -> bool {
+        (self.floor..=self.ceiling).contains(&pos.z) && self.shape.contains(pos.truncate())
+    }
+    /// Whether the straight line from `a` to `b`, flown anywhere between altitudes `low` and
+    /// `high`, passes through the volume.
+    #[must_use]
+    pub fn blocks(&self, a: Pos2, b: Pos2, low: f32, high: f32) -> bool {
+        low <= self.ceiling && high >= self.floor && self.shape.crosses(a, b)
+    }
+}
+
+/// Everything planes route around at some moment: the weather cells that exist then, the
+/// airspace that is active then, and ground too high to climb over.
+#[derive(Clone, Debug)]
+pub struct Obstacles<'a> {
+    pub cells: Vec<ActiveCell>,
+    pub airspace: Vec<&'a Airspace>,
+    pub terrain: &'a Terrain,
+}
+
+impl Obstacles<'_> {
+    /// Whether there is no weather or active airspace. The terrain never changes, so routes are
+    /// only planned around it once.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.cells.is_empty() && self.airspace.is_empty()
+    }
+    /// Whether anything lies across the straight line from `a` to `b`, flown at the cruising
+    /// altitude for it.
+    #[must_use]
+    pub fn blocks(&self, a: Pos2, b: Pos2, config: &Config) -> bool {
+        let altitude = self.terrain.cruising_altitude(a, b, config);
+        self.terrain.blocks(a, b, config)
+            || self.cells.iter().any(|cell| cell.blocks(a, b, altitude))
+            || self
+                .airspace
+                .iter()
+                .any(|airspace| airspace.blocks(a, b, altitude, altitude))
+    }
+}
+
+impl WorldData {
+    /// The airspace that is active at `time`.
+    #[must_use]
+    pub fn airspace_at(&self, time: f64) -> Vec<&Airspace> {
+        self.airspace
+            .iter()
+            .filter(|a| a.active(time))
+            .map(AsRef::as_ref)
+            .collect()
+    }
+    #[must_use]
+    pub fn obstacles_at(&self, time: f64) -> Obstacles<'_> {
+        Obstacles {
+            cells: self.weather_at(time),
+            airspace: self.airspace_at(time),
+            terrain: &self.terrain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule() {
+        let mut airspace = Airspace {
+            name: "R1".into(),
+            kind: AirspaceKind::Military,
+            shape: CellShape::Circle {
+                centre: Pos2::ZERO,
+                radius: 10.0,
+            },
+            floor: 0.0,
+            ceiling: 1000.0,
+            schedule: Arc::new([]),
+            period: None,
+        };
+        assert!(airspace.active(0.0));
+        assert!(airspace.active(1e6));
+
+        airspace.schedule = Arc::new([
+            Activation {
+                start: 100.0,
+                end: 200.0,
+            },
+            Activation {
+                start: 300.0,
+                end: 400.0,
+            },
+        ]);
+        assert!(!airspace.active(50.0));
+        assert!(airspace.active(150.0));
+        assert!(!airspace.active(250.0));
+        assert!(airspace.active(300.0));
+        assert!(!airspace.active(1150.0));
+
+        airspace.period = Some(1000.0);
+        assert!(airspace.active(1150.0));
+        assert!(!airspace.active(1250.0));
+        assert!(airspace.active(2350.0));
+    }
+
+    #[test]
+    fn volume() {
+        let airspace = Airspace {
+            name: "P1".into(),
+            kind: AirspaceKind::Prohibited,
+            shape: CellShape::Circle {
+                centre: Pos2::ZERO,
+                radius: 10.0,
+            },
+            floor: 500.0,
+            ceiling: 1000.0,
+            schedule: Arc::new([]),
+            period: None,
+        };
+        assert!(airspace.contains(Pos2::new(5.0, 0.0).extend(700.0)));
+        assert!(!airspace.contains(Pos2::new(5.0, 0.0).extend(200.0)));
+        assert!(!airspace.contains(Pos2::new(15.0, 0.0).extend(700.0)));
+
+        let (a, b) = (Pos2::new(-20.0, 0.0), Pos2::new(20.0, 0.0));
+        assert!(airspace.blocks(a, b, 700.0, 700.0));
+        assert!(!airspace.blocks(a, b, 200.0, 200.0));
+        // climbing through the volume
+        assert!(airspace.blocks(a, b, 200.0, 1200.0));
+        assert!(!airspace.blocks(a, Pos2::new(-20.0, 20.0), 700.0, 700.0));
+    }
+}
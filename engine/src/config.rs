@@ -5,9 +5,18 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 #[derive(
-    Clone, Debug, Serialize, Deserialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
+    Clone,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
 )]
 #[ts(export)]
+#[serde(default)]
 pub struct Config {
     pub tick_duration: f32,
     pub plane_spawn_chance: f32,
@@ -15,8 +24,70 @@ pub struct Config {
     pub cruising_altitude_plus: f32,
     pub cruising_altitude_minus: f32,
     pub ns_before_ew: bool,
+    /// Seed for every random decision in the engine. A random seed is picked if unset.
+    pub seed: Option<u64>,
+    /// Start of the simulation clock, in seconds since the Unix epoch. Defaults to the current time.
+    pub epoch: Option<u64>,
     #[rkyv(with = rkyv::with::Map<rkyv::with::AsString>)]
     pub save_path: Option<PathBuf>,
+    /// File to append a journal of the session to, for [`crate::engine::Engine::replay`].
+    #[rkyv(with = rkyv::with::Map<rkyv::with::AsString>)]
+    pub journal_path: Option<PathBuf>,
+    /// Number of past states kept for rewinding. Rewinding is disabled if zero.
+    pub history_len: usize,
+    /// Only keep a state every this many ticks.
+    #[ts(type = "number")]
+    pub history_interval: u64,
+    /// Horizontal distance airborne planes must keep from each other
+    pub separation_horizontal: f32,
+    /// Vertical distance airborne planes must keep from each other
+    pub separation_vertical: f32,
+    /// Seconds ahead to predict losses of separation
+    pub conflict_look_ahead: f32,
+    /// Whether planes are ordered to climb or descend out of conflicts
+    pub collision_avoidance: bool,
+    /// Seconds before the closest approach of a conflict that collision avoidance steps in
+    pub advisory_time: f32,
+    /// Seconds a runway is kept free after a plane leaves it
+    pub runway_spacing_time: f32,
+    /// Distance the last plane to use a runway must be from it before the next is cleared onto it
+    pub runway_spacing_distance: f32,
+    /// Altitude of the lowest level of a holding stack. Each level above it is
+    /// [`Config::separation_vertical`] higher.
+    pub holding_altitude: f32,
+    /// Distance before the runway from which a landing plane must be low and slow enough to make
+    /// the touchdown point, or go around
+    pub stable_approach_distance: f32,
+    /// Fastest a plane may fly within [`Config::stable_approach_distance`], as a fraction of its
+    /// top speed
+    pub stable_approach_speed: f32,
+    /// Highest a plane may be within [`Config::stable_approach_distance`], as height above the
+    /// runway per distance left to the touchdown point
+    pub stable_approach_slope: f32,
+    /// Height above the runway to climb to when going around, for runways without a missed
+    /// approach procedure
+    pub missed_approach_height: f32,
+    /// Speed planes taxi at
+    pub taxi_speed: f32,
+    /// Radius of the turns taxiing planes make at taxiway corners
+    pub taxi_turning_radius: f32,
+    /// Distance a taxiing plane waits behind the plane ahead of it
+    pub taxi_separation: f32,
+    /// Seconds between departures from a runway behind a plane of a larger class
+    pub departure_wake_spacing: f32,
+    /// Seconds between departures from a runway to the same airport
+    pub departure_route_spacing: f32,
+    /// Seconds a plane stays parked at its gate before leaving as its next flight
+    pub turnaround_time: f32,
+    /// Headwind a runway may have less of than the one most into the wind and still be used
+    pub runway_headwind_margin: f32,
+    /// Height above the ground planes cruise and descend no lower than
+    pub terrain_clearance: f32,
+    /// Highest altitude planes climb to over high ground. Ground needing more than this is flown
+    /// around.
+    pub max_altitude: f32,
+    /// Height above the ground within which airborne planes are warned of it
+    pub terrain_warning_height: f32,
 }
 
 impl Default for Config {
@@ -28,7 +99,34 @@ impl Default for Config {
             cruising_altitude_plus: 1024.0,
             cruising_altitude_minus: 512.0,
             ns_before_ew: false,
+            seed: None,
+            epoch: None,
             save_path: None,
+            journal_path: None,
+            history_len: 0,
+            history_interval: 1,
+            separation_horizontal: 50.0,
+            separation_vertical: 100.0,
+            conflict_look_ahead: 180.0,
+            collision_avoidance: true,
+            advisory_time: 40.0,
+            runway_spacing_time: 30.0,
+            runway_spacing_distance: 100.0,
+            holding_altitude: 256.0,
+            stable_approach_distance: 100.0,
+            stable_approach_speed: 0.8,
+            stable_approach_slope: 2.0,
+            missed_approach_height: 100.0,
+            taxi_speed: 2.0,
+            taxi_turning_radius: 5.0,
+            taxi_separation: 10.0,
+            departure_wake_spacing: 120.0,
+            departure_route_spacing: 60.0,
+            turnaround_time: 300.0,
+            runway_headwind_margin: 0.5,
+            terrain_clearance: 100.0,
+            max_altitude: 4096.0,
+            terrain_warning_height: 50.0,
         }
     }
 }
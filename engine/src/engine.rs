@@ -1,19 +1,47 @@
 use bytes::Bytes;
+use eyre::{eyre, Result};
 use tracing::{error, info, warn};
 
-use crate::{config::Config, state::State, util::PlaneStateId, world_data::WorldData};
+use crate::{
+    config::Config,
+    history::History,
+    journal::{Command, JournalEntry, JournalWriter, Record},
+    save,
+    state::{clock::Clock, reconcile::Reconciliation, State},
+    util::PlaneStateId,
+    world_data::WorldData,
+    world_diff::WorldDiff,
+};
 
 #[derive(Clone, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
 pub struct Engine {
     pub world: WorldData,
     pub config: Config,
     pub state: State,
+    #[rkyv(with = rkyv::with::Skip)]
+    pub journal: Option<JournalWriter>,
+    #[rkyv(with = rkyv::with::Skip)]
+    pub history: History,
 }
 
 impl Engine {
-    #[must_use]
-    pub fn new(world: WorldData, config: Config) -> Self {
-        if let Some(old_engine) = config
+    /// Starts an engine, or resumes the one at [`Config::save_path`]. Fails if `world` does not
+    /// pass [`WorldData::validate`].
+    pub fn new(world: WorldData, config: Config) -> Result<Self> {
+        let mut engine = Self::new_unjournaled(world, config)?;
+        engine.journal = open_journal(&engine.config);
+        engine.state.journal.push(JournalEntry {
+            tick: engine.state.clock.tick,
+            record: Record::Start {
+                config: engine.config.clone(),
+            },
+        });
+        engine.flush_journal();
+        Ok(engine)
+    }
+    fn new_unjournaled(world: WorldData, config: Config) -> Result<Self> {
+        world.validate()?;
+        if let Some((state, reconciliation)) = config
             .save_path
             .as_ref()
             .and_then(|p| {
@@ -22,33 +50,345 @@ impl Engine {
                     .ok()
             })
             .and_then(|b| {
-                rkyv::from_bytes::<Self, rkyv::rancor::Error>(&b)
+                save::from_bytes(&b, &world, &config)
                     .inspect_err(|e| error!(in_ = "load", "{e:#}"))
                     .ok()
             })
-            .and_then(|ng| {
-                if ng.world == world {
-                    Some(ng)
-                } else {
-                    warn!(in_ = "load", "ignoring old state due to new world data");
-                    None
-                }
-            })
         {
-            info!(path=?config.save_path.as_ref().unwrap(), "Loading old engine");
-            Self {
-                config,
-                ..old_engine
+            info!(path=?config.save_path.as_ref().unwrap(), kept = reconciliation.kept.len(), "Loading old engine");
+            for (id, reason) in &reconciliation.dropped {
+                warn!(in_ = "load", %id, %reason, "Dropped plane from save");
             }
-        } else {
-            Self {
-                state: State::new(&world.airports),
+            Ok(Self {
                 world,
                 config,
+                state,
+                journal: None,
+                history: History::default(),
+            })
+        } else {
+            let seed = config.seed.unwrap_or_else(rand::random);
+            let clock = Clock::new(config.epoch);
+            info!(seed, "Starting new engine");
+            Ok(Self {
+                state: State::new(&world.airports, seed, clock),
+                world,
+                config: Config {
+                    seed: Some(seed),
+                    epoch: Some(clock.epoch),
+                    ..config
+                },
+                journal: None,
+                history: History::default(),
+            })
+        }
+    }
+    /// Re-runs a session from its initial world and journal, failing if it produces different
+    /// spawns or clearances than were recorded.
+    pub fn replay(world: WorldData, journal: &[JournalEntry]) -> Result<Self> {
+        let Some((
+            JournalEntry {
+                tick: 0,
+                record: Record::Start { config },
+            },
+            entries,
+        )) = journal.split_first()
+        else {
+            return Err(eyre!("Journal does not start from a new engine"));
+        };
+        let mut engine = Self::new_unjournaled(
+            world,
+            Config {
+                save_path: None,
+                journal_path: None,
+                ..config.clone()
+            },
+        )?;
+        // Commands of a tick are journaled before its outputs, and a rewind moves the clock back,
+        // so entries are followed in order rather than grouped by tick
+        let mut expected = vec![];
+        for entry in entries {
+            while engine.state.clock.tick < entry.tick {
+                engine.replay_tick(&mut expected)?;
+            }
+            if engine.state.clock.tick > entry.tick {
+                return Err(eyre!(
+                    "Journal entry for tick {} found at tick {}",
+                    entry.tick,
+                    engine.state.clock.tick
+                ));
+            }
+            match &entry.record {
+                // the replay must not write to the files of the session it replays
+                Record::Command(Command::SetConfig(config)) => engine.set_config(Config {
+                    save_path: None,
+                    journal_path: None,
+                    ..config.clone()
+                }),
+                Record::Command(command) => engine.apply(command.clone())?,
+                Record::Start { .. } => {
+                    return Err(eyre!("Unexpected start record at tick {}", entry.tick))
+                }
+                record => expected.push(record.clone()),
+            }
+        }
+        // the journal is only written after a tick, so the tick of the last entry always ran
+        if !entries.is_empty() {
+            engine.replay_tick(&mut expected)?;
+        }
+        Ok(engine)
+    }
+    fn replay_tick(&mut self, expected: &mut Vec<Record>) -> Result<()> {
+        let tick = self.state.clock.tick;
+        self.history.record(&self.state, &self.config);
+        self.state.tick(&self.config, &self.world);
+        let actual = std::mem::take(&mut self.state.journal)
+            .into_iter()
+            .map(|a| a.record)
+            .filter(Record::is_output)
+            .collect::<Vec<_>>();
+        let expected = std::mem::take(expected);
+        if actual != expected {
+            return Err(eyre!(
+                "Replay diverged at tick {tick}: expected {expected:?}, got {actual:?}"
+            ));
+        }
+        Ok(())
+    }
+    pub fn apply(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::ReloadWorld(world) => {
+                self.reload_world(world)?;
             }
+            Command::SetConfig(config) => self.set_config(config),
+            Command::Rewind(tick) => {
+                self.rewind(tick)?;
+            }
+        }
+        Ok(())
+    }
+    /// Swaps in `config`. A changed [`Config::journal_path`] starts a new journal there, after the
+    /// change itself is written to the old one.
+    pub fn set_config(&mut self, config: Config) {
+        self.record(Command::SetConfig(config.clone()));
+        if config.journal_path != self.config.journal_path {
+            self.flush_journal();
+            self.journal = open_journal(&config);
+        }
+        self.config = config;
+    }
+    fn record(&mut self, command: Command) {
+        self.state.journal.push(JournalEntry {
+            tick: self.state.clock.tick,
+            record: Record::Command(command),
+        });
+    }
+    fn flush_journal(&mut self) -> Vec<JournalEntry> {
+        let entries = std::mem::take(&mut self.state.journal);
+        if let Some(journal) = &self.journal {
+            let _ = journal
+                .write(&entries)
+                .inspect_err(|e| error!(in_ = "journal", "{e:#}"));
         }
+        entries
+    }
+    /// Swaps in a new world while keeping every plane that can still fly in it. The current world
+    /// is kept if the new one does not pass [`WorldData::validate`].
+    pub fn reload_world(&mut self, world: WorldData) -> Result<(WorldDiff, Reconciliation)> {
+        world.validate()?;
+        self.record(Command::ReloadWorld(world.clone()));
+        let diff = WorldDiff::new(&self.world, &world);
+        let reconciliation = self.state.reconcile(&world);
+        info!(
+            kept = reconciliation.kept.len(),
+            replanned = reconciliation.replanned.len(),
+            dropped = reconciliation.dropped.len(),
+            "Reloaded world"
+        );
+        self.world = world;
+        Ok((diff, reconciliation))
+    }
+    /// Restores the latest kept state at or before `tick` and resumes from there. Returns the
+    /// planes that no longer exist.
+    pub fn rewind(&mut self, tick: u64) -> Result<Vec<PlaneStateId>> {
+        let Some(mut state) = self.history.rewind(tick) else {
+            return Err(eyre!("No state kept at or before tick {tick}"));
+        };
+        self.record(Command::Rewind(tick));
+        state.journal = std::mem::take(&mut self.state.journal);
+        // the world may have been reloaded since the snapshot was taken
+        let reconciliation = state.reconcile(&self.world);
+        for (id, reason) in &reconciliation.dropped {
+            warn!(in_ = "rewind", %id, %reason, "Dropped plane from snapshot");
+        }
+        let removed = self
+            .state
+            .planes
+            .iter()
+            .map(|a| a.id)
+            .filter(|id| state.plane(id).is_none())
+            .collect();
+        info!(
+            from = self.state.clock.tick,
+            to = state.clock.tick,
+            "Rewound"
+        );
+        self.state = state;
+        Ok(removed)
     }
     pub fn tick(&mut self) -> (Vec<PlaneStateId>, Bytes) {
-        self.state.tick(&self.config, &self.world)
+        let (removed, state, _) = self.tick_journaled();
+        (removed, state)
+    }
+    /// [`Self::tick`], also returning what the tick wrote to the journal.
+    pub fn tick_journaled(&mut self) -> (Vec<PlaneStateId>, Bytes, Vec<JournalEntry>) {
+        self.history.record(&self.state, &self.config);
+        let (removed, state) = self.state.tick(&self.config, &self.world);
+        let entries = self.flush_journal();
+        (removed, state, entries)
+    }
+}
+
+fn open_journal(config: &Config) -> Option<JournalWriter> {
+    config.journal_path.as_ref().and_then(|p| {
+        JournalWriter::open(p)
+            .inspect_err(|e| error!(in_ = "journal", "{e:#}"))
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        fixtures,
+        util::{Pos2, WaypointId},
+        world_data::{Flight, Waypoint},
+    };
+
+    fn waypoint(name: &str, x: f32, connections: &[&str]) -> Arc<Waypoint> {
+        fixtures::waypoint(name, Pos2::new(x, 1000.0), connections)
+    }
+
+    #[test]
+    fn reload_world() {
+        let world = WorldData {
+            airports: Arc::new([
+                fixtures::airport("FOO", 0.0),
+                fixtures::airport("BAR", 5000.0),
+            ]),
+            flights: Some(Arc::new([Arc::new(Flight {
+                from: "FOO".into(),
+                to: "BAR".into(),
+                plane: Arc::new(["A320".into()]),
+                ..Flight::default()
+            })])),
+            waypoints: Arc::new([
+                waypoint("A", 0.0, &["B"]),
+                waypoint("B", 2500.0, &["A", "C"]),
+                waypoint("C", 5000.0, &["B"]),
+            ]),
+            ..fixtures::world()
+        };
+        let mut engine = Engine::new(
+            world.clone(),
+            Config {
+                plane_spawn_chance: 1.0,
+                max_planes: Some(1),
+                seed: Some(0),
+                ..Config::default()
+            },
+        )
+        .unwrap();
+        engine.tick();
+        let plane = engine.state.planes[0].id;
+
+        let mut new_world = world;
+        new_world.waypoints = Arc::new([waypoint("A", 0.0, &[]), waypoint("C", 5000.0, &[])]);
+        let (diff, reconciliation) = engine.reload_world(new_world).unwrap();
+
+        assert_eq!(diff.waypoints.removed, [WaypointId::from("B")]);
+        assert_eq!(diff.waypoints.changed.len(), 2);
+        assert!(diff.airports.is_empty());
+        assert_eq!(reconciliation.kept, [plane]);
+        assert_eq!(reconciliation.replanned, [plane]);
+        assert!(engine.state.planes[0]
+            .pos
+            .planner
+            .route
+            .iter()
+            .all(|a| a.name != "B"));
+    }
+
+    #[test]
+    fn rewind() {
+        let mut engine = Engine::new(
+            fixtures::world(),
+            Config {
+                plane_spawn_chance: 0.3,
+                seed: Some(0),
+                history_len: 10,
+                history_interval: 5,
+                ..Config::default()
+            },
+        )
+        .unwrap();
+        let mut states = vec![];
+        for _ in 0..60 {
+            states.push(engine.state.coord_state());
+            engine.tick();
+        }
+        assert_eq!(
+            engine.history.ticks().collect::<Vec<_>>(),
+            (10..60).step_by(5).collect::<Vec<_>>()
+        );
+        let _ = engine.rewind(3).unwrap_err();
+
+        engine.rewind(22).unwrap();
+        assert_eq!(engine.state.clock.tick, 20);
+        assert_eq!(engine.state.coord_state(), states[20]);
+        assert_eq!(engine.history.ticks().last(), Some(15));
+        for state in &states[20..] {
+            assert_eq!(engine.state.coord_state(), *state);
+            engine.tick();
+        }
+    }
+
+    #[test]
+    fn rewind_keeps_departure_spacing() {
+        let mut spaced = false;
+        for seed in 0..20 {
+            let mut engine = Engine::new(
+                fixtures::world(),
+                Config {
+                    plane_spawn_chance: 0.5,
+                    seed: Some(seed),
+                    history_len: 10,
+                    history_interval: 50,
+                    ..Config::default()
+                },
+            )
+            .unwrap();
+            let mut states = vec![];
+            for _ in 0..200 {
+                states.push(engine.state.coord_state());
+                engine.tick();
+            }
+
+            engine.rewind(100).unwrap();
+            spaced |= engine
+                .state
+                .airports
+                .iter()
+                .flat_map(|a| &a.runways)
+                .any(|a| a.last_departure.is_some());
+            for state in &states[100..] {
+                assert_eq!(engine.state.coord_state(), *state, "seed {seed}");
+                engine.tick();
+            }
+        }
+        assert!(spaced);
     }
 }
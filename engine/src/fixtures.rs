@@ -0,0 +1,59 @@
+//! Worlds shared by the tests of the engine.
+
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::{
+    util::{Pos2, WaypointId},
+    world_data::{AirportData, ModelMotion, PlaneData, Runway, Waypoint, WorldData},
+};
+
+/// An airport with a single 50 m runway "18/36" running north from `x`.
+pub fn airport(code: &str, x: f32) -> Arc<AirportData> {
+    Arc::new(AirportData {
+        code: code.into(),
+        runways: Arc::new([Arc::new(Runway {
+            name: "18/36".into(),
+            start: Pos2::new(x, 0.0),
+            end: Pos2::new(x, 50.0),
+            ..Runway::default()
+        })]),
+        ..AirportData::default()
+    })
+}
+
+pub fn a320() -> Arc<PlaneData> {
+    Arc::new(PlaneData {
+        id: "A320".into(),
+        motion: ModelMotion {
+            max_a: Vec2::new(5.0, 2.5),
+            max_v: Vec2::new(50.0, 10.0),
+            turning_radius: 50.0,
+        },
+        ..PlaneData::default()
+    })
+}
+
+pub fn waypoint(name: &str, pos: Pos2, connections: &[&str]) -> Arc<Waypoint> {
+    Arc::new(Waypoint {
+        name: WaypointId::from(name),
+        pos,
+        connections: connections.iter().map(|a| WaypointId::from(*a)).collect(),
+    })
+}
+
+/// FOO and BAR 1 km apart, with an airway of two waypoints between them, flown by the A320.
+pub fn world() -> WorldData {
+    WorldData {
+        classes: Arc::new([]),
+        airports: Arc::new([airport("FOO", 0.0), airport("BAR", 1000.0)]),
+        flights: None,
+        planes: Arc::new([a320()]),
+        waypoints: Arc::new([
+            waypoint("A", Pos2::new(300.0, 400.0), &["B"]),
+            waypoint("B", Pos2::new(700.0, 400.0), &["A"]),
+        ]),
+        ..WorldData::default()
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+
+use crate::{config::Config, state::State};
+
+/// Ring buffer of past states that the engine can be rewound to.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    snapshots: VecDeque<State>,
+}
+
+impl History {
+    /// Keeps a copy of `state` if its tick falls on [`Config::history_interval`], dropping the
+    /// oldest snapshot past [`Config::history_len`].
+    pub fn record(&mut self, state: &State, config: &Config) {
+        if config.history_len == 0
+            || !state
+                .clock
+                .tick
+                .is_multiple_of(config.history_interval.max(1))
+        {
+            return;
+        }
+        let mut snapshot = state.clone();
+        snapshot.journal.clear();
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > config.history_len {
+            self.snapshots.pop_front();
+        }
+    }
+    pub fn ticks(&self) -> impl Iterator<Item = u64> + '_ {
+        self.snapshots.iter().map(|a| a.clock.tick)
+    }
+    /// Takes the latest snapshot at or before `tick`, discarding it and every snapshot after it.
+    /// It is recorded again once the engine resumes from it.
+    pub fn rewind(&mut self, tick: u64) -> Option<State> {
+        let i = self.snapshots.iter().rposition(|a| a.clock.tick <= tick)?;
+        self.snapshots.truncate(i + 1);
+        self.snapshots.pop_back()
+    }
+}
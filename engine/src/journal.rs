@@ -0,0 +1,288 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use crate::{
+    config::Config,
+    state::plane::{GoAroundReason, Sense},
+    util::{AirportCode, AirportStateId, FlightCode, PlaneModelId, PlaneStateId},
+    world_data::WorldData,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub tick: u64,
+    pub record: Record,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Record {
+    /// Written once when the engine starts, with the seed and epoch already resolved.
+    Start {
+        config: Config,
+    },
+    Spawn {
+        plane: PlaneStateId,
+        flight: FlightCode,
+        from: AirportCode,
+        to: AirportCode,
+        model: PlaneModelId,
+        runway: SmolStr,
+    },
+    Clearance {
+        airport: AirportStateId,
+        plane: PlaneStateId,
+        runway: SmolStr,
+    },
+    /// `airport` cleared `plane` to take off from `runway`, `delay` seconds after it asked to.
+    TakeoffClearance {
+        airport: AirportStateId,
+        plane: PlaneStateId,
+        runway: SmolStr,
+        delay: f64,
+    },
+    /// `airport` sent `plane` to hold at `fix`, or down its stack to `altitude`.
+    Hold {
+        airport: AirportStateId,
+        plane: PlaneStateId,
+        fix: SmolStr,
+        altitude: f32,
+    },
+    /// `plane` abandoned its landing on `runway` at `airport`.
+    GoAround {
+        airport: AirportStateId,
+        plane: PlaneStateId,
+        runway: SmolStr,
+        reason: GoAroundReason,
+    },
+    /// `airport` has no runway `plane` can land on, and sent it on to `to`.
+    Divert {
+        airport: AirportStateId,
+        plane: PlaneStateId,
+        to: AirportCode,
+    },
+    /// `plane` landed on `runway` at `airport`, and was either removed or sent to a gate.
+    Landed {
+        airport: AirportCode,
+        plane: PlaneStateId,
+        runway: SmolStr,
+    },
+    /// Collision avoidance ordered `plane` to climb or descend away from `against`.
+    Advisory {
+        plane: PlaneStateId,
+        against: PlaneStateId,
+        sense: Sense,
+    },
+    /// `plane` is clear of conflict and resumes its route.
+    ClearOfConflict {
+        plane: PlaneStateId,
+    },
+    /// `plane`, parked at `from`, left its gate as its next flight.
+    Turnaround {
+        plane: PlaneStateId,
+        flight: FlightCode,
+        from: AirportCode,
+        to: AirportCode,
+        runway: SmolStr,
+    },
+    /// `plane` was re-routed around weather or active airspace lying across its route.
+    Reroute {
+        plane: PlaneStateId,
+    },
+    /// `plane` entered `airspace` while it was active.
+    Infringement {
+        plane: PlaneStateId,
+        airspace: SmolStr,
+    },
+    /// `plane` came closer to the ground than [`crate::config::Config::terrain_warning_height`].
+    TerrainWarning {
+        plane: PlaneStateId,
+    },
+    Command(Command),
+}
+
+/// Anything done to a running engine from outside of [`crate::engine::Engine::tick`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Command {
+    ReloadWorld(WorldData),
+    SetConfig(Config),
+    Rewind(u64),
+}
+
+impl Record {
+    /// Whether this record is produced by the simulation itself, as opposed to being fed into it.
+    #[must_use]
+    pub const fn is_output(&self) -> bool {
+        matches!(
+            self,
+            Self::Spawn { .. }
+                | Self::Clearance { .. }
+                | Self::TakeoffClearance { .. }
+                | Self::Hold { .. }
+                | Self::GoAround { .. }
+                | Self::Divert { .. }
+                | Self::Landed { .. }
+                | Self::Advisory { .. }
+                | Self::ClearOfConflict { .. }
+                | Self::Turnaround { .. }
+                | Self::Reroute { .. }
+                | Self::Infringement { .. }
+                | Self::TerrainWarning { .. }
+        )
+    }
+}
+
+/// Append-only JSON lines writer. Clones share the same file.
+#[derive(Clone, Debug)]
+pub struct JournalWriter(Arc<Mutex<BufWriter<File>>>);
+
+impl JournalWriter {
+    /// Starts a new journal at `path`. A journal already there is kept as the first free of
+    /// `path.1`, `path.2`, … so that each file holds a single session.
+    pub fn open(path: &Path) -> Result<Self> {
+        if std::fs::metadata(path).is_ok_and(|a| a.len() > 0) {
+            let mut name = path.as_os_str().to_owned();
+            name.push(".");
+            let rotated = (1..=u32::MAX)
+                .map(|i| {
+                    let mut name = name.clone();
+                    name.push(i.to_string());
+                    PathBuf::from(name)
+                })
+                .find(|a| !a.exists())
+                .ok_or_else(|| eyre::eyre!("No free name to keep the old journal at"))?;
+            std::fs::rename(path, rotated)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self(Arc::new(Mutex::new(BufWriter::new(file)))))
+    }
+    pub fn write(&self, entries: &[JournalEntry]) -> Result<()> {
+        let mut file = self
+            .0
+            .lock()
+            .map_err(|_| eyre::eyre!("Journal writer poisoned"))?;
+        for entry in entries {
+            serde_json::to_writer(&mut *file, entry)?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()?;
+        drop(file);
+        Ok(())
+    }
+}
+
+pub fn read(path: &Path) -> Result<Vec<JournalEntry>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|a| a.as_ref().is_ok_and(|a| !a.trim().is_empty()))
+        .map(|a| Ok(serde_json::from_str(&a?)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{engine::Engine, fixtures::world, save};
+
+    #[test]
+    fn replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let mut engine = Engine::new(
+            world(),
+            Config {
+                plane_spawn_chance: 0.3,
+                journal_path: Some(path.clone()),
+                history_len: 50,
+                ..Config::default()
+            },
+        )
+        .unwrap();
+        for i in 0..300 {
+            if i == 100 {
+                engine.set_config(Config {
+                    plane_spawn_chance: 0.6,
+                    ..engine.config.clone()
+                });
+            }
+            if i == 200 {
+                engine.rewind(170).unwrap();
+            }
+            engine.tick();
+        }
+
+        let journal = read(&path).unwrap();
+        assert!(journal
+            .iter()
+            .any(|a| matches!(a.record, Record::Clearance { .. })));
+        let mut replayed = Engine::replay(world(), &journal).unwrap();
+        // ticks without any output leave nothing in the journal
+        while replayed.state.clock.tick < engine.state.clock.tick {
+            replayed.tick();
+        }
+        assert_eq!(replayed.state.coord_state(), engine.state.coord_state());
+    }
+    #[test]
+    fn journal_per_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let config = Config {
+            plane_spawn_chance: 0.3,
+            save_path: Some(dir.path().join("save.rkyv")),
+            journal_path: Some(path.clone()),
+            ..Config::default()
+        };
+        let mut engine = Engine::new(world(), config.clone()).unwrap();
+        for _ in 0..50 {
+            engine.tick();
+        }
+        std::fs::write(
+            dir.path().join("save.rkyv"),
+            save::to_bytes(&engine).unwrap(),
+        )
+        .unwrap();
+        for _ in 0..20 {
+            engine.tick();
+        }
+
+        let mut resumed = Engine::new(world(), config).unwrap();
+        assert_eq!(resumed.state.clock.tick, 50);
+        for _ in 0..10 {
+            resumed.tick();
+        }
+        let journal = read(&path).unwrap();
+        assert_eq!(journal[0].tick, 50);
+        assert!(journal[1..]
+            .iter()
+            .all(|a| !matches!(a.record, Record::Start { .. })));
+
+        // the session before is kept whole and still replays
+        let journal = read(&dir.path().join("journal.jsonl.1")).unwrap();
+        let mut replayed = Engine::replay(world(), &journal).unwrap();
+        while replayed.state.clock.tick < engine.state.clock.tick {
+            replayed.tick();
+        }
+        assert_eq!(replayed.state.coord_state(), engine.state.coord_state());
+
+        let moved = dir.path().join("moved.jsonl");
+        resumed.set_config(Config {
+            journal_path: Some(moved.clone()),
+            ..resumed.config.clone()
+        });
+        for _ in 0..100 {
+            resumed.tick();
+        }
+        assert!(matches!(
+            read(&path).unwrap().last().unwrap().record,
+            Record::Command(Command::SetConfig(_))
+        ));
+        assert!(!read(&moved).unwrap().is_empty());
+    }
+}
@@ -1,5 +1,18 @@
+pub mod airspace;
 pub mod config;
 pub mod engine;
+#[cfg(test)]
+mod fixtures;
+pub mod history;
+pub mod journal;
+pub mod procedure;
+pub mod route;
+pub mod save;
 pub mod state;
+pub mod terrain;
 pub mod util;
+pub mod validation;
+pub mod weather;
+pub mod wind;
 pub mod world_data;
+pub mod world_diff;
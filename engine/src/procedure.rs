@@ -0,0 +1,223 @@
+use std::{iter::once, sync::Arc};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use ts_rs::TS;
+
+use crate::{
+    util::{Pos2, WaypointId},
+    world_data::{empty, AirportData, Runway, Waypoint},
+};
+
+/// A standard instrument departure (SID) or standard terminal arrival route (STAR): a fixed path
+/// between the runways it serves and a waypoint of the airways.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct Procedure {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    /// Names of the runways the procedure is flown from or to
+    #[ts(as = "Vec<String>")]
+    pub runways: Arc<[SmolStr]>,
+    /// Waypoint a departure joins the airways at, or an arrival leaves them at
+    #[ts(as = "String")]
+    pub waypoint: WaypointId,
+    /// Points flown through between the runway and the waypoint, in the order they are flown
+    #[serde(default = "empty")]
+    pub fixes: Arc<[ProcedureFix]>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct ProcedureFix {
+    #[ts(as = "String")]
+    pub name: WaypointId,
+    #[ts(as = "(f32, f32)")]
+    pub pos: Pos2,
+    /// Lowest altitude to fly towards the fix at
+    #[serde(default)]
+    pub min_altitude: Option<f32>,
+    /// Highest altitude to fly towards the fix at
+    #[serde(default)]
+    pub max_altitude: Option<f32>,
+    /// Fastest to fly towards the fix at
+    #[serde(default)]
+    pub max_speed: Option<f32>,
+}
+
+impl Procedure {
+    /// Whether the procedure is flown from or to `runway`.
+    #[must_use]
+    pub fn serves(&self, runway: &Runway) -> bool {
+        self.runways.contains(&runway.name)
+    }
+    #[must_use]
+    pub fn fix(&self, name: &WaypointId) -> Option<&ProcedureFix> {
+        self.fixes.iter().find(|a| a.name == *name)
+    }
+    /// The fixes as waypoints to add to a route. They are not joined to anything.
+    pub fn waypoints(&self) -> impl Iterator<Item = Arc<Waypoint>> + '_ {
+        self.fixes.iter().map(|a| {
+            Arc::new(Waypoint {
+                name: a.name.clone(),
+                pos: a.pos,
+                connections: empty(),
+            })
+        })
+    }
+    /// Points flown through from `start`, through the fixes, to `end`.
+    pub fn path(&self, start: Pos2, end: Pos2) -> impl Iterator<Item = Pos2> + '_ {
+        once(start)
+            .chain(self.fixes.iter().map(|a| a.pos))
+            .chain(once(end))
+    }
+    /// Whether none of the straight lines of [`Self::path`] are ones for which `blocked` holds.
+    pub fn flyable<F: Fn(Pos2, Pos2) -> bool>(&self, start: Pos2, end: Pos2, blocked: &F) -> bool {
+        !self
+            .path(start, end)
+            .tuple_windows()
+            .any(|(a, b)| blocked(a, b))
+    }
+    /// Length of [`Self::path`].
+    #[must_use]
+    pub fn length(&self, start: Pos2, end: Pos2) -> f32 {
+        self.path(start, end)
+            .tuple_windows()
+            .map(|(a, b)| a.distance(b))
+            .sum()
+    }
+}
+
+impl ProcedureFix {
+    /// `altitude` raised or lowered to within the altitude constraints of the fix.
+    #[must_use]
+    pub fn constrain_altitude(&self, altitude: f32) -> f32 {
+        let altitude = self.max_altitude.map_or(altitude, |a| altitude.min(a));
+        self.min_altitude.map_or(altitude, |a| altitude.max(a))
+    }
+}
+
+impl AirportData {
+    /// SIDs flown from `runway`.
+    pub fn sids_from<'a>(&'a self, runway: &'a Runway) -> impl Iterator<Item = &'a Arc<Procedure>> {
+        self.sids.iter().filter(|a| a.serves(runway))
+    }
+    /// The STAR from `waypoint` to the first of `runways` one serves.
+    #[must_use]
+    pub fn star(&self, waypoint: &WaypointId, runways: &[&Arc<Runway>]) -> Option<&Arc<Procedure>> {
+        runways.iter().find_map(|runway| {
+            self.stars
+                .iter()
+                .find(|a| a.waypoint == *waypoint && a.serves(runway))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assertables::*;
+
+    use super::*;
+
+    fn procedure(name: &str, runways: &[&str], waypoint: &str) -> Arc<Procedure> {
+        Arc::new(Procedure {
+            name: name.into(),
+            runways: runways.iter().map(|a| (*a).into()).collect(),
+            waypoint: waypoint.into(),
+            fixes: Arc::new([
+                ProcedureFix {
+                    name: format!("{name}1").into(),
+                    pos: Pos2::new(0.0, 100.0),
+                    ..ProcedureFix::default()
+                },
+                ProcedureFix {
+                    name: format!("{name}2").into(),
+                    pos: Pos2::new(100.0, 100.0),
+                    max_altitude: Some(500.0),
+                    min_altitude: Some(300.0),
+                    ..ProcedureFix::default()
+                },
+            ]),
+        })
+    }
+
+    #[test]
+    fn pick() {
+        let runway = |name: &str| {
+            Arc::new(Runway {
+                name: name.into(),
+                ..Runway::default()
+            })
+        };
+        let (north, south) = (runway("36"), runway("18"));
+        let airport = AirportData {
+            sids: Arc::new([procedure("N1", &["36"], "A"), procedure("S1", &["18"], "A")]),
+            stars: Arc::new([
+                procedure("A1", &["18", "36"], "A"),
+                procedure("B1", &["36"], "B"),
+            ]),
+            ..AirportData::default()
+        };
+        let names = |a: Vec<&Arc<Procedure>>| {
+            a.iter()
+                .map(|a| a.name.as_str().to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(airport.sids_from(&south).collect()), ["S1"]);
+        assert_eq!(
+            names(
+                airport
+                    .star(&"B".into(), &[&south, &north])
+                    .into_iter()
+                    .collect()
+            ),
+            ["B1"]
+        );
+        assert_eq!(airport.star(&"B".into(), &[&south]), None);
+        assert_eq!(airport.star(&"C".into(), &[&north]), None);
+    }
+
+    #[test]
+    fn constraints() {
+        let procedure = procedure("N1", &["36"], "A");
+        assert_in_delta!(
+            procedure.length(Pos2::ZERO, Pos2::new(100.0, 0.0)),
+            300.0,
+            1e-3
+        );
+        let fix = procedure.fix(&"N12".into()).unwrap();
+        assert_in_delta!(fix.constrain_altitude(1000.0), 500.0, 1e-3);
+        assert_in_delta!(fix.constrain_altitude(100.0), 300.0, 1e-3);
+        assert_in_delta!(fix.constrain_altitude(400.0), 400.0, 1e-3);
+        assert_in_delta!(procedure.fixes[0].constrain_altitude(1000.0), 1000.0, 1e-3);
+        assert_eq!(
+            procedure
+                .waypoints()
+                .map(|a| a.name.as_str().to_owned())
+                .collect::<Vec<_>>(),
+            ["N11", "N12"]
+        );
+    }
+}
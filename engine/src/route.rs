@@ -0,0 +1,239 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use itertools::Itertools;
+
+use crate::{
+    util::{Pos2, WaypointId},
+    world_data::Waypoint,
+};
+
+/// The waypoint network as adjacency lists over positions in [`crate::world_data::WorldData::waypoints`].
+#[derive(Clone, Debug, Default)]
+pub struct WaypointGraph {
+    pos: Vec<Pos2>,
+    neighbours: Vec<Vec<(usize, f32)>>,
+}
+
+impl WaypointGraph {
+    #[must_use]
+    pub fn new(waypoints: &[Arc<Waypoint>]) -> Self {
+        let ids = waypoints
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, a)| (&a.name, i))
+            .collect::<HashMap<&WaypointId, usize>>();
+        Self {
+            pos: waypoints.iter().map(|a| a.pos).collect(),
+            neighbours: waypoints
+                .iter()
+                .map(|a| {
+                    a.connections
+                        .iter()
+                        .filter_map(|c| ids.get(c))
+                        .map(|&j| (j, a.pos.distance(waypoints[j].pos)))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.pos.len()
+    }
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.pos.is_empty()
+    }
+    #[must_use]
+    pub fn nearest(&self, pos: Pos2) -> Option<usize> {
+        self.pos
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+            .map(|(i, _)| i)
+    }
+    /// Up to `k` waypoints nearest to `pos`, nearest first.
+    #[must_use]
+    pub fn nearest_k(&self, pos: Pos2, k: usize) -> Vec<usize> {
+        let mut nearest = (0..self.len()).collect::<Vec<_>>();
+        let by_distance = |a: &usize, b: &usize| {
+            self.pos[*a]
+                .distance_squared(pos)
+                .total_cmp(&self.pos[*b].distance_squared(pos))
+        };
+        if k < nearest.len() {
+            nearest.select_nth_unstable_by(k, by_distance);
+            nearest.truncate(k);
+        }
+        nearest.sort_by(by_distance);
+        nearest
+    }
+    #[must_use]
+    pub fn length(&self, path: &[usize]) -> f32 {
+        path.iter()
+            .tuple_windows()
+            .map(|(a, b)| self.pos[*a].distance(self.pos[*b]))
+            .sum()
+    }
+    /// Connections, as pairs of waypoints, for which `blocked` holds between their positions.
+    pub fn blocked_edges<F: Fn(Pos2, Pos2) -> bool>(&self, blocked: F) -> HashSet<(usize, usize)> {
+        self.neighbours
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| a.iter().map(move |(j, _)| (i, *j)))
+            .filter(|(i, j)| blocked(self.pos[*i], self.pos[*j]))
+            .collect()
+    }
+    /// A* from `from` to `to`, both included in the returned path. Ties are broken by position so
+    /// that the same network always gives the same route.
+    #[must_use]
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        self.shortest_path_avoiding(from, to, &HashSet::new())
+    }
+    /// [`Self::shortest_path`] without using any of the `blocked` connections.
+    #[must_use]
+    pub fn shortest_path_avoiding(
+        &self,
+        from: usize,
+        to: usize,
+        blocked: &HashSet<(usize, usize)>,
+    ) -> Option<Vec<usize>> {
+        let h = |i: usize| self.pos[i].distance(self.pos[to]);
+        let mut g_score = vec![f32::INFINITY; self.len()];
+        let mut came_from = vec![usize::MAX; self.len()];
+        let mut closed = vec![false; self.len()];
+        let mut open = BinaryHeap::from([Open {
+            f: h(from),
+            node: from,
+        }]);
+        g_score[from] = 0.0;
+
+        while let Some(Open { node, .. }) = open.pop() {
+            if node == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while came_from[current] != usize::MAX {
+                    current = came_from[current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if std::mem::replace(&mut closed[node], true) {
+                continue;
+            }
+            for &(next, cost) in &self.neighbours[node] {
+                if closed[next] || blocked.contains(&(node, next)) {
+                    continue;
+                }
+                let tent_g = g_score[node] + cost;
+                if tent_g < g_score[next] {
+                    g_score[next] = tent_g;
+                    came_from[next] = node;
+                    open.push(Open {
+                        f: tent_g + h(next),
+                        node: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Open {
+    f: f32,
+    node: usize,
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reversed, so that [`BinaryHeap`] pops the lowest score first.
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Routes already found in a [`WaypointGraph`], keyed by entry and exit waypoint. Unreachable pairs
+/// are cached too.
+#[derive(Debug, Default)]
+pub struct RouteCache(Mutex<HashMap<(usize, usize), Route>>);
+
+type Route = Option<Arc<[usize]>>;
+
+impl RouteCache {
+    pub fn get_or_find(&self, graph: &WaypointGraph, from: usize, to: usize) -> Route {
+        if let Some(route) = self.0.lock().ok().and_then(|a| a.get(&(from, to)).cloned()) {
+            return route;
+        }
+        let route = graph.shortest_path(from, to).map(Arc::from);
+        if let Ok(mut cache) = self.0.lock() {
+            cache.insert((from, to), route.clone());
+        }
+        route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waypoint(name: &str, x: f32, y: f32, connections: &[&str]) -> Arc<Waypoint> {
+        Arc::new(Waypoint {
+            name: name.into(),
+            pos: Pos2::new(x, y),
+            connections: connections.iter().map(|a| WaypointId::from(*a)).collect(),
+        })
+    }
+
+    #[test]
+    fn shortest_path() {
+        // A - B - D is shorter than A - C - D, and E is cut off
+        let graph = WaypointGraph::new(&[
+            waypoint("A", 0.0, 0.0, &["B", "C"]),
+            waypoint("B", 50.0, 10.0, &["A", "D"]),
+            waypoint("C", 50.0, 100.0, &["A", "D"]),
+            waypoint("D", 100.0, 0.0, &["B", "C"]),
+            waypoint("E", 500.0, 0.0, &[]),
+        ]);
+        assert_eq!(graph.shortest_path(0, 3), Some(vec![0, 1, 3]));
+        assert_eq!(graph.shortest_path(2, 2), Some(vec![2]));
+        assert_eq!(graph.shortest_path(0, 4), None);
+        assert_eq!(graph.nearest(Pos2::new(90.0, 5.0)), Some(3));
+
+        let cache = RouteCache::default();
+        assert_eq!(
+            cache.get_or_find(&graph, 0, 3).as_deref(),
+            Some(&[0, 1, 3][..])
+        );
+        assert_eq!(cache.get_or_find(&graph, 0, 4), None);
+        assert_eq!(cache.0.lock().unwrap().len(), 2);
+
+        let blocked = graph.blocked_edges(|a, b| a.y > 5.0 || b.y > 5.0);
+        assert!(blocked.contains(&(0, 1)) && blocked.contains(&(1, 0)));
+        assert_eq!(blocked.len(), 8);
+        assert_eq!(graph.shortest_path_avoiding(0, 3, &blocked), None);
+        let b = Pos2::new(50.0, 10.0);
+        let blocked = graph.blocked_edges(|a, c| a == b || c == b);
+        assert_eq!(
+            graph.shortest_path_avoiding(0, 3, &blocked),
+            Some(vec![0, 2, 3])
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use eyre::{eyre, Result};
+use rkyv::util::AlignedVec;
+
+use crate::{
+    config::Config,
+    engine::Engine,
+    state::{clock::Clock, reconcile::Reconciliation, State},
+    world_data::WorldData,
+};
+
+pub mod v0;
+pub mod v1;
+
+pub const SAVE_MAGIC: [u8; 4] = *b"ATSS";
+/// Bump whenever the archived layout of [`Engine`] changes, and add a migration from the previous
+/// version to [`from_bytes`].
+pub const SAVE_VERSION: u32 = 2;
+
+pub fn to_bytes(engine: &Engine) -> Result<Vec<u8>> {
+    let payload = rkyv::to_bytes::<rkyv::rancor::Error>(engine)?;
+    let mut out = Vec::with_capacity(SAVE_MAGIC.len() + 4 + payload.len());
+    out.extend(SAVE_MAGIC);
+    out.extend(SAVE_VERSION.to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+/// Returns the save version and the archived payload. Saves without a header are version 0.
+#[must_use]
+pub fn header(bytes: &[u8]) -> (u32, &[u8]) {
+    match bytes.split_first_chunk::<4>() {
+        Some((magic, rest)) if *magic == SAVE_MAGIC => rest
+            .split_first_chunk::<4>()
+            .map_or((0, bytes), |(version, payload)| {
+                (u32::from_le_bytes(*version), payload)
+            }),
+        _ => (0, bytes),
+    }
+}
+
+/// Decodes a save of any known version and reconciles its state against `world`.
+pub fn from_bytes(
+    bytes: &[u8],
+    world: &WorldData,
+    config: &Config,
+) -> Result<(State, Reconciliation)> {
+    let (version, payload) = header(bytes);
+    let mut aligned = AlignedVec::<16>::with_capacity(payload.len());
+    aligned.extend_from_slice(payload);
+
+    let mut state = match version {
+        0 => v0::migrate(
+            rkyv::from_bytes::<v0::Engine, rkyv::rancor::Error>(&aligned)?,
+            config.seed.unwrap_or_else(rand::random),
+            Clock::new(config.epoch),
+        ),
+        1 => v1::migrate(rkyv::from_bytes::<v1::Engine, rkyv::rancor::Error>(
+            &aligned,
+        )?),
+        SAVE_VERSION => rkyv::from_bytes::<Engine, rkyv::rancor::Error>(&aligned)?.state,
+        version => {
+            return Err(eyre!(
+                "Save version {version} is newer than the supported version {SAVE_VERSION}"
+            ))
+        }
+    };
+    let reconciliation = state.reconcile(world);
+    Ok((state, reconciliation))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Arc};
+
+    use glam::Vec2;
+
+    use super::*;
+    use crate::{
+        fixtures::{waypoint, world},
+        state::reconcile::DropReason,
+        util::{rng::SimRng, PlaneStateId, Pos2, Pos3},
+    };
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new(
+            world(),
+            Config {
+                plane_spawn_chance: 1.0,
+                max_planes: Some(5),
+                seed: Some(1),
+                ..Config::default()
+            },
+        )
+        .unwrap();
+        for _ in 0..10 {
+            engine.tick();
+        }
+        engine
+    }
+
+    #[test]
+    fn round_trip() {
+        let engine = engine();
+        let bytes = to_bytes(&engine).unwrap();
+        assert_eq!(header(&bytes).0, SAVE_VERSION);
+
+        let (state, reconciliation) = from_bytes(&bytes, &engine.world, &engine.config).unwrap();
+        assert!(reconciliation.dropped.is_empty());
+        assert_eq!(state.planes.len(), engine.state.planes.len());
+        assert_eq!(state.coord_state(), engine.state.coord_state());
+        assert_eq!(state.index, engine.state.index);
+    }
+
+    #[test]
+    fn keeps_planes_in_changed_world() {
+        let engine = engine();
+        let bytes = to_bytes(&engine).unwrap();
+
+        let mut new_world = world();
+        new_world.waypoints = new_world
+            .waypoints
+            .iter()
+            .cloned()
+            .chain([waypoint("C", Pos2::new(500.0, 800.0), &[])])
+            .collect();
+        let (state, reconciliation) = from_bytes(&bytes, &new_world, &engine.config).unwrap();
+        assert!(reconciliation.dropped.is_empty());
+        assert_eq!(state.planes.len(), engine.state.planes.len());
+
+        new_world.planes = Arc::new([]);
+        let (state, reconciliation) = from_bytes(&bytes, &new_world, &engine.config).unwrap();
+        assert!(state.planes.is_empty());
+        assert_eq!(reconciliation.dropped.len(), engine.state.planes.len());
+        assert!(reconciliation
+            .dropped
+            .iter()
+            .all(|(_, reason)| *reason == DropReason::Model("A320".into())));
+    }
+
+    fn v0_runway() -> Arc<v0::Runway> {
+        Arc::new(v0::Runway {
+            name: "18/36".into(),
+            start: Pos2::new(0.0, 0.0),
+            end: Pos2::new(0.0, 50.0),
+            altitude: 0.0,
+            class: "".into(),
+        })
+    }
+
+    fn v0_world() -> v0::WorldData {
+        v0::WorldData {
+            classes: Arc::new([]),
+            airports: Arc::new([]),
+            flights: None,
+            planes: Arc::new([]),
+            waypoints: Arc::new([]),
+        }
+    }
+
+    /// A plane taking off from FOO for BAR.
+    fn v0_plane() -> v0::Plane {
+        let runway = v0_runway();
+        v0::Plane {
+            id: PlaneStateId::nil(),
+            pos: v0::PlanePos {
+                pos_ang: v0::Pos3Angle(Pos3::new(0.0, 10.0, 0.0), v0::Angle(0.0)),
+                kinematics: v0::Kinematics {
+                    x_target: vec![],
+                    y_target: vec![],
+                    v: Vec2::new(10.0, 0.0),
+                },
+                planner: v0::FlightPlanner {
+                    instructions: VecDeque::new(),
+                    route: VecDeque::new(),
+                    instruction_s: 0.0,
+                    past_instructions: vec![],
+                    past_route: vec![],
+                    past_pos: vec![],
+                },
+            },
+            model: Arc::new(v0::PlaneData {
+                id: "A320".into(),
+                name: "".into(),
+                manufacturer: "".into(),
+                class: "".into(),
+                motion: v0::ModelMotion {
+                    max_v: Vec2::ONE,
+                    max_a: Vec2::ONE,
+                    turning_radius: 1.0,
+                },
+                icon: None,
+            }),
+            flight: Arc::new(v0::Flight {
+                airline: "".into(),
+                code: "".into(),
+                from: "FOO".into(),
+                to: "BAR".into(),
+                plane: Arc::new(["A320".into()]),
+            }),
+            phase: v0::PhaseData::Takeoff { runway },
+            events: VecDeque::new(),
+            start_time: 1234,
+        }
+    }
+
+    #[test]
+    fn migrate_v0() {
+        let old = v0::Engine {
+            world: v0_world(),
+            config: v0::Config {
+                tick_duration: 1.0,
+                plane_spawn_chance: 0.0,
+                max_planes: None,
+                cruising_altitude_plus: 0.0,
+                cruising_altitude_minus: 0.0,
+                ns_before_ew: false,
+                save_path: None,
+            },
+            state: v0::State {
+                planes: vec![v0_plane()],
+                airports: vec![],
+            },
+        };
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&old).unwrap();
+        assert_eq!(header(&bytes).0, 0);
+
+        let (state, reconciliation) = from_bytes(&bytes, &world(), &Config::default()).unwrap();
+        assert_eq!(reconciliation.kept, [PlaneStateId::nil()]);
+        assert_eq!(state.airports.len(), 2);
+        let plane = &state.planes[0];
+        assert_eq!(plane.model.id, "A320");
+        assert!((plane.start_time - 1234.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn migrate_v1() {
+        let v0::Plane {
+            id,
+            pos,
+            model,
+            flight,
+            phase,
+            ..
+        } = v0_plane();
+        let old = v1::Engine {
+            world: v0_world(),
+            config: v1::Config {
+                tick_duration: 1.0,
+                plane_spawn_chance: 0.0,
+                max_planes: None,
+                cruising_altitude_plus: 0.0,
+                cruising_altitude_minus: 0.0,
+                ns_before_ew: false,
+                seed: Some(1),
+                epoch: Some(1000),
+                save_path: None,
+            },
+            state: v1::State {
+                planes: vec![v1::Plane {
+                    id,
+                    pos,
+                    model,
+                    flight,
+                    phase,
+                    events: VecDeque::from([v1::PlaneEvent {
+                        from: "BAR".into(),
+                        time: 1100.0,
+                        payload: v0::PlaneEventPayload::ClearForLanding(v0_runway()),
+                    }]),
+                    start_time: 1050.0,
+                    phase_start: 1080.0,
+                }],
+                airports: vec![v1::Airport {
+                    id: "BAR".into(),
+                    airport: Arc::new(v0::AirportData {
+                        name: "".into(),
+                        code: "BAR".into(),
+                        runways: Arc::new([v0_runway()]),
+                    }),
+                    events: VecDeque::from([v1::AirportEvent {
+                        from: id,
+                        time: 1090.0,
+                        payload: v0::AirportEventPayload::RequestRunway,
+                    }]),
+                }],
+                rng: v1::SimRng { s: [1, 2, 3, 4] },
+                clock: v1::Clock {
+                    tick: 120,
+                    elapsed: 120.0,
+                    epoch: 1000,
+                },
+            },
+        };
+        let payload = rkyv::to_bytes::<rkyv::rancor::Error>(&old).unwrap();
+        let bytes = [&SAVE_MAGIC, &1_u32.to_le_bytes(), &*payload].concat();
+        assert_eq!(header(&bytes).0, 1);
+
+        let (state, reconciliation) = from_bytes(&bytes, &world(), &Config::default()).unwrap();
+        assert_eq!(reconciliation.kept, [PlaneStateId::nil()]);
+        // carried on from where the save left off, rather than started afresh
+        assert_eq!(state.clock.tick, 120);
+        assert_eq!(state.clock.epoch, 1000);
+        let rng = rkyv::to_bytes::<rkyv::rancor::Error>(&old.state.rng).unwrap();
+        assert_eq!(
+            state.rng,
+            rkyv::from_bytes::<SimRng, rkyv::rancor::Error>(&rng).unwrap()
+        );
+        let plane = &state.planes[0];
+        assert!((plane.start_time - 1050.0).abs() < f64::EPSILON);
+        assert!((plane.phase_start - 1080.0).abs() < f64::EPSILON);
+        assert!((plane.events[0].time - 1100.0).abs() < f64::EPSILON);
+        let airport = state.airport(&"BAR".into()).unwrap();
+        assert!((airport.events[0].time - 1090.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,508 @@
+//! The unversioned save layout, i.e. a bare archived `Engine` as written before save headers existed.
+//!
+//! These types are frozen copies and must not change with the live ones.
+
+use std::{collections::VecDeque, path::PathBuf, sync::Arc};
+
+use dubins_paths::f32 as dubins;
+use glam::{Vec2, Vec3};
+use smol_str::SmolStr;
+use uuid::Uuid;
+
+use crate::{
+    state::{self, clock::Clock},
+    util::{angle, kinematics, pos, ray},
+    world_data,
+};
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Engine {
+    pub world: WorldData,
+    pub config: Config,
+    pub state: State,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct WorldData {
+    pub classes: Arc<[Arc<[SmolStr]>]>,
+    pub airports: Arc<[Arc<AirportData>]>,
+    pub flights: Option<Arc<[Arc<Flight>]>>,
+    pub planes: Arc<[Arc<PlaneData>]>,
+    pub waypoints: Arc<[Arc<Waypoint>]>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct AirportData {
+    pub name: SmolStr,
+    pub code: SmolStr,
+    pub runways: Arc<[Arc<Runway>]>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Runway {
+    pub name: SmolStr,
+    pub start: Vec2,
+    pub end: Vec2,
+    pub altitude: f32,
+    pub class: SmolStr,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Flight {
+    pub airline: SmolStr,
+    pub code: SmolStr,
+    pub from: SmolStr,
+    pub to: SmolStr,
+    pub plane: Arc<[SmolStr]>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct PlaneData {
+    pub id: SmolStr,
+    pub name: SmolStr,
+    pub manufacturer: SmolStr,
+    pub class: SmolStr,
+    pub motion: ModelMotion,
+    #[rkyv(with = rkyv::with::Map<rkyv::with::AsString>)]
+    pub icon: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct ModelMotion {
+    pub max_v: Vec2,
+    pub max_a: Vec2,
+    pub turning_radius: f32,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Waypoint {
+    pub name: SmolStr,
+    pub pos: Vec2,
+    pub connections: Arc<[SmolStr]>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Config {
+    pub tick_duration: f32,
+    pub plane_spawn_chance: f32,
+    pub max_planes: Option<usize>,
+    pub cruising_altitude_plus: f32,
+    pub cruising_altitude_minus: f32,
+    pub ns_before_ew: bool,
+    #[rkyv(with = rkyv::with::Map<rkyv::with::AsString>)]
+    pub save_path: Option<PathBuf>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct State {
+    pub planes: Vec<Plane>,
+    pub airports: Vec<Airport>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Plane {
+    pub id: Uuid,
+    pub pos: PlanePos,
+    pub model: Arc<PlaneData>,
+    pub flight: Arc<Flight>,
+    pub phase: PhaseData,
+    pub events: VecDeque<PlaneEvent>,
+    pub start_time: u64,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct PlanePos {
+    pub pos_ang: Pos3Angle,
+    pub kinematics: Kinematics,
+    pub planner: FlightPlanner,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Kinematics {
+    pub x_target: Vec<Target>,
+    pub y_target: Vec<Target>,
+    pub v: Vec2,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Target {
+    pub a: f32,
+    pub dt: f32,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct FlightPlanner {
+    pub instructions: VecDeque<FlightInstruction>,
+    pub route: VecDeque<Arc<Waypoint>>,
+    pub instruction_s: f32,
+    pub past_instructions: Vec<FlightInstruction>,
+    pub past_route: Vec<Arc<Waypoint>>,
+    pub past_pos: Vec<Vec3>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub enum FlightInstruction {
+    Dubins(DubinsPath),
+    Straight(Ray),
+    Turn {
+        origin: Pos2Angle,
+        angle: Angle,
+        radius: f32,
+    },
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub enum PhaseData {
+    Takeoff { runway: Arc<Runway> },
+    Cruise,
+    Descent,
+    Landing { runway: Arc<Runway> },
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct PlaneEvent {
+    pub from: SmolStr,
+    pub payload: PlaneEventPayload,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub enum PlaneEventPayload {
+    ClearForLanding(Arc<Runway>),
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Airport {
+    pub id: SmolStr,
+    pub airport: Arc<AirportData>,
+    pub events: VecDeque<AirportEvent>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct AirportEvent {
+    pub from: Uuid,
+    pub payload: AirportEventPayload,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub enum AirportEventPayload {
+    RequestRunway,
+}
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Angle(pub f32);
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Pos2Angle(pub Vec2, pub Angle);
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Pos3Angle(pub Vec3, pub Angle);
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Ray {
+    pub tail: Vec2,
+    pub vec: Vec2,
+}
+
+/// `dubins_paths::f32::DubinsPath`, as archived by version 3.1.
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct DubinsPath {
+    pub qi: PosRot,
+    pub rho: f32,
+    pub param: [f32; 3],
+    pub path_type: PathType,
+}
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct PosRot(pub Vec2, pub f32);
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub enum PathType {
+    LSL,
+    LSR,
+    RSL,
+    RSR,
+    RLR,
+    LRL,
+}
+
+/// Lifts a v0 state into the current layout. World references are converted as-is and are expected
+/// to be re-pointed by [`state::State::reconcile`] afterwards.
+#[must_use]
+pub fn migrate(old: Engine, seed: u64, clock: Clock) -> state::State {
+    let now = clock.now();
+    let mut state = state::State::new(&[], seed, clock);
+    state.planes = old
+        .state
+        .planes
+        .into_iter()
+        .map(|a| a.migrate(now))
+        .collect();
+    state.airports = old
+        .state
+        .airports
+        .into_iter()
+        .map(|a| {
+            let mut airport = state::airport::Airport::new(Arc::new(a.airport.migrate()));
+            airport.events = a
+                .events
+                .into_iter()
+                .map(|e| state::airport::AirportEvent {
+                    from: e.from,
+                    time: now,
+                    payload: e.payload.migrate(),
+                })
+                .collect();
+            airport
+        })
+        .collect();
+    state
+}
+
+impl Plane {
+    /// The plane in the current layout, with its events and phase taken to have happened `now`.
+    pub(super) fn migrate(self, now: f64) -> state::plane::Plane {
+        state::plane::Plane {
+            id: self.id,
+            pos: self.pos.migrate(),
+            model: Arc::new(self.model.migrate()),
+            flight: Arc::new(self.flight.migrate()),
+            phase: self.phase.migrate(),
+            events: self
+                .events
+                .into_iter()
+                .map(|e| state::plane::PlaneEvent {
+                    from: e.from,
+                    time: now,
+                    payload: e.payload.migrate(),
+                })
+                .collect(),
+            start_time: self.start_time as f64,
+            phase_start: now,
+            advisory: None,
+            taxi_hold: None,
+        }
+    }
+}
+
+impl PlanePos {
+    fn migrate(self) -> state::plane_pos::PlanePos {
+        state::plane_pos::PlanePos {
+            pos_ang: self.pos_ang.migrate(),
+            kinematics: kinematics::Kinematics {
+                x_target: self
+                    .kinematics
+                    .x_target
+                    .into_iter()
+                    .map(Target::migrate)
+                    .collect(),
+                y_target: self
+                    .kinematics
+                    .y_target
+                    .into_iter()
+                    .map(Target::migrate)
+                    .collect(),
+                v: self.kinematics.v,
+            },
+            planner: state::plane_pos::FlightPlanner {
+                instructions: self
+                    .planner
+                    .instructions
+                    .into_iter()
+                    .map(FlightInstruction::migrate)
+                    .collect(),
+                route: self
+                    .planner
+                    .route
+                    .iter()
+                    .map(|a| Arc::new(a.migrate()))
+                    .collect(),
+                instruction_s: self.planner.instruction_s,
+                past_instructions: self
+                    .planner
+                    .past_instructions
+                    .into_iter()
+                    .map(FlightInstruction::migrate)
+                    .collect(),
+                past_route: self
+                    .planner
+                    .past_route
+                    .iter()
+                    .map(|a| Arc::new(a.migrate()))
+                    .collect(),
+                past_pos: self.planner.past_pos,
+                procedure: None,
+            },
+        }
+    }
+}
+
+impl PhaseData {
+    fn migrate(self) -> state::plane::PhaseData {
+        match self {
+            Self::Takeoff { runway } => state::plane::PhaseData::Takeoff {
+                runway: Arc::new(runway.migrate()),
+            },
+            Self::Cruise => state::plane::PhaseData::Cruise,
+            Self::Descent => state::plane::PhaseData::Descent,
+            Self::Landing { runway } => state::plane::PhaseData::Landing {
+                runway: Arc::new(runway.migrate()),
+            },
+        }
+    }
+}
+
+impl PlaneEventPayload {
+    pub(super) fn migrate(self) -> state::plane::PlaneEventPayload {
+        match self {
+            Self::ClearForLanding(runway) => {
+                state::plane::PlaneEventPayload::ClearForLanding(Arc::new(runway.migrate()))
+            }
+        }
+    }
+}
+
+impl AirportEventPayload {
+    pub(super) const fn migrate(self) -> state::airport::AirportEventPayload {
+        match self {
+            Self::RequestRunway => state::airport::AirportEventPayload::RequestRunway,
+        }
+    }
+}
+
+impl Target {
+    const fn migrate(self) -> kinematics::Target {
+        kinematics::Target {
+            a: self.a,
+            dt: self.dt,
+        }
+    }
+}
+
+impl FlightInstruction {
+    const fn migrate(self) -> state::plane_pos::FlightInstruction {
+        match self {
+            Self::Dubins(path) => state::plane_pos::FlightInstruction::Dubins(path.migrate()),
+            Self::Straight(ray) => state::plane_pos::FlightInstruction::Straight(ray.migrate()),
+            Self::Turn {
+                origin,
+                angle,
+                radius,
+            } => state::plane_pos::FlightInstruction::Turn {
+                origin: origin.migrate(),
+                angle: angle.migrate(),
+                radius,
+            },
+        }
+    }
+}
+
+impl Angle {
+    const fn migrate(self) -> angle::Angle {
+        angle::Angle(self.0)
+    }
+}
+
+impl Pos2Angle {
+    const fn migrate(self) -> pos::Pos2Angle {
+        pos::Pos2Angle(self.0, self.1.migrate())
+    }
+}
+
+impl Pos3Angle {
+    const fn migrate(self) -> pos::Pos3Angle {
+        pos::Pos3Angle(self.0, self.1.migrate())
+    }
+}
+
+impl Ray {
+    const fn migrate(self) -> ray::Ray<Vec2> {
+        ray::Ray {
+            tail: self.tail,
+            vec: self.vec,
+        }
+    }
+}
+
+impl DubinsPath {
+    const fn migrate(self) -> dubins::DubinsPath {
+        dubins::DubinsPath {
+            qi: dubins::PosRot::new(self.qi.0, self.qi.1),
+            rho: self.rho,
+            param: self.param,
+            path_type: match self.path_type {
+                PathType::LSL => dubins_paths::PathType::LSL,
+                PathType::LSR => dubins_paths::PathType::LSR,
+                PathType::RSL => dubins_paths::PathType::RSL,
+                PathType::RSR => dubins_paths::PathType::RSR,
+                PathType::RLR => dubins_paths::PathType::RLR,
+                PathType::LRL => dubins_paths::PathType::LRL,
+            },
+        }
+    }
+}
+
+impl AirportData {
+    pub(super) fn migrate(&self) -> world_data::AirportData {
+        world_data::AirportData {
+            name: self.name.clone(),
+            code: self.code.clone(),
+            runways: self.runways.iter().map(|a| Arc::new(a.migrate())).collect(),
+            ..world_data::AirportData::default()
+        }
+    }
+}
+
+impl Runway {
+    fn migrate(&self) -> world_data::Runway {
+        world_data::Runway {
+            name: self.name.clone(),
+            start: self.start,
+            end: self.end,
+            altitude: self.altitude,
+            class: self.class.clone(),
+            missed_approach: None,
+        }
+    }
+}
+
+impl Flight {
+    fn migrate(&self) -> world_data::Flight {
+        world_data::Flight {
+            airline: self.airline.clone(),
+            code: self.code.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            plane: Arc::clone(&self.plane),
+        }
+    }
+}
+
+impl PlaneData {
+    fn migrate(&self) -> world_data::PlaneData {
+        world_data::PlaneData {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            manufacturer: self.manufacturer.clone(),
+            class: self.class.clone(),
+            motion: world_data::ModelMotion {
+                max_v: self.motion.max_v,
+                max_a: self.motion.max_a,
+                turning_radius: self.motion.turning_radius,
+            },
+            min_takeoff_distance: 0.0,
+            min_landing_distance: 0.0,
+            max_crosswind: None,
+            icon: self.icon.clone(),
+        }
+    }
+}
+
+impl Waypoint {
+    fn migrate(&self) -> world_data::Waypoint {
+        world_data::Waypoint {
+            name: self.name.clone(),
+            pos: self.pos,
+            connections: Arc::clone(&self.connections),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! The first versioned save layout: v0 with the seeded RNG, the simulation clock and the times
+//! of plane and airport events.
+//!
+//! These types are frozen copies and must not change with the live ones. Those unchanged since v0
+//! are the v0 types.
+
+use std::{collections::VecDeque, path::PathBuf, sync::Arc};
+
+use rand::SeedableRng;
+use smol_str::SmolStr;
+use uuid::Uuid;
+
+use super::v0;
+use crate::{
+    state::{self, clock},
+    util::rng,
+};
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Engine {
+    pub world: v0::WorldData,
+    pub config: Config,
+    pub state: State,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Config {
+    pub tick_duration: f32,
+    pub plane_spawn_chance: f32,
+    pub max_planes: Option<usize>,
+    pub cruising_altitude_plus: f32,
+    pub cruising_altitude_minus: f32,
+    pub ns_before_ew: bool,
+    pub seed: Option<u64>,
+    pub epoch: Option<u64>,
+    #[rkyv(with = rkyv::with::Map<rkyv::with::AsString>)]
+    pub save_path: Option<PathBuf>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct State {
+    pub planes: Vec<Plane>,
+    pub airports: Vec<Airport>,
+    pub rng: SimRng,
+    pub clock: Clock,
+}
+
+/// The four words of xoshiro256++ state.
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct SimRng {
+    pub s: [u64; 4],
+}
+
+#[derive(Clone, Copy, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Clock {
+    pub tick: u64,
+    pub elapsed: f64,
+    pub epoch: u64,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Plane {
+    pub id: Uuid,
+    pub pos: v0::PlanePos,
+    pub model: Arc<v0::PlaneData>,
+    pub flight: Arc<v0::Flight>,
+    pub phase: v0::PhaseData,
+    pub events: VecDeque<PlaneEvent>,
+    pub start_time: f64,
+    pub phase_start: f64,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct PlaneEvent {
+    pub from: SmolStr,
+    pub time: f64,
+    pub payload: v0::PlaneEventPayload,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct Airport {
+    pub id: SmolStr,
+    pub airport: Arc<v0::AirportData>,
+    pub events: VecDeque<AirportEvent>,
+}
+
+#[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+pub struct AirportEvent {
+    pub from: Uuid,
+    pub time: f64,
+    pub payload: v0::AirportEventPayload,
+}
+
+/// Lifts a v1 state into the current layout, carrying on from its RNG and clock. World references
+/// are converted as-is and are expected to be re-pointed by [`state::State::reconcile`] afterwards.
+#[must_use]
+pub fn migrate(old: Engine) -> state::State {
+    let mut state = state::State::new(&[], 0, old.state.clock.migrate());
+    state.rng = old.state.rng.migrate();
+    state.planes = old.state.planes.into_iter().map(Plane::migrate).collect();
+    state.airports = old
+        .state
+        .airports
+        .into_iter()
+        .map(|a| {
+            let mut airport = state::airport::Airport::new(Arc::new(a.airport.migrate()));
+            airport.events = a
+                .events
+                .into_iter()
+                .map(|e| state::airport::AirportEvent {
+                    from: e.from,
+                    time: e.time,
+                    payload: e.payload.migrate(),
+                })
+                .collect();
+            airport
+        })
+        .collect();
+    state
+}
+
+impl Plane {
+    fn migrate(self) -> state::plane::Plane {
+        let mut plane = v0::Plane {
+            id: self.id,
+            pos: self.pos,
+            model: self.model,
+            flight: self.flight,
+            phase: self.phase,
+            events: VecDeque::new(),
+            start_time: 0,
+        }
+        .migrate(self.phase_start);
+        plane.start_time = self.start_time;
+        plane.events = self
+            .events
+            .into_iter()
+            .map(|e| state::plane::PlaneEvent {
+                from: e.from,
+                time: e.time,
+                payload: e.payload.migrate(),
+            })
+            .collect();
+        plane
+    }
+}
+
+impl SimRng {
+    fn migrate(&self) -> rng::SimRng {
+        let mut seed = [0; 32];
+        for (chunk, word) in seed.chunks_exact_mut(8).zip(self.s) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        rng::SimRng::from_seed(seed)
+    }
+}
+
+impl Clock {
+    const fn migrate(self) -> clock::Clock {
+        clock::Clock {
+            tick: self.tick,
+            elapsed: self.elapsed,
+            epoch: self.epoch,
+        }
+    }
+}
@@ -1,14 +1,19 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{cmp::Ordering, collections::VecDeque, sync::Arc};
 
-use rand::{prelude::*, rng};
+use rand::prelude::*;
 use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use tracing::{debug, warn};
 use ts_rs::TS;
 
 use crate::{
     config::Config,
-    state::plane::{PlaneEvent, PlaneEventPayload},
-    util::{AirportStateId, PlaneStateId},
-    world_data::AirportData,
+    state::{
+        clock::Clock,
+        plane::{GoAroundReason, PhaseData, Plane, PlaneEvent, PlaneEventPayload},
+    },
+    util::{rng::SimRng, AirportCode, AirportStateId, Class, PlaneStateId},
+    world_data::{AirportData, Gate, HoldingFix, Runway, WorldData},
 };
 
 #[derive(
@@ -21,6 +26,98 @@ pub struct Airport {
     pub airport: Arc<AirportData>,
     #[ts(as = "Vec<AirportEvent>")]
     pub events: VecDeque<AirportEvent>,
+    pub runways: Vec<RunwayState>,
+    /// Runway requests waiting for a runway to become free, first come first served
+    #[ts(as = "Vec<AirportEvent>")]
+    pub queue: VecDeque<AirportEvent>,
+    /// Queued planes sent to hold
+    pub holding: Vec<Holding>,
+    pub gates: Vec<GateState>,
+}
+
+#[derive(
+    Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
+)]
+#[ts(export)]
+pub struct Holding {
+    #[ts(as = "String")]
+    pub plane: PlaneStateId,
+    pub fix: Arc<HoldingFix>,
+    /// Level in the stack at `fix`, 0 being the lowest
+    pub level: u32,
+}
+
+impl Holding {
+    fn event(&self, airport: &AirportStateId, config: &Config, now: f64) -> PlaneEvent {
+        PlaneEvent {
+            from: airport.clone(),
+            time: now,
+            payload: PlaneEventPayload::Hold {
+                fix: Arc::clone(&self.fix),
+                altitude: self.altitude(config),
+            },
+        }
+    }
+    #[must_use]
+    pub const fn altitude(&self, config: &Config) -> f32 {
+        (self.level as f32).mul_add(config.separation_vertical, config.holding_altitude)
+    }
+}
+
+#[derive(
+    Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
+)]
+#[ts(export)]
+pub struct RunwayState {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    /// Plane cleared to land on or take off from the runway, until it has left it
+    #[ts(as = "Option<String>")]
+    pub occupant: Option<PlaneStateId>,
+    /// The last plane to leave the runway, and when
+    #[ts(as = "Option<(String, f64)>")]
+    pub vacated: Option<(PlaneStateId, f64)>,
+    /// The last plane cleared to take off from the runway, which the next departure is spaced
+    /// behind
+    pub last_departure: Option<LastDeparture>,
+}
+
+#[derive(
+    Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
+)]
+#[ts(export)]
+pub struct LastDeparture {
+    #[ts(as = "String")]
+    pub plane: PlaneStateId,
+    pub time: f64,
+    #[ts(as = "String")]
+    pub class: Class,
+    #[ts(as = "String")]
+    pub to: AirportCode,
+}
+
+impl RunwayState {
+    #[must_use]
+    pub const fn new(name: SmolStr) -> Self {
+        Self {
+            name,
+            occupant: None,
+            vacated: None,
+            last_departure: None,
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
+)]
+#[ts(export)]
+pub struct GateState {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    /// Plane parked at the gate, taxiing to it, or being pushed back from it
+    #[ts(as = "Option<String>")]
+    pub occupant: Option<PlaneStateId>,
 }
 
 impl Airport {
@@ -28,27 +125,425 @@ impl Airport {
     pub fn new(airport: Arc<AirportData>) -> Self {
         Self {
             id: airport.code.clone(),
+            runways: airport
+                .runways
+                .iter()
+                .map(|a| RunwayState::new(a.name.clone()))
+                .collect(),
+            gates: airport
+                .gates
+                .iter()
+                .map(|a| GateState {
+                    name: a.name.clone(),
+                    occupant: None,
+                })
+                .collect(),
             airport,
             events: VecDeque::new(),
+            queue: VecDeque::new(),
+            holding: Vec::new(),
+        }
+    }
+    #[must_use]
+    pub fn runway_state(&self, name: &SmolStr) -> Option<&RunwayState> {
+        self.runways.iter().find(|a| a.name == *name)
+    }
+    /// Whether a movement can be cleared onto `runway`: nobody occupies it, and the last plane to
+    /// use it left at least [`Config::runway_spacing_time`] ago and is
+    /// [`Config::runway_spacing_distance`] away from it.
+    #[must_use]
+    pub fn runway_free<'a, F: Fn(&PlaneStateId) -> Option<&'a Plane>>(
+        &self,
+        runway: &Runway,
+        config: &Config,
+        now: f64,
+        plane: F,
+    ) -> bool {
+        let Some(state) = self.runway_state(&runway.name) else {
+            return false;
+        };
+        state.occupant.is_none()
+            && state.vacated.is_none_or(|(id, time)| {
+                now - time >= f64::from(config.runway_spacing_time)
+                    && plane(&id).is_none_or(|a| {
+                        a.on_ground()
+                            || runway.distance(a.pos.pos_ang.0.truncate())
+                                >= config.runway_spacing_distance
+                    })
+            })
+    }
+    pub fn occupy(&mut self, runway: &SmolStr, plane: PlaneStateId) {
+        if let Some(state) = self.runways.iter_mut().find(|a| a.name == *runway) {
+            state.occupant = Some(plane);
+        }
+    }
+    /// Gates nobody occupies whose taxiway exists.
+    pub fn free_gates(&self) -> impl Iterator<Item = &Arc<Gate>> {
+        self.airport.gates.iter().filter(|gate| {
+            self.airport.taxiway(&gate.taxiway).is_some()
+                && self
+                    .gates
+                    .iter()
+                    .any(|a| a.name == gate.name && a.occupant.is_none())
+        })
+    }
+    pub fn occupy_gate(&mut self, gate: &SmolStr, plane: PlaneStateId) {
+        if let Some(state) = self.gates.iter_mut().find(|a| a.name == *gate) {
+            state.occupant = Some(plane);
+        }
+    }
+    /// The gate `plane` occupies or has been given.
+    #[must_use]
+    pub fn gate_of(&self, plane: &PlaneStateId) -> Option<&Arc<Gate>> {
+        let state = self.gates.iter().find(|a| a.occupant == Some(*plane))?;
+        self.airport.gates.iter().find(|a| a.name == state.name)
+    }
+    /// Picks a gate for `plane`: the one it already has, or a free one it fits. Gates its airline
+    /// prefers come first, then gates no airline prefers, then the rest.
+    pub fn allocate_gate(
+        &self,
+        wd: &WorldData,
+        plane: &Plane,
+        rng: &mut SimRng,
+    ) -> Option<Arc<Gate>> {
+        if let Some(gate) = self.gate_of(&plane.id) {
+            return Some(Arc::clone(gate));
+        }
+        let preference = |gate: &Gate| {
+            if gate.airlines.contains(&plane.flight.airline) {
+                0
+            } else if gate.airlines.is_empty() {
+                1
+            } else {
+                2
+            }
+        };
+        let gates = self
+            .free_gates()
+            .filter(|a| wd.can_park(&plane.model, a))
+            .collect::<Vec<_>>();
+        let best = gates.iter().map(|a| preference(a)).min()?;
+        gates
+            .into_iter()
+            .filter(|a| preference(a) == best)
+            .collect::<Vec<_>>()
+            .choose(rng)
+            .map(|a| Arc::clone(a))
+    }
+    /// Fraction of the gates occupied, if the airport has any.
+    #[must_use]
+    pub fn gate_utilisation(&self) -> Option<f32> {
+        (!self.gates.is_empty()).then(|| {
+            self.gates.iter().filter(|a| a.occupant.is_some()).count() as f32
+                / self.gates.len() as f32
+        })
+    }
+    /// Frees gates whose occupant has left them, or is no longer coming.
+    fn release_gates<'a, F: Fn(&PlaneStateId) -> Option<&'a Plane>>(&mut self, plane: F) {
+        for state in &mut self.gates {
+            let Some(id) = state.occupant else {
+                continue;
+            };
+            if plane(&id).is_none_or(|a| {
+                let inbound = a.flight.to == self.id
+                    && matches!(
+                        a.phase,
+                        PhaseData::Descent
+                            | PhaseData::Holding { .. }
+                            | PhaseData::Landing { .. }
+                            | PhaseData::GoAround { .. }
+                            | PhaseData::MissedApproach { .. }
+                    );
+                !inbound
+                    && !matches!(
+                        a.phase,
+                        PhaseData::Pushback { .. }
+                            | PhaseData::TaxiIn { .. }
+                            | PhaseData::Parked { .. }
+                    )
+            }) {
+                debug!(%id, gate = %state.name, "Gate vacated");
+                state.occupant = None;
+            }
+        }
+    }
+    /// Frees runways whose occupant has taken off and is cruising, or has landed and is taxiing
+    /// in or gone.
+    fn release_runways<'a, F: Fn(&PlaneStateId) -> Option<&'a Plane>>(
+        &mut self,
+        plane: F,
+        now: f64,
+    ) {
+        for state in &mut self.runways {
+            let Some(id) = state.occupant else {
+                continue;
+            };
+            if plane(&id).is_none_or(|a| {
+                matches!(
+                    a.phase,
+                    PhaseData::Cruise | PhaseData::TaxiIn { .. } | PhaseData::Parked { .. }
+                )
+            }) {
+                debug!(%id, runway = %state.name, "Runway vacated");
+                state.occupant = None;
+                state.vacated = Some((id, now));
+            }
         }
     }
-    pub fn tick(&mut self, _config: &Config) -> Vec<(PlaneStateId, PlaneEvent)> {
+    /// Sends `plane` to the lowest free level of the stack at the holding fix nearest to it.
+    fn hold(
+        &mut self,
+        plane: &Plane,
+        config: &Config,
+        now: f64,
+    ) -> Option<(PlaneStateId, PlaneEvent)> {
+        let fix = self.airport.holding_fix(plane.pos.pos_ang.0.truncate())?;
+        let level = (0..=u32::MAX)
+            .find(|level| {
+                !self
+                    .holding
+                    .iter()
+                    .any(|a| a.fix.name == fix.name && a.level == *level)
+            })
+            .unwrap_or_default();
+        let holding = Holding {
+            plane: plane.id,
+            fix,
+            level,
+        };
+        debug!(plane = %plane.id, fix = %holding.fix.name, level, "Holding");
+        let event = holding.event(&self.id, config, now);
+        self.holding.push(holding);
+        Some((plane.id, event))
+    }
+    /// Moves planes down their stack into levels left by planes cleared to land.
+    fn descend_stacks(&mut self, config: &Config, now: f64) -> Vec<(PlaneStateId, PlaneEvent)> {
         let mut send = vec![];
-        for event in self.events.drain(..) {
-            match event.payload {
+        self.holding
+            .sort_by(|a, b| a.fix.name.cmp(&b.fix.name).then(a.level.cmp(&b.level)));
+        for stack in self.holding.chunk_by_mut(|a, b| a.fix.name == b.fix.name) {
+            for (level, holding) in (0..).zip(stack) {
+                if holding.level != level {
+                    holding.level = level;
+                    send.push((holding.plane, holding.event(&self.id, config, now)));
+                }
+            }
+        }
+        send
+    }
+    /// Queues requests, and frees the runways of planes that went around.
+    fn take_events(&mut self, now: f64) {
+        for event in std::mem::take(&mut self.events) {
+            match &event.payload {
+                AirportEventPayload::RequestRunway
+                | AirportEventPayload::RequestDeparture { .. } => {
+                    self.queue.push_back(event);
+                }
+                AirportEventPayload::GoAround { runway, .. } => {
+                    if let Some(state) = self
+                        .runways
+                        .iter_mut()
+                        .find(|a| a.name == *runway && a.occupant == Some(event.from))
+                    {
+                        state.occupant = None;
+                        state.vacated = Some((event.from, now));
+                    }
+                }
+            }
+        }
+    }
+    /// Clears `plane` to take off from `runway` if it is free, no departure that asked earlier is
+    /// waiting for it, and the last departure from it is far enough ahead: by
+    /// [`Config::departure_wake_spacing`] behind a larger plane, and by
+    /// [`Config::departure_route_spacing`] behind one to the same airport.
+    #[expect(clippy::too_many_arguments)]
+    fn clear_departure<'a, F: Fn(&PlaneStateId) -> Option<&'a Plane> + Copy>(
+        &mut self,
+        plane: &Plane,
+        runway: &SmolStr,
+        waiting: &VecDeque<AirportEvent>,
+        config: &Config,
+        wd: &WorldData,
+        lookup: F,
+        clock: &Clock,
+    ) -> Option<PlaneEvent> {
+        if waiting.iter().any(|a| {
+            matches!(&a.payload, AirportEventPayload::RequestDeparture { runway: r } if r == runway)
+        }) {
+            return None;
+        }
+        let data = self.airport.runways.iter().find(|a| a.name == *runway)?;
+        if !self.runway_free(data, config, clock.now(), lookup) {
+            return None;
+        }
+        if let Some(last) = &self.runway_state(runway)?.last_departure {
+            let mut spacing = 0.0f32;
+            if wd.cmp_class(&last.class, &plane.model.class) == Some(Ordering::Greater) {
+                spacing = spacing.max(config.departure_wake_spacing);
+            }
+            if last.to == plane.flight.to {
+                spacing = spacing.max(config.departure_route_spacing);
+            }
+            if clock.now() - last.time < f64::from(spacing) {
+                return None;
+            }
+        }
+        let data = Arc::clone(data);
+        debug!(plane = %plane.id, %runway, "Cleared for takeoff");
+        if let Some(state) = self.runways.iter_mut().find(|a| a.name == *runway) {
+            state.occupant = Some(plane.id);
+            state.last_departure = Some(LastDeparture {
+                plane: plane.id,
+                time: clock.now(),
+                class: plane.model.class.clone(),
+                to: plane.flight.to.clone(),
+            });
+        }
+        Some(PlaneEvent {
+            from: self.id.clone(),
+            time: clock.now(),
+            payload: PlaneEventPayload::ClearForTakeoff(data),
+        })
+    }
+    /// Sends `plane`, which fits none of the runways, on to the other airport with the runway
+    /// nearest to it that it can land on, if there is one.
+    fn divert(&self, wd: &WorldData, plane: &Plane, clock: &Clock) -> Option<PlaneEvent> {
+        let pos = plane.pos.pos_ang.0.truncate();
+        let Some(alternate) = wd
+            .airports
+            .iter()
+            .filter(|a| a.code != self.airport.code)
+            .filter_map(|a| {
+                a.runways
+                    .iter()
+                    .filter(|r| wd.can_land(&plane.model, r))
+                    .map(|r| r.start.distance(pos))
+                    .min_by(f32::total_cmp)
+                    .map(|d| (a, d))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(a, _)| a)
+        else {
+            warn!(plane = %plane.id, airport = %self.id, "No runway anywhere fits plane");
+            return None;
+        };
+        warn!(plane = %plane.id, airport = %self.id, to = %alternate.code, "No runway fits plane, diverting");
+        Some(PlaneEvent {
+            from: self.id.clone(),
+            time: clock.now(),
+            payload: PlaneEventPayload::Divert(Arc::clone(alternate)),
+        })
+    }
+    /// Answers runway requests in the order they came in, once a runway the plane can land on is
+    /// free, and at airports with taxiways a gate it fits too. Planes left waiting are sent to
+    /// hold. Departures are cleared onto their runway in the same order. `plane` gives a plane by
+    /// its id.
+    pub fn tick<'a, F: Fn(&PlaneStateId) -> Option<&'a Plane> + Copy>(
+        &mut self,
+        config: &Config,
+        wd: &WorldData,
+        plane: F,
+        clock: &Clock,
+        rng: &mut SimRng,
+    ) -> Vec<(PlaneStateId, PlaneEvent)> {
+        self.release_runways(plane, clock.now());
+        self.release_gates(plane);
+        self.take_events(clock.now());
+
+        let mut send = vec![];
+        let mut waiting = VecDeque::new();
+        while let Some(event) = self.queue.pop_front() {
+            match &event.payload {
+                // handled as soon as it comes in
+                AirportEventPayload::GoAround { .. } => {}
+                AirportEventPayload::RequestDeparture { runway } => {
+                    let Some(requester) = plane(&event.from) else {
+                        continue;
+                    };
+                    match self
+                        .clear_departure(requester, runway, &waiting, config, wd, plane, clock)
+                    {
+                        Some(clearance) => send.push((event.from, clearance)),
+                        None => waiting.push_back(event),
+                    }
+                }
                 AirportEventPayload::RequestRunway => {
+                    let Some(requester) = plane(&event.from) else {
+                        continue;
+                    };
+                    let runways = self
+                        .airport
+                        .runways
+                        .iter()
+                        .filter(|r| wd.can_land(&requester.model, r))
+                        .collect::<Vec<_>>();
+                    // with no airport to divert to, it is left to hold in case the world changes
+                    if runways.is_empty() {
+                        if let Some(divert) = self.divert(wd, requester, clock) {
+                            send.push((event.from, divert));
+                            continue;
+                        }
+                    }
+                    // arrivals wait for the runways into the wind rather than land downwind
+                    let runway = wd
+                        .runways_into_wind(
+                            &requester.model,
+                            runways,
+                            clock.elapsed,
+                            config.runway_headwind_margin,
+                        )
+                        .into_iter()
+                        .filter(|r| self.runway_free(r, config, clock.now(), plane))
+                        .collect::<Vec<_>>()
+                        .choose(rng)
+                        .map(|a| Arc::clone(a));
+                    // a full apron keeps arrivals in the air
+                    let gate = if self.airport.has_ground() {
+                        let gate = self.allocate_gate(wd, requester, rng);
+                        if gate.is_none() {
+                            debug!(plane = %event.from, airport = %self.id, "No gate free");
+                        }
+                        gate.map(Some)
+                    } else {
+                        Some(None)
+                    };
+                    let (Some(runway), Some(gate)) = (runway, gate) else {
+                        if !self.holding.iter().any(|a| a.plane == event.from) {
+                            send.extend(self.hold(requester, config, clock.now()));
+                        }
+                        waiting.push_back(event);
+                        continue;
+                    };
+                    self.occupy(&runway.name, event.from);
+                    if let Some(gate) = gate {
+                        self.occupy_gate(&gate.name, event.from);
+                    }
+                    if let Some(i) = self.holding.iter().position(|a| a.plane == event.from) {
+                        self.holding.remove(i);
+                        send.push((
+                            event.from,
+                            PlaneEvent {
+                                from: self.id.clone(),
+                                time: clock.now(),
+                                payload: PlaneEventPayload::Release,
+                            },
+                        ));
+                    }
                     send.push((
                         event.from,
                         PlaneEvent {
                             from: self.id.clone(),
-                            payload: PlaneEventPayload::ClearForLanding(Arc::clone(
-                                self.airport.runways.choose(&mut rng()).unwrap(),
-                            )),
+                            time: clock.now(),
+                            payload: PlaneEventPayload::ClearForLanding(runway),
                         },
                     ));
                 }
             }
         }
+        self.holding
+            .retain(|a| waiting.iter().any(|e| e.from == a.plane));
+        self.queue = waiting;
+        send.extend(self.descend_stacks(config, clock.now()));
         send
     }
 }
@@ -59,6 +554,7 @@ impl Airport {
 #[ts(export)]
 pub struct AirportEvent {
     pub from: PlaneStateId,
+    pub time: f64,
     pub payload: AirportEventPayload,
 }
 
@@ -69,4 +565,358 @@ pub struct AirportEvent {
 #[non_exhaustive]
 pub enum AirportEventPayload {
     RequestRunway,
+    /// The plane is waiting at the holding point of `runway` for takeoff clearance
+    RequestDeparture {
+        #[ts(as = "String")]
+        runway: SmolStr,
+    },
+    /// The plane abandoned its landing on `runway`
+    GoAround {
+        #[ts(as = "String")]
+        runway: SmolStr,
+        reason: GoAroundReason,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::world,
+        state::ground::tests::ground_world,
+        util::Pos2,
+        world_data::{Flight, PlaneData},
+    };
+
+    #[test]
+    fn one_plane_per_runway() {
+        let wd = world();
+        let config = Config::default();
+        let mut clock = Clock::default();
+        let mut rng = SimRng::seed_from_u64(0);
+        let flight = Arc::new(Flight {
+            from: "FOO".into(),
+            to: "BAR".into(),
+            ..Flight::default()
+        });
+        let mut planes = (0..3)
+            .map(|_| {
+                Plane::new(
+                    &wd.planes[0],
+                    &flight,
+                    &wd.airports[0].runways[0],
+                    &config,
+                    &wd,
+                    &clock,
+                    &mut rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        for plane in &mut planes {
+            plane.phase = PhaseData::Descent;
+        }
+        let [first, second, third] = [0, 1, 2].map(|i| planes[i].id);
+        let mut airport = Airport::new(Arc::clone(&wd.airports[1]));
+        for plane in &planes {
+            airport.events.push_back(AirportEvent {
+                from: plane.id,
+                time: clock.now(),
+                payload: AirportEventPayload::RequestRunway,
+            });
+        }
+        let payloads = |send: Vec<(PlaneStateId, PlaneEvent)>| {
+            send.into_iter()
+                .map(|(id, event)| match event.payload {
+                    PlaneEventPayload::ClearForLanding(_) => (id, "clear", 0.0),
+                    PlaneEventPayload::ClearForTakeoff(_) => (id, "takeoff", 0.0),
+                    PlaneEventPayload::Hold { altitude, .. } => (id, "hold", altitude),
+                    PlaneEventPayload::Release => (id, "release", 0.0),
+                    PlaneEventPayload::GoAround(_) => (id, "go around", 0.0),
+                    PlaneEventPayload::Divert(_) => (id, "divert", 0.0),
+                })
+                .collect::<Vec<_>>()
+        };
+        let (low, high) = (
+            config.holding_altitude,
+            config.holding_altitude + config.separation_vertical,
+        );
+
+        // one plane is cleared, and the others stacked up at the fix
+        let lookup = |id: &PlaneStateId| planes.iter().find(|a| a.id == *id);
+        let send = airport.tick(&config, &wd, lookup, &clock, &mut rng);
+        assert_eq!(
+            payloads(send),
+            [
+                (first, "clear", 0.0),
+                (second, "hold", low),
+                (third, "hold", high),
+            ]
+        );
+        assert_eq!(airport.runways[0].occupant, Some(first));
+        assert_eq!(airport.queue.len(), 2);
+
+        // the first plane has landed, but the runway is not free again until the spacing time is
+        // over
+        planes.remove(0);
+        let lookup = |id: &PlaneStateId| planes.iter().find(|a| a.id == *id);
+        clock.advance(1.0);
+        assert!(airport
+            .tick(&config, &wd, lookup, &clock, &mut rng)
+            .is_empty());
+        assert_eq!(airport.runways[0].occupant, None);
+        assert_eq!(airport.queue.len(), 2);
+
+        // the bottom of the stack is released, and the plane above moves down
+        clock.advance(config.runway_spacing_time);
+        let send = airport.tick(&config, &wd, lookup, &clock, &mut rng);
+        assert_eq!(
+            payloads(send),
+            [
+                (second, "release", 0.0),
+                (second, "clear", 0.0),
+                (third, "hold", low),
+            ]
+        );
+        assert_eq!(airport.queue.len(), 1);
+        assert_eq!(airport.holding.len(), 1);
+    }
+
+    #[test]
+    fn gate_allocation() {
+        let mut wd = ground_world();
+        // planes and runways are of the default class, and larger than "S"
+        wd.classes = Arc::new([Arc::new(["S".into(), "".into()])]);
+        let gate = |name: &str, class: &str, airlines: &[&str]| {
+            Arc::new(Gate {
+                name: name.into(),
+                taxiway: "A2".into(),
+                class: class.into(),
+                airlines: airlines.iter().map(|a| SmolStr::from(*a)).collect(),
+                ..Gate::default()
+            })
+        };
+        let mut data = (*wd.airports[0]).clone();
+        data.gates = Arc::new([
+            gate("small", "S", &[]),
+            gate("any", "", &[]),
+            gate("ours", "", &["XY"]),
+            gate("theirs", "", &["ZZ"]),
+        ]);
+        let mut airport = Airport::new(Arc::new(data));
+        let clock = Clock::default();
+        let mut rng = SimRng::seed_from_u64(0);
+        let flight = Arc::new(Flight {
+            airline: "XY".into(),
+            from: "BAR".into(),
+            to: "FOO".into(),
+            ..Flight::default()
+        });
+        let mut planes = (0..4)
+            .map(|_| {
+                let mut plane = Plane::new(
+                    &wd.planes[0],
+                    &flight,
+                    &airport.airport.runways[0],
+                    &Config::default(),
+                    &wd,
+                    &clock,
+                    &mut rng,
+                );
+                plane.phase = PhaseData::Descent;
+                plane
+            })
+            .collect::<Vec<_>>();
+        let plane = planes.pop().unwrap();
+
+        // the airline's own gate first, then one nobody prefers, then anybody else's, and never
+        // one too small
+        for (other, expected) in planes.iter().zip(["ours", "any", "theirs"]) {
+            let gate = airport.allocate_gate(&wd, other, &mut rng).unwrap();
+            assert_eq!(gate.name, expected);
+            airport.occupy_gate(&gate.name, other.id);
+        }
+        assert!(airport.allocate_gate(&wd, &plane, &mut rng).is_none());
+        assert_eq!(airport.gate_utilisation(), Some(0.75));
+
+        // a full apron keeps arrivals holding, even with the runway free
+        airport.events.push_back(AirportEvent {
+            from: plane.id,
+            time: clock.now(),
+            payload: AirportEventPayload::RequestRunway,
+        });
+        planes.push(plane);
+        let lookup = |id: &PlaneStateId| planes.iter().find(|a| a.id == *id);
+        let send = airport.tick(&Config::default(), &wd, lookup, &clock, &mut rng);
+        assert!(matches!(
+            send[..],
+            [(
+                _,
+                PlaneEvent {
+                    payload: PlaneEventPayload::Hold { .. },
+                    ..
+                }
+            )]
+        ));
+        assert_eq!(airport.runways[0].occupant, None);
+        assert_eq!(airport.queue.len(), 1);
+    }
+
+    #[test]
+    fn departure_spacing() {
+        let mut wd = world();
+        wd.classes = Arc::new([Arc::new(["S".into(), "L".into()])]);
+        let config = Config::default();
+        let mut clock = Clock::default();
+        let mut rng = SimRng::seed_from_u64(0);
+        let bar = Arc::clone(&wd.airports[1]);
+        let mut depart = |class: &str, to: &str, clock: &Clock| {
+            let model = Arc::new(PlaneData {
+                class: class.into(),
+                ..(*wd.planes[0]).clone()
+            });
+            let flight = Arc::new(Flight {
+                from: "BAR".into(),
+                to: to.into(),
+                ..Flight::default()
+            });
+            let mut plane = Plane::new(
+                &model,
+                &flight,
+                &bar.runways[0],
+                &config,
+                &wd,
+                clock,
+                &mut rng,
+            );
+            plane.phase = PhaseData::HoldingPoint {
+                runway: Arc::clone(&bar.runways[0]),
+            };
+            plane
+        };
+        let mut planes = vec![depart("L", "FOO", &clock), depart("S", "BAR", &clock)];
+        let mut airport = Airport::new(Arc::clone(&bar));
+        let request = |airport: &mut Airport, plane: &Plane, clock: &Clock| {
+            airport.events.push_back(AirportEvent {
+                from: plane.id,
+                time: clock.now(),
+                payload: AirportEventPayload::RequestDeparture {
+                    runway: "18/36".into(),
+                },
+            });
+        };
+        for plane in &planes {
+            request(&mut airport, plane, &clock);
+        }
+        let mut rng = SimRng::seed_from_u64(0);
+        let mut tick_at = |airport: &mut Airport, planes: &[Plane], time: f64| {
+            let lookup = |id: &PlaneStateId| planes.iter().find(|a| a.id == *id);
+            clock.advance((time - clock.now()) as f32);
+            airport
+                .tick(&config, &wd, lookup, &clock, &mut rng)
+                .into_iter()
+                .filter(|(_, a)| matches!(a.payload, PlaneEventPayload::ClearForTakeoff(_)))
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>()
+        };
+
+        // one departure at a time
+        let (first, second) = (planes[0].id, planes[1].id);
+        assert_eq!(tick_at(&mut airport, &planes, 0.0), [first]);
+        assert_eq!(airport.runways[0].occupant, Some(first));
+
+        // a smaller plane waits out the wake of a larger one
+        planes.remove(0);
+        assert!(tick_at(&mut airport, &planes, 1.0).is_empty());
+        assert!(tick_at(&mut airport, &planes, 119.0).is_empty());
+        assert_eq!(tick_at(&mut airport, &planes, 120.0), [second]);
+
+        // and planes to the same airport are spaced apart too
+        planes.push(depart("S", "BAR", &Clock::default()));
+        let third = planes[1].id;
+        request(&mut airport, &planes[1], &Clock::default());
+        planes.remove(0);
+        assert!(tick_at(&mut airport, &planes, 121.0).is_empty());
+        assert!(tick_at(&mut airport, &planes, 179.0).is_empty());
+        assert_eq!(tick_at(&mut airport, &planes, 180.0), [third]);
+        assert_eq!(
+            airport.runways[0].last_departure.as_ref().map(|a| a.plane),
+            Some(third)
+        );
+    }
+    #[test]
+    fn diverts_planes_no_runway_fits() {
+        // too long to land on any runway of the world
+        let mut wd = world();
+        wd.planes = Arc::new([Arc::new(PlaneData {
+            min_landing_distance: 100.0,
+            ..(*wd.planes[0]).clone()
+        })]);
+        let [foo, bar] = [0, 1].map(|i| Arc::clone(&wd.airports[i]));
+        let config = Config::default();
+        let clock = Clock::default();
+        let mut rng = SimRng::seed_from_u64(0);
+        let flight = Arc::new(Flight {
+            from: "FOO".into(),
+            to: "BAR".into(),
+            ..Flight::default()
+        });
+        let mut plane = Plane::new(
+            &wd.planes[0],
+            &flight,
+            &foo.runways[0],
+            &config,
+            &wd,
+            &clock,
+            &mut rng,
+        );
+        plane.phase = PhaseData::Descent;
+        let mut airport = Airport::new(Arc::clone(&bar));
+        airport.events.push_back(AirportEvent {
+            from: plane.id,
+            time: clock.now(),
+            payload: AirportEventPayload::RequestRunway,
+        });
+
+        // with nowhere else to go, it holds in case the world changes
+        let lookup = |id: &PlaneStateId| (*id == plane.id).then_some(&plane);
+        let send = airport.tick(&config, &wd, lookup, &clock, &mut rng);
+        assert!(matches!(
+            send[..],
+            [(
+                _,
+                PlaneEvent {
+                    payload: PlaneEventPayload::Hold { .. },
+                    ..
+                }
+            )]
+        ));
+        assert_eq!(airport.queue.len(), 1);
+
+        wd.airports = Arc::new([
+            foo,
+            bar,
+            Arc::new(AirportData {
+                code: "BAZ".into(),
+                runways: Arc::new([Arc::new(Runway {
+                    start: Pos2::new(2000.0, 0.0),
+                    end: Pos2::new(2000.0, 200.0),
+                    ..Runway::default()
+                })]),
+                ..AirportData::default()
+            }),
+        ]);
+        let send = airport.tick(&config, &wd, lookup, &clock, &mut rng);
+        let [(id, event)] = &*send else {
+            panic!("{send:?}");
+        };
+        assert_eq!(*id, plane.id);
+        assert!(matches!(&event.payload, PlaneEventPayload::Divert(to) if to.code == "BAZ"));
+        assert!(airport.queue.is_empty());
+        assert!(airport.holding.is_empty());
+
+        plane.events.push_back(event.clone());
+        plane.tick(&config, &wd, &clock, &mut rng);
+        assert_eq!(plane.flight.to, "BAZ");
+        assert!(matches!(plane.phase, PhaseData::Cruise));
+    }
 }
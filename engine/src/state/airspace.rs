@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use tracing::warn;
+use ts_rs::TS;
+
+use crate::{
+    airspace::AirspaceKind,
+    journal::{JournalEntry, Record},
+    state::{plane::Plane, State},
+    util::PlaneStateId,
+    world_data::WorldData,
+};
+
+/// A plane inside a volume of active airspace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Infringement {
+    #[ts(as = "String")]
+    pub plane: PlaneStateId,
+    #[ts(as = "String")]
+    pub airspace: SmolStr,
+    pub kind: AirspaceKind,
+}
+
+/// Finds every plane inside airspace active at `time`, on the ground or in the air.
+#[must_use]
+pub fn detect(planes: &[Plane], wd: &WorldData, time: f64) -> Vec<Infringement> {
+    let airspace = wd.airspace_at(time);
+    planes
+        .iter()
+        .flat_map(|plane| {
+            airspace
+                .iter()
+                .filter(|a| a.contains(plane.pos.pos_ang.0))
+                .map(|a| Infringement {
+                    plane: plane.id,
+                    airspace: a.name.clone(),
+                    kind: a.kind,
+                })
+        })
+        .collect()
+}
+
+impl State {
+    /// Replaces [`Self::infringements`] with the ones found now, counting and journaling those
+    /// that were not there on the last tick.
+    pub(super) fn tick_infringements(&mut self, wd: &WorldData) {
+        let infringements = detect(&self.planes, wd, self.clock.elapsed);
+        for infringement in &infringements {
+            if self
+                .infringements
+                .iter()
+                .any(|a| a.plane == infringement.plane && a.airspace == infringement.airspace)
+            {
+                continue;
+            }
+            warn!(%infringement.plane, %infringement.airspace, "Airspace infringed");
+            self.stats.infringements += 1;
+            self.journal.push(JournalEntry {
+                tick: self.clock.tick,
+                record: Record::Infringement {
+                    plane: infringement.plane,
+                    airspace: infringement.airspace.clone(),
+                },
+            });
+        }
+        self.infringements = infringements;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        airspace::{Activation, Airspace},
+        config::Config,
+        fixtures::world,
+        state::{clock::Clock, plane::PhaseData},
+        util::{angle::Angle, pos::Pos3Angle, Pos2},
+        weather::CellShape,
+        world_data::Flight,
+    };
+
+    #[test]
+    fn infringements() {
+        let mut wd = world();
+        wd.airspace = Arc::new([Arc::new(Airspace {
+            name: "P1".into(),
+            kind: AirspaceKind::Prohibited,
+            shape: CellShape::Polygon {
+                points: Arc::new([
+                    Pos2::new(400.0, 0.0),
+                    Pos2::new(600.0, 0.0),
+                    Pos2::new(600.0, 200.0),
+                    Pos2::new(400.0, 200.0),
+                ]),
+            },
+            floor: 0.0,
+            ceiling: 2000.0,
+            schedule: Arc::new([Activation {
+                start: 10.0,
+                end: 100.0,
+            }]),
+            period: None,
+        })]);
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let flight = Arc::new(Flight {
+            from: "FOO".into(),
+            to: "BAR".into(),
+            ..Flight::default()
+        });
+        for (x, z) in [(500.0, 1000.0), (500.0, 3000.0), (300.0, 1000.0)] {
+            let mut plane = Plane::new(
+                &wd.planes[0],
+                &flight,
+                &wd.airports[0].runways[0],
+                &Config::default(),
+                &wd,
+                &state.clock,
+                &mut state.rng,
+            );
+            plane.pos.pos_ang = Pos3Angle(Pos2::new(x, 100.0).extend(z), Angle(0.0));
+            plane.phase = PhaseData::Cruise;
+            state.planes.push(plane);
+        }
+        state.reindex();
+        let inside = state.planes[0].id;
+
+        // not active yet
+        state.tick_infringements(&wd);
+        assert!(state.infringements.is_empty());
+
+        // only the plane inside the outline and below the ceiling is in it, and it is only
+        // counted once for as long as it stays there
+        state.clock.elapsed = 20.0;
+        state.tick_infringements(&wd);
+        state.tick_infringements(&wd);
+        assert_eq!(
+            state.infringements,
+            [Infringement {
+                plane: inside,
+                airspace: "P1".into(),
+                kind: AirspaceKind::Prohibited,
+            }]
+        );
+        assert_eq!(state.stats.infringements, 1);
+        assert_eq!(
+            state
+                .journal
+                .iter()
+                .filter(|a| matches!(a.record, Record::Infringement { .. }))
+                .count(),
+            1
+        );
+
+        state.clock.elapsed = 200.0;
+        state.tick_infringements(&wd);
+        assert!(state.infringements.is_empty());
+    }
+}
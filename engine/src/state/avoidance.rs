@@ -0,0 +1,225 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use tracing::info;
+
+use crate::{
+    config::Config,
+    journal::{JournalEntry, Record},
+    state::{
+        conflict,
+        plane::{Advisory, PhaseData, Plane, Sense},
+        State,
+    },
+    terrain::Terrain,
+    util::PlaneStateId,
+    world_data::WorldData,
+};
+
+impl Sense {
+    #[must_use]
+    pub const fn rev(self) -> Self {
+        match self {
+            Self::Climb => Self::Descend,
+            Self::Descend => Self::Climb,
+        }
+    }
+}
+
+impl Plane {
+    /// Only cruising planes are free to leave their altitude.
+    const fn can_manoeuvre(&self) -> bool {
+        matches!(self.phase, PhaseData::Cruise)
+    }
+    fn advise(&mut self, advisory: Advisory, config: &Config) {
+        let ds = match advisory.sense {
+            Sense::Climb => config.separation_vertical,
+            Sense::Descend => -config.separation_vertical,
+        };
+        self.pos
+            .kinematics
+            .target_y(Some(0.0), Some(ds), None, None, self.model.motion);
+        self.advisory = Some(advisory);
+    }
+    fn clear_of_conflict(&mut self, config: &Config, terrain: &Terrain) {
+        self.advisory = None;
+        if self.can_manoeuvre() {
+            self.pos.kinematics.target_y(
+                Some(0.0),
+                Some(self.cruising_altitude(config, terrain) - self.pos.pos_ang.0.z),
+                None,
+                None,
+                self.model.motion,
+            );
+        }
+    }
+}
+
+impl State {
+    /// Orders both planes of a conflict due within [`Config::advisory_time`] apart vertically,
+    /// and returns planes to their cruising altitude once they are past the plane they avoided
+    /// and nothing else conflicts with them.
+    pub(super) fn tick_avoidance(&mut self, config: &Config, wd: &WorldData) {
+        if !config.collision_avoidance {
+            return;
+        }
+        let conflicts = self
+            .conflicts
+            .iter()
+            .filter(|a| a.time_to_closest <= config.advisory_time)
+            .map(|a| a.planes)
+            .collect::<Vec<_>>();
+        for (a, b) in conflicts {
+            let (Some(plane_a), Some(plane_b)) = (self.plane(&a), self.plane(&b)) else {
+                continue;
+            };
+            let sense_a = match (plane_a.advisory, plane_b.advisory) {
+                (None, None) => {
+                    // the higher plane climbs, and the lower one descends
+                    let (za, zb) = (plane_a.pos.pos_ang.0.z, plane_b.pos.pos_ang.0.z);
+                    match za.total_cmp(&zb).then(b.cmp(&a)) {
+                        Ordering::Greater => Sense::Climb,
+                        _ => Sense::Descend,
+                    }
+                }
+                (Some(advisory), None) => advisory.sense,
+                (None, Some(advisory)) => advisory.sense.rev(),
+                (Some(_), Some(_)) => continue,
+            };
+            self.advise(a, b, sense_a, config);
+            self.advise(b, a, sense_a.rev(), config);
+        }
+
+        let in_conflict = self
+            .conflicts
+            .iter()
+            .flat_map(|a| [a.planes.0, a.planes.1])
+            .collect::<HashSet<_>>();
+        let clear = self
+            .planes
+            .iter()
+            .filter(|plane| {
+                plane.advisory.is_some_and(|advisory| {
+                    !in_conflict.contains(&plane.id)
+                        && self.plane(&advisory.against).is_none_or(|against| {
+                            conflict::diverging(plane, against, wd, self.clock.elapsed)
+                        })
+                })
+            })
+            .map(|a| a.id)
+            .collect::<Vec<_>>();
+        for id in clear {
+            let Some(plane) = self.plane_mut(&id) else {
+                continue;
+            };
+            info!(%id, "Clear of conflict");
+            plane.clear_of_conflict(config, &wd.terrain);
+            self.journal.push(JournalEntry {
+                tick: self.clock.tick,
+                record: Record::ClearOfConflict { plane: id },
+            });
+        }
+    }
+    fn advise(&mut self, id: PlaneStateId, against: PlaneStateId, sense: Sense, config: &Config) {
+        let Some(plane) = self.plane_mut(&id) else {
+            return;
+        };
+        if plane.advisory.is_some() || !plane.can_manoeuvre() {
+            return;
+        }
+        info!(%id, %against, ?sense, "Resolution advisory");
+        plane.advise(Advisory { against, sense }, config);
+        self.stats.advisories += 1;
+        self.journal.push(JournalEntry {
+            tick: self.clock.tick,
+            record: Record::Advisory {
+                plane: id,
+                against,
+                sense,
+            },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, f32::consts::PI, sync::Arc};
+
+    use glam::Vec2;
+
+    use super::*;
+    use crate::{
+        fixtures::world,
+        state::{clock::Clock, plane_pos::FlightInstruction},
+        util::{angle::Angle, pos::Pos3Angle, ray::Ray, Pos2, Pos3},
+        world_data::Flight,
+    };
+
+    #[test]
+    fn head_on() {
+        let wd = world();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let flight = Arc::new(Flight {
+            from: "FOO".into(),
+            to: "BAR".into(),
+            ..Flight::default()
+        });
+        // two planes flying towards each other at the same level, 2000 apart
+        for (x, heading) in [(0.0, 0.0), (2000.0, PI)] {
+            let mut plane = Plane::new(
+                &wd.planes[0],
+                &flight,
+                &wd.airports[0].runways[0],
+                &Config::default(),
+                &wd,
+                &state.clock,
+                &mut state.rng,
+            );
+            let pos = Pos2::new(x, 500.0);
+            plane.pos.pos_ang = Pos3Angle(pos.extend(1000.0), Angle(heading));
+            plane.pos.kinematics.x_target.clear();
+            plane.pos.kinematics.v = Vec2::new(50.0, 0.0);
+            plane.pos.planner.route.clear();
+            plane.pos.planner.instructions = VecDeque::from([FlightInstruction::Straight(Ray {
+                tail: pos,
+                vec: Angle(heading).vec() * 10000.0,
+            })]);
+            plane.phase = PhaseData::Cruise;
+            state.planes.push(plane);
+        }
+        state.reindex();
+        let config = Config {
+            plane_spawn_chance: 0.0,
+            ..Config::default()
+        };
+
+        let mut closest = Pos3::ZERO;
+        for _ in 0..60 {
+            state.tick(&config, &wd);
+            assert!(state.conflicts.iter().all(|a| !a.current));
+            let d = state.planes[1].pos.pos_ang.0 - state.planes[0].pos.pos_ang.0;
+            if d.truncate().length() < 50.0 {
+                closest = d;
+            }
+        }
+        assert!(closest.z.abs() >= config.separation_vertical);
+
+        let senses = state
+            .journal
+            .iter()
+            .filter_map(|a| match a.record {
+                Record::Advisory { sense, .. } => Some(sense),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(senses.len(), 2);
+        assert_eq!(senses[0], senses[1].rev());
+        let clears = state
+            .journal
+            .iter()
+            .filter(|a| matches!(a.record, Record::ClearOfConflict { .. }))
+            .count();
+        assert_eq!(clears, 2);
+        assert_eq!(state.stats.advisories, 2);
+        assert!(state.planes.iter().all(|a| a.advisory.is_none()));
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+)]
+#[ts(export)]
+pub struct Clock {
+    #[ts(type = "number")]
+    pub tick: u64,
+    pub elapsed: f64,
+    #[ts(type = "number")]
+    pub epoch: u64,
+}
+
+impl Clock {
+    #[must_use]
+    pub fn new(epoch: Option<u64>) -> Self {
+        Self {
+            tick: 0,
+            elapsed: 0.0,
+            epoch: epoch.unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+            }),
+        }
+    }
+    /// Simulated time as seconds since the Unix epoch
+    #[must_use]
+    pub fn now(&self) -> f64 {
+        self.epoch as f64 + self.elapsed
+    }
+    pub fn advance(&mut self, dt: f32) {
+        self.tick += 1;
+        self.elapsed += f64::from(dt);
+    }
+}
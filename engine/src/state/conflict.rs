@@ -0,0 +1,194 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    config::Config,
+    state::plane::{PhaseData, Plane},
+    util::PlaneStateId,
+    world_data::WorldData,
+};
+
+/// Two planes that have lost, or are predicted to lose, separation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Conflict {
+    #[ts(as = "(String, String)")]
+    pub planes: (PlaneStateId, PlaneStateId),
+    /// Seconds until the planes are closest while in conflict
+    pub time_to_closest: f32,
+    /// Horizontal distance between the planes when they are closest
+    pub min_distance: f32,
+    /// Vertical distance between the planes when they are closest
+    pub vertical_distance: f32,
+    /// Whether separation is already lost, rather than predicted to be
+    pub current: bool,
+}
+
+/// Where a plane is and where it is going, assumed to stay constant while probing.
+#[derive(Clone, Copy, Debug)]
+struct Track {
+    id: PlaneStateId,
+    pos: Vec2,
+    z: f32,
+    v: Vec2,
+    vz: f32,
+}
+
+impl Track {
+    fn new(plane: &Plane, wd: &WorldData, time: f64) -> Self {
+        Self {
+            id: plane.id,
+            pos: plane.pos.pos_ang.0.truncate(),
+            z: plane.pos.pos_ang.0.z,
+            v: plane.pos.ground_velocity(plane.wind(wd, time)),
+            vz: plane.pos.kinematics.v.y,
+        }
+    }
+    fn probe(&self, other: &Self, config: &Config) -> Option<Conflict> {
+        let (dp, dv) = (other.pos - self.pos, other.v - self.v);
+        let (dz, dvz) = (other.z - self.z, other.vz - self.vz);
+        if dv
+            .length()
+            .mul_add(-config.conflict_look_ahead, dp.length())
+            > config.separation_horizontal
+        {
+            return None;
+        }
+
+        // times at which the planes are within each minimum, solving |dp + dv t| < h and
+        // |dz + dvz t| < v
+        let horizontal = if dv.length_squared() <= f32::EPSILON {
+            (dp.length() < config.separation_horizontal).then_some((f32::MIN, f32::MAX))
+        } else {
+            let (a, b) = (dv.length_squared(), dp.dot(dv));
+            let disc = b.mul_add(
+                b,
+                -a * config
+                    .separation_horizontal
+                    .mul_add(-config.separation_horizontal, dp.length_squared()),
+            );
+            (disc > 0.0).then(|| ((-b - disc.sqrt()) / a, (-b + disc.sqrt()) / a))
+        }?;
+        let vertical = if dvz.abs() <= f32::EPSILON {
+            (dz.abs() < config.separation_vertical).then_some((f32::MIN, f32::MAX))
+        } else {
+            let t0 = (-config.separation_vertical - dz) / dvz;
+            let t1 = (config.separation_vertical - dz) / dvz;
+            Some((t0.min(t1), t0.max(t1)))
+        }?;
+        let start = horizontal.0.max(vertical.0).max(0.0);
+        let end = horizontal.1.min(vertical.1).min(config.conflict_look_ahead);
+        if start >= end {
+            return None;
+        }
+
+        let closest = if dv.length_squared() <= f32::EPSILON {
+            start
+        } else {
+            (-dp.dot(dv) / dv.length_squared()).clamp(start, end)
+        };
+        Some(Conflict {
+            planes: (self.id, other.id),
+            time_to_closest: closest,
+            min_distance: (dp + dv * closest).length(),
+            vertical_distance: dvz.mul_add(closest, dz).abs(),
+            current: start <= 0.0,
+        })
+    }
+}
+
+/// Whether two planes are moving apart horizontally, i.e. are past their closest approach.
+#[must_use]
+pub fn diverging(a: &Plane, b: &Plane, wd: &WorldData, time: f64) -> bool {
+    let (a, b) = (Track::new(a, wd, time), Track::new(b, wd, time));
+    (b.pos - a.pos).dot(b.v - a.v) > 0.0
+}
+
+/// Probes every pair of airborne planes for losses of separation.
+///
+/// Each plane is assumed to keep its velocity over the ground, `time` seconds in, for
+/// [`Config::conflict_look_ahead`]. Planes taking off, landing or on the ground are left to the airport
+/// to separate.
+#[must_use]
+pub fn detect(planes: &[Plane], config: &Config, wd: &WorldData, time: f64) -> Vec<Conflict> {
+    let tracks = planes
+        .iter()
+        .filter(|a| {
+            !a.on_ground()
+                && !matches!(
+                    a.phase,
+                    PhaseData::Takeoff { .. } | PhaseData::Landing { .. }
+                )
+        })
+        .map(|a| Track::new(a, wd, time))
+        .collect::<Vec<_>>();
+    tracks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| {
+            tracks[i + 1..]
+                .iter()
+                .filter_map(move |b| a.probe(b, config))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(x: f32, y: f32, z: f32, v: Vec2, vz: f32) -> Track {
+        Track {
+            id: PlaneStateId::nil(),
+            pos: Vec2::new(x, y),
+            z,
+            v,
+            vz,
+        }
+    }
+
+    #[test]
+    fn probe() {
+        let config = Config {
+            separation_horizontal: 50.0,
+            separation_vertical: 100.0,
+            conflict_look_ahead: 60.0,
+            ..Config::default()
+        };
+        let east = Vec2::new(10.0, 0.0);
+
+        // head-on at the same level, closest in 50 s
+        let conflict = track(0.0, 0.0, 500.0, east, 0.0)
+            .probe(&track(1000.0, 0.0, 500.0, -east, 0.0), &config)
+            .unwrap();
+        assert!(!conflict.current);
+        assert!((conflict.time_to_closest - 50.0).abs() < 1e-3);
+        assert!(conflict.min_distance < 1e-3);
+
+        // the same, but beyond the look-ahead
+        assert!(track(0.0, 0.0, 500.0, east, 0.0)
+            .probe(&track(2000.0, 0.0, 500.0, -east, 0.0), &config)
+            .is_none());
+
+        // head-on, but 200 apart vertically
+        assert!(track(0.0, 0.0, 500.0, east, 0.0)
+            .probe(&track(1000.0, 0.0, 700.0, -east, 0.0), &config)
+            .is_none());
+
+        // one climbing through the level of the other, whom it follows too closely already
+        let conflict = track(0.0, 0.0, 300.0, east, 5.0)
+            .probe(&track(30.0, 0.0, 500.0, east, 0.0), &config)
+            .unwrap();
+        assert!(!conflict.current);
+        assert!((conflict.time_to_closest - 20.0).abs() < 1e-3);
+        assert!((conflict.min_distance - 30.0).abs() < 1e-3);
+
+        // parallel and within both minima
+        let conflict = track(0.0, 0.0, 500.0, east, 0.0)
+            .probe(&track(0.0, 40.0, 550.0, east, 0.0), &config)
+            .unwrap();
+        assert!(conflict.current);
+        assert!(conflict.time_to_closest.abs() < 1e-3);
+    }
+}
@@ -0,0 +1,66 @@
+use smol_str::SmolStr;
+
+use crate::{
+    state::{
+        plane::{GoAroundReason, PhaseData, Plane, PlaneEvent, PlaneEventPayload},
+        State,
+    },
+    util::AirportCode,
+};
+
+impl Plane {
+    /// The airport and runway the plane is taking off from or landing on, if any.
+    fn runway_in_use(&self) -> Option<(&AirportCode, &str)> {
+        match &self.phase {
+            PhaseData::Takeoff { runway } => Some((&self.flight.from, &runway.name)),
+            PhaseData::Landing { runway } => Some((&self.flight.to, &runway.name)),
+            _ => None,
+        }
+    }
+    /// Whether the plane is on the ground, or just above it, between the ends of its runway.
+    fn on_runway(&self) -> bool {
+        let (PhaseData::Takeoff { runway } | PhaseData::Landing { runway }) = &self.phase else {
+            return false;
+        };
+        runway.distance(self.pos.pos_ang.0.truncate()) <= 1.0
+            && self.pos.pos_ang.0.z - runway.altitude <= 1.0
+    }
+}
+
+impl State {
+    /// Has airports tell planes on approach to go around if another plane is on their runway.
+    pub(super) fn tick_go_arounds(&mut self) {
+        let occupied = self
+            .planes
+            .iter()
+            .filter(|a| a.on_runway())
+            .filter_map(|a| {
+                let (airport, runway) = a.runway_in_use()?;
+                Some((a.id, airport.clone(), SmolStr::from(runway)))
+            })
+            .collect::<Vec<_>>();
+        if occupied.is_empty() {
+            return;
+        }
+        let now = self.clock.now();
+        for plane in &mut self.planes {
+            if !matches!(plane.phase, PhaseData::Landing { .. }) || plane.on_runway() {
+                continue;
+            }
+            let Some((airport, runway)) = plane.runway_in_use() else {
+                continue;
+            };
+            if !occupied
+                .iter()
+                .any(|(id, a, r)| *id != plane.id && a == airport && r == runway)
+            {
+                continue;
+            }
+            plane.events.push_back(PlaneEvent {
+                from: airport.clone(),
+                time: now,
+                payload: PlaneEventPayload::GoAround(GoAroundReason::RunwayOccupied),
+            });
+        }
+    }
+}
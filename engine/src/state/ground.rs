@@ -0,0 +1,446 @@
+use std::{f32::consts::FRAC_1_SQRT_2, sync::Arc};
+
+use glam::Vec2;
+use tracing::{debug, warn};
+
+use crate::{
+    config::Config,
+    state::{
+        airport::AirportEventPayload,
+        plane::{PhaseData, Plane},
+        plane_pos::FlightInstruction,
+        State,
+    },
+    util::{angle::Angle, pos::Pos3Angle, ray::Ray, AirportStateId, PlaneStateId, Pos2},
+    world_data::{Gate, Runway, WorldData},
+};
+
+impl Plane {
+    /// Whether the plane is moving about an airport on the ground.
+    #[must_use]
+    pub const fn taxiing(&self) -> bool {
+        matches!(
+            self.phase,
+            PhaseData::Pushback { .. }
+                | PhaseData::TaxiOut { .. }
+                | PhaseData::HoldingPoint { .. }
+                | PhaseData::TaxiIn { .. }
+        )
+    }
+    #[must_use]
+    pub const fn on_ground(&self) -> bool {
+        self.taxiing() || matches!(self.phase, PhaseData::Parked { .. })
+    }
+    /// Parks the plane nose in at `gate`, to be pushed back onto the taxiway at `taxiway`, and then
+    /// taxi to the holding point of `runway`.
+    pub fn park(&mut self, gate: &Arc<Gate>, taxiway: Pos2, runway: &Arc<Runway>) {
+        self.pos.pos_ang = Pos3Angle(
+            gate.pos.extend(runway.altitude),
+            Angle((gate.pos - taxiway).to_angle()),
+        );
+        self.pos.planner.instructions =
+            [FlightInstruction::Straight(Ray::new(gate.pos, taxiway))].into();
+        self.pos.planner.instruction_s = 0.0;
+        self.pos.kinematics.x_target.clear();
+        self.pos.kinematics.v = Vec2::ZERO;
+        self.phase = PhaseData::Pushback {
+            gate: Arc::clone(gate),
+            runway: Arc::clone(runway),
+        };
+    }
+    /// Stops the plane at the start of `runway`, where it waits for takeoff clearance at
+    /// airports without taxiways.
+    pub fn hold_short(&mut self, runway: &Arc<Runway>) {
+        self.pos.planner.instructions.clear();
+        self.pos.planner.instruction_s = 0.0;
+        self.pos.kinematics.x_target.clear();
+        self.pos.kinematics.v = Vec2::ZERO;
+        self.phase = PhaseData::TaxiOut {
+            runway: Arc::clone(runway),
+        };
+    }
+    /// Whether the plane is still at its gate, not yet pushed back.
+    fn awaiting_pushback(&self) -> bool {
+        matches!(self.phase, PhaseData::Pushback { .. })
+            && self.taxi_hold.is_none()
+            && self.pos.planner.instruction_s == 0.0
+            && self.pos.kinematics.v.x == 0.0
+            && self.pos.kinematics.x_target.is_empty()
+    }
+    /// Sets off at taxi speed, stopping at the end of the pushback, at the holding point or at the
+    /// gate.
+    fn taxi(&mut self, config: &Config) {
+        match self.phase {
+            PhaseData::Pushback { .. } | PhaseData::TaxiOut { .. } | PhaseData::TaxiIn { .. } => {
+                let ds = self
+                    .pos
+                    .planner
+                    .instructions
+                    .iter()
+                    .map(FlightInstruction::length)
+                    .sum::<f32>()
+                    - self.pos.planner.instruction_s;
+                self.pos.kinematics.target_x(
+                    Some(0.0),
+                    Some(ds.max(0.0)),
+                    None,
+                    Some(config.taxi_speed),
+                    self.model.motion,
+                );
+            }
+            _ => {}
+        }
+    }
+    fn stop(&mut self) {
+        self.pos
+            .kinematics
+            .target_x(Some(0.0), None, None, None, self.model.motion);
+    }
+    /// Direction the plane is moving in, which is backwards while it is being pushed back.
+    fn track(&self) -> Vec2 {
+        let heading = self.pos.pos_ang.1.vec();
+        if matches!(self.phase, PhaseData::Pushback { .. }) {
+            -heading
+        } else {
+            heading
+        }
+    }
+    /// Whether a landing plane has slowed down or run out of runway, and is vacating it.
+    fn rolled_out(&self) -> bool {
+        matches!(self.phase, PhaseData::Landing { .. })
+            && (self.pos.planner.instructions.is_empty()
+                || self.pos.kinematics.v.x < self.model.motion.max_v.x / 10.0)
+    }
+    fn follow_taxi_route(&mut self, points: &[Pos2], phase: PhaseData, config: &Config, now: f64) {
+        self.pos.planner.instructions =
+            FlightInstruction::taxi(points, config.taxi_turning_radius).into();
+        self.pos.planner.instruction_s = 0.0;
+        self.set_phase(phase, now);
+        self.taxi(config);
+    }
+}
+
+impl State {
+    /// Moves planes between the runways and the gates of airports with taxiways, and has taxiing
+    /// planes wait behind the plane ahead of them.
+    /// Returns planes that have nowhere to go after their turnaround, which are removed.
+    pub(super) fn tick_ground(&mut self, config: &Config, wd: &WorldData) -> Vec<PlaneStateId> {
+        self.taxi_out(config, wd);
+        self.taxi_in(config, wd);
+        self.tick_taxi_holds(config);
+        self.tick_turnarounds(config, wd)
+    }
+    /// Parks a new departure at a free gate of an airport with taxiways, or holds it short of
+    /// `runway` otherwise. Returns `false` if no gate the plane fits is free, or arrivals are waiting for
+    /// one.
+    pub(super) fn place_departure(
+        &mut self,
+        plane: &mut Plane,
+        runway: &Arc<Runway>,
+        wd: &WorldData,
+    ) -> bool {
+        let from = plane.flight.from.clone();
+        let mut rng = self.rng.fork();
+        let Some(airport) = self.airport(&from).filter(|a| a.airport.has_ground()) else {
+            plane.hold_short(runway);
+            return true;
+        };
+        if airport
+            .queue
+            .iter()
+            .any(|a| matches!(a.payload, AirportEventPayload::RequestRunway))
+        {
+            debug!(%from, "Arrivals waiting, no departure");
+            return false;
+        }
+        let data = Arc::clone(&airport.airport);
+        let Some(gate) = airport.allocate_gate(wd, plane, &mut rng) else {
+            debug!(%from, "No gate free for departure");
+            return false;
+        };
+        if let Some(taxiway) = data.taxiway(&gate.taxiway) {
+            plane.park(&gate, taxiway.pos, runway);
+        }
+        if let Some(airport) = self.airport_mut(&from) {
+            airport.occupy_gate(&gate.name, plane.id);
+        }
+        true
+    }
+    /// Whether a departure is waiting at the start of `runway` of `airport`, or on its way there.
+    pub(super) fn holding_short(&self, airport: &AirportStateId, runway: &Runway) -> bool {
+        self.planes.iter().any(|a| {
+            a.flight.from == *airport
+                && matches!(
+                    &a.phase,
+                    PhaseData::TaxiOut { runway: r } | PhaseData::HoldingPoint { runway: r }
+                        if r.name == runway.name
+                )
+        })
+    }
+    /// Pushes planes back from their gates, and sends them to the holding point of their runway,
+    /// the taxiway node nearest to its start, once they have been pushed back.
+    fn taxi_out(&mut self, config: &Config, wd: &WorldData) {
+        let now = self.clock.now();
+        for i in 0..self.planes.len() {
+            let plane = &self.planes[i];
+            let PhaseData::Pushback { runway, .. } = &plane.phase else {
+                continue;
+            };
+            if plane.taxi_hold.is_some() || !plane.pos.kinematics.x_target.is_empty() {
+                continue;
+            }
+            let Some(airport) = self.airport(&plane.flight.from) else {
+                continue;
+            };
+            let (id, runway) = (plane.id, Arc::clone(runway));
+            if plane.awaiting_pushback() {
+                self.planes[i].taxi(config);
+                continue;
+            }
+            let pos = plane.pos.pos_ang.0.truncate();
+            let points = match wd.taxi_route(&airport.airport, pos, runway.start) {
+                Some(mut points) if points.len() > 2 => {
+                    points.pop();
+                    points
+                }
+                Some(points) => points,
+                None => {
+                    warn!(%id, "No taxi route to runway");
+                    vec![pos, runway.start]
+                }
+            };
+            self.planes[i].follow_taxi_route(&points, PhaseData::TaxiOut { runway }, config, now);
+        }
+    }
+    /// Sends landed planes to the gate they were given when cleared to land, or else to a free
+    /// one, if there is one.
+    fn taxi_in(&mut self, config: &Config, wd: &WorldData) {
+        let now = self.clock.now();
+        let landed = self
+            .planes
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.rolled_out())
+            .filter_map(|(i, a)| {
+                let airport = self.airport(&a.flight.to)?;
+                airport
+                    .airport
+                    .has_ground()
+                    .then(|| (i, airport.id.clone()))
+            })
+            .collect::<Vec<_>>();
+        for (i, airport) in landed {
+            let mut rng = self.rng.fork();
+            let Some(gate) = self
+                .airport(&airport)
+                .and_then(|a| a.allocate_gate(wd, &self.planes[i], &mut rng))
+            else {
+                debug!(plane = %self.planes[i].id, "No gate free");
+                continue;
+            };
+            let id = self.planes[i].id;
+            self.record_landing(id);
+            let Some(airport) = self.airport_mut(&airport) else {
+                continue;
+            };
+            airport.occupy_gate(&gate.name, id);
+            let data = Arc::clone(&airport.airport);
+
+            let plane = &mut self.planes[i];
+            let pos = plane.pos.pos_ang.0.truncate();
+            let Some(taxiway) = data.taxiway(&gate.taxiway).map(|a| a.pos) else {
+                continue;
+            };
+            let mut points = wd
+                .taxi_route(&data, pos, taxiway)
+                .unwrap_or_else(|| vec![pos, taxiway]);
+            points.push(gate.pos);
+            if let PhaseData::Landing { runway } = &plane.phase {
+                plane.pos.pos_ang.0.z = runway.altitude;
+            }
+            plane.pos.kinematics.y_target.clear();
+            plane.pos.kinematics.v.y = 0.0;
+            plane.follow_taxi_route(&points, PhaseData::TaxiIn { gate }, config, now);
+        }
+    }
+    /// Stops taxiing planes within [`Config::taxi_separation`] behind another, and sets them off
+    /// again once it has moved on. Of two planes facing each other, the one with the lower id
+    /// goes first.
+    fn tick_taxi_holds(&mut self, config: &Config) {
+        let taxiing = self
+            .planes
+            .iter()
+            .filter(|a| a.taxiing() && !a.awaiting_pushback())
+            .map(|a| (a.id, a.pos.pos_ang.0.truncate(), a.track()))
+            .collect::<Vec<_>>();
+        let ahead = |(pos, track): (Pos2, Vec2), other: Pos2| {
+            let d = other - pos;
+            d.length() < config.taxi_separation && d.dot(track) > d.length() * FRAC_1_SQRT_2
+        };
+        for plane in &mut self.planes {
+            if !plane.taxiing() || plane.awaiting_pushback() {
+                plane.taxi_hold = None;
+                continue;
+            }
+            let (pos, track) = (plane.pos.pos_ang.0.truncate(), plane.track());
+            let blocker = taxiing
+                .iter()
+                .filter(|(id, other, other_track)| {
+                    *id != plane.id
+                        && ahead((pos, track), *other)
+                        && !(plane.id < *id && ahead((*other, *other_track), pos))
+                })
+                .min_by(|(_, a, _), (_, b, _)| a.distance(pos).total_cmp(&b.distance(pos)))
+                .map(|(id, ..)| *id);
+            match (blocker, plane.taxi_hold) {
+                (Some(blocker), None) => {
+                    debug!(%plane.id, %blocker, "Waiting for plane ahead");
+                    plane.stop();
+                    plane.taxi_hold = Some(blocker);
+                    self.stats.taxi_holds += 1;
+                }
+                (None, Some(_)) => {
+                    plane.taxi_hold = None;
+                    plane.taxi(config);
+                }
+                (Some(blocker), Some(_)) => plane.taxi_hold = Some(blocker),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        fixtures::world,
+        state::clock::Clock,
+        util::WaypointId,
+        world_data::{Flight, Waypoint},
+    };
+
+    fn taxiway(name: &str, x: f32, y: f32, connections: &[&str]) -> Arc<Waypoint> {
+        Arc::new(Waypoint {
+            name: name.into(),
+            pos: Pos2::new(x, y),
+            connections: connections.iter().map(|a| WaypointId::from(*a)).collect(),
+        })
+    }
+
+    /// [`world`] with taxiways and two gates at FOO, and flights from FOO back to FOO.
+    pub fn ground_world() -> WorldData {
+        let mut wd = world();
+        let [foo, bar] = &*wd.airports else {
+            unreachable!()
+        };
+        let (mut foo, bar) = ((**foo).clone(), Arc::clone(bar));
+        foo.taxiways = Arc::new([
+            taxiway("A1", -20.0, -5.0, &["A2"]),
+            taxiway("A2", -20.0, 25.0, &["A1", "A3"]),
+            taxiway("A3", -20.0, 55.0, &["A2"]),
+        ]);
+        foo.gates = Arc::new([
+            Arc::new(Gate {
+                name: "1".into(),
+                pos: Pos2::new(-35.0, 25.0),
+                taxiway: "A2".into(),
+                ..Gate::default()
+            }),
+            Arc::new(Gate {
+                name: "2".into(),
+                pos: Pos2::new(-35.0, 55.0),
+                taxiway: "A3".into(),
+                ..Gate::default()
+            }),
+        ]);
+        wd.airports = Arc::new([Arc::new(foo), bar]);
+        wd.flights = Some(Arc::new([Arc::new(Flight {
+            from: "FOO".into(),
+            to: "FOO".into(),
+            plane: Arc::new(["A320".into()]),
+            ..Flight::default()
+        })]));
+        wd
+    }
+
+    #[test]
+    fn gate_to_gate() {
+        let wd = ground_world();
+        let config = Config {
+            plane_spawn_chance: 1.0,
+            max_planes: Some(1),
+            ..Config::default()
+        };
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        state.tick(&config, &wd);
+        let plane = &state.planes[0];
+        let (id, gate) = (plane.id, plane.pos.pos_ang.0.truncate());
+        assert!(wd.airports[0].gates.iter().any(|a| a.pos == gate));
+        assert!(state.airports[0]
+            .gates
+            .iter()
+            .any(|a| a.occupant == Some(id)));
+
+        let mut phases = vec![];
+        while state.stats.turnarounds == 0 && state.clock.tick < 2000 {
+            state.tick(&config, &wd);
+            let plane = state.plane(&id).unwrap();
+            if phases.last() != Some(&plane.phase.str()) {
+                phases.push(plane.phase.str());
+            }
+            if plane.on_ground() {
+                // taxiing planes keep to the taxiways, gates and runway
+                let pos = plane.pos.pos_ang.0.truncate();
+                assert!(pos.x < 1.0 && pos.y > -10.0 && pos.y < 60.0, "{pos}");
+            }
+        }
+        // parked, and then off again as the next flight
+        assert_eq!(
+            phases[..4],
+            ["Pushback", "TaxiOut", "HoldingPoint", "Takeoff"]
+        );
+        assert_eq!(
+            phases[phases.len() - 4..],
+            ["Landing", "TaxiIn", "Parked", "Pushback"]
+        );
+        assert_eq!(state.stats.turnarounds, 1);
+        assert!(state.airports[0].gate_of(&id).is_some());
+    }
+
+    #[test]
+    fn wait_behind() {
+        let wd = world();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let runway = Arc::clone(&wd.airports[0].runways[0]);
+        for x in [0.0, 5.0] {
+            let mut plane = Plane::new(
+                &wd.planes[0],
+                &Arc::new(Flight::default()),
+                &runway,
+                &Config::default(),
+                &wd,
+                &state.clock,
+                &mut state.rng,
+            );
+            plane.pos.pos_ang = Pos3Angle(Pos2::new(x, -100.0).extend(0.0), Angle(0.0));
+            plane.phase = PhaseData::TaxiOut {
+                runway: Arc::clone(&runway),
+            };
+            state.planes.push(plane);
+        }
+        state.reindex();
+        let (behind, ahead) = (state.planes[0].id, state.planes[1].id);
+        let config = Config::default();
+
+        state.tick_ground(&config, &wd);
+        assert_eq!(state.planes[0].taxi_hold, Some(ahead));
+        assert_eq!(state.planes[1].taxi_hold, None);
+        assert_eq!(state.stats.taxi_holds, 1);
+
+        state.planes[1].pos.pos_ang.0.x = 50.0;
+        state.tick_ground(&config, &wd);
+        assert_eq!(state.plane(&behind).unwrap().taxi_hold, None);
+        assert!(!state.planes[0].pos.kinematics.x_target.is_empty());
+    }
+}
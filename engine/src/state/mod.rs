@@ -1,23 +1,41 @@
 use std::sync::Arc;
 
-use airport::Airport;
+use airport::{Airport, AirportEventPayload};
+use airspace::Infringement;
 use bytes::Bytes;
-use plane::Plane;
-use rand::{prelude::*, rng, RngExt};
+use clock::Clock;
+use conflict::Conflict;
+use plane::{PhaseData, Plane, PlaneEventPayload};
+use rand::{prelude::*, RngExt};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
-use tracing::{debug, info};
+use stats::Stats;
+use terrain::TerrainWarning;
+use tracing::{debug, info, warn};
 
 use crate::{
     config::Config,
-    util::{AirportCode, AirportStateId, FlightCode, PlaneStateId},
-    world_data::{AirportData, Flight, WorldData},
+    journal::{JournalEntry, Record},
+    util::{index::Index, rng::SimRng, AirportCode, AirportStateId, FlightCode, PlaneStateId},
+    world_data::{AirportData, Flight, PlaneData, Runway, WorldData},
 };
 
 pub mod airport;
+pub mod airspace;
+pub mod avoidance;
+pub mod clock;
+pub mod conflict;
+pub mod go_around;
+pub mod ground;
 pub mod plane;
 pub mod plane_pos;
+pub mod procedure;
+pub mod reconcile;
+pub mod reroute;
+pub mod stats;
+pub mod terrain;
+pub mod turnaround;
 
 #[derive(
     Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive,
@@ -25,33 +43,85 @@ pub mod plane_pos;
 pub struct State {
     pub planes: Vec<Plane>,
     pub airports: Vec<Airport>,
+    pub rng: SimRng,
+    pub clock: Clock,
+    pub stats: Stats,
+    /// Records produced since the engine last flushed them to its journal
+    #[serde(skip)]
+    #[rkyv(with = rkyv::with::Skip)]
+    pub journal: Vec<JournalEntry>,
+    /// Losses of separation found by the last tick
+    #[serde(skip)]
+    #[rkyv(with = rkyv::with::Skip)]
+    pub conflicts: Vec<Conflict>,
+    /// Planes found inside active airspace by the last tick
+    #[serde(skip)]
+    #[rkyv(with = rkyv::with::Skip)]
+    pub infringements: Vec<Infringement>,
+    /// Planes found too close to the ground by the last tick
+    #[serde(skip)]
+    #[rkyv(with = rkyv::with::Skip)]
+    pub terrain_warnings: Vec<TerrainWarning>,
+    /// Positions of planes and airports by id, rebuilt by [`Self::reindex`]
+    #[serde(skip)]
+    #[rkyv(with = rkyv::with::Skip)]
+    pub index: StateIndex,
 }
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateIndex {
+    planes: Index<PlaneStateId>,
+    airports: Index<AirportStateId>,
+}
+
 impl State {
     #[must_use]
-    pub fn new(airports: &[Arc<AirportData>]) -> Self {
-        Self {
+    pub fn new(airports: &[Arc<AirportData>], seed: u64, clock: Clock) -> Self {
+        let mut state = Self {
             planes: Vec::default(),
             airports: airports
                 .iter()
                 .map(|a| Airport::new(Arc::clone(a)))
                 .collect(),
-        }
+            rng: SimRng::seed_from_u64(seed),
+            clock,
+            stats: Stats::default(),
+            journal: Vec::new(),
+            conflicts: Vec::new(),
+            infringements: Vec::new(),
+            terrain_warnings: Vec::new(),
+            index: StateIndex::default(),
+        };
+        state.reindex();
+        state
+    }
+    /// Rebuilds the lookup tables after planes or airports were added or removed other than by
+    /// spawning.
+    pub fn reindex(&mut self) {
+        self.index = StateIndex {
+            planes: Index::new(&self.planes, |a| &a.id),
+            airports: Index::new(&self.airports, |a| &a.id),
+        };
     }
     #[must_use]
     pub fn plane(&self, id: &PlaneStateId) -> Option<&Plane> {
-        self.planes.iter().find(|a| a.id == *id)
+        let i = self.index.planes.get(&self.planes, id, |a| &a.id)?;
+        Some(&self.planes[i])
     }
     #[must_use]
     pub fn plane_mut(&mut self, id: &PlaneStateId) -> Option<&mut Plane> {
-        self.planes.iter_mut().find(|a| a.id == *id)
+        let i = self.index.planes.get(&self.planes, id, |a| &a.id)?;
+        Some(&mut self.planes[i])
     }
     #[must_use]
     pub fn airport(&self, id: &AirportStateId) -> Option<&Airport> {
-        self.airports.iter().find(|a| a.id == *id)
+        let i = self.index.airports.get(&self.airports, id, |a| &a.id)?;
+        Some(&self.airports[i])
     }
     #[must_use]
     pub fn airport_mut(&mut self, id: &AirportStateId) -> Option<&mut Airport> {
-        self.airports.iter_mut().find(|a| a.id == *id)
+        let i = self.index.airports.get(&self.airports, id, |a| &a.id)?;
+        Some(&mut self.airports[i])
     }
     pub fn airport_departures<'a>(
         &'a self,
@@ -66,36 +136,135 @@ impl State {
         self.planes.iter().filter(|a| a.flight.to == *code)
     }
 
-    fn tick_planes(&mut self, config: &Config) -> Vec<PlaneStateId> {
+    fn tick_planes(&mut self, config: &Config, wd: &WorldData) -> Vec<PlaneStateId> {
         let mut remove_list = vec![];
+        let rngs = self
+            .planes
+            .iter()
+            .map(|_| self.rng.fork())
+            .collect::<Vec<_>>();
         for (id, (remove, send)) in self
             .planes
             .par_iter_mut()
-            .map(|plane| (plane.id, plane.tick(config)))
+            .zip(rngs)
+            .map(|(plane, mut rng)| (plane.id, plane.tick(config, wd, &self.clock, &mut rng)))
             .collect::<Vec<_>>()
         {
             if remove {
                 info!(%id, "Removing plane");
+                self.record_landing(id);
                 remove_list.push(id);
             }
             for (airport, event) in send {
+                if let AirportEventPayload::GoAround { runway, reason } = &event.payload {
+                    self.stats.go_arounds += 1;
+                    self.journal.push(JournalEntry {
+                        tick: self.clock.tick,
+                        record: Record::GoAround {
+                            airport: airport.clone(),
+                            plane: id,
+                            runway: runway.clone(),
+                            reason: *reason,
+                        },
+                    });
+                }
                 if let Some(airport) = self.airport_mut(&airport) {
                     debug!(?event, to=%airport.id, "Sending airport event");
                     airport.events.push_back(event);
                 }
             }
         }
-        self.planes.retain(|plane| !remove_list.contains(&plane.id));
+        if !remove_list.is_empty() {
+            self.planes.retain(|plane| !remove_list.contains(&plane.id));
+            self.reindex();
+        }
         remove_list
     }
-    fn tick_airports(&mut self, config: &Config) {
+    /// Journals that `id` rolled out on its runway, if it was landing.
+    fn record_landing(&mut self, id: PlaneStateId) {
+        let Some(plane) = self.plane(&id) else {
+            return;
+        };
+        let PhaseData::Landing { runway } = &plane.phase else {
+            return;
+        };
+        let record = Record::Landed {
+            airport: plane.flight.to.clone(),
+            plane: id,
+            runway: runway.name.clone(),
+        };
+        self.journal.push(JournalEntry {
+            tick: self.clock.tick,
+            record,
+        });
+    }
+    fn tick_airports(&mut self, config: &Config, wd: &WorldData) {
+        let rngs = self
+            .airports
+            .iter()
+            .map(|_| self.rng.fork())
+            .collect::<Vec<_>>();
+        let (planes, index) = (&self.planes, &self.index.planes);
+        let plane = |id: &PlaneStateId| index.get(planes, id, |a| &a.id).map(|i| &planes[i]);
         for send in self
             .airports
             .par_iter_mut()
-            .map(|airport| airport.tick(config))
+            .zip(rngs)
+            .map(|(airport, mut rng)| airport.tick(config, wd, plane, &self.clock, &mut rng))
             .collect::<Vec<_>>()
         {
             for (plane, event) in send {
+                match &event.payload {
+                    PlaneEventPayload::ClearForLanding(runway) => {
+                        self.journal.push(JournalEntry {
+                            tick: self.clock.tick,
+                            record: Record::Clearance {
+                                airport: event.from.clone(),
+                                plane,
+                                runway: runway.name.clone(),
+                            },
+                        });
+                    }
+                    PlaneEventPayload::ClearForTakeoff(runway) => {
+                        // planes ask for departure as they reach the holding point
+                        let delay = self
+                            .plane(&plane)
+                            .map_or(0.0, |a| self.clock.now() - a.phase_start);
+                        self.stats.departures += 1;
+                        self.stats.departure_delay += delay;
+                        self.journal.push(JournalEntry {
+                            tick: self.clock.tick,
+                            record: Record::TakeoffClearance {
+                                airport: event.from.clone(),
+                                plane,
+                                runway: runway.name.clone(),
+                                delay,
+                            },
+                        });
+                    }
+                    PlaneEventPayload::Hold { fix, altitude } => {
+                        self.journal.push(JournalEntry {
+                            tick: self.clock.tick,
+                            record: Record::Hold {
+                                airport: event.from.clone(),
+                                plane,
+                                fix: fix.name.clone(),
+                                altitude: *altitude,
+                            },
+                        });
+                    }
+                    PlaneEventPayload::Divert(to) => {
+                        self.journal.push(JournalEntry {
+                            tick: self.clock.tick,
+                            record: Record::Divert {
+                                airport: event.from.clone(),
+                                plane,
+                                to: to.code.clone(),
+                            },
+                        });
+                    }
+                    PlaneEventPayload::Release | PlaneEventPayload::GoAround(_) => {}
+                }
                 if let Some(plane) = self.plane_mut(&plane) {
                     debug!(?event, to=%plane.id, "Sending plane event");
                     plane.events.push_back(event);
@@ -105,42 +274,147 @@ impl State {
     }
     fn tick_spawn_planes(&mut self, config: &Config, wd: &WorldData) {
         if config.max_planes.is_some_and(|m| self.planes.len() >= m)
-            || rng().random_range(0.0..=1.0) > config.plane_spawn_chance
+            || self.rng.random_range(0.0..=1.0) > config.plane_spawn_chance
         {
             return;
         }
 
-        let plane = wd.planes.choose(&mut rng()).unwrap();
-        #[expect(clippy::option_if_let_else)]
-        let flight = if let Some(flights) = &wd.flights {
-            flights.choose(&mut rng()).unwrap()
+        let (plane, flight) = if let Some(flights) = &wd.flights {
+            let flight = flights.choose(&mut self.rng).unwrap();
+            let Some(plane) = flight
+                .plane
+                .iter()
+                .filter_map(|a| wd.plane_model(a))
+                .collect::<Vec<_>>()
+                .choose(&mut self.rng)
+                .copied()
+            else {
+                warn!(%flight.code, "Flight has no known plane model");
+                return;
+            };
+            (plane, Arc::clone(flight))
         } else {
-            // TODO check whether plane can land in runway
-            &Arc::new(Flight {
+            let plane = wd.planes.choose(&mut self.rng).unwrap();
+            let from = self
+                .airports
+                .iter()
+                .filter(|a| a.airport.runways.iter().any(|r| wd.can_take_off(plane, r)))
+                .collect::<Vec<_>>();
+            let to = wd
+                .airports
+                .iter()
+                .filter(|a| a.runways.iter().any(|r| wd.can_land(plane, r)))
+                .collect::<Vec<_>>();
+            let (Some(from), Some(to)) = (from.choose(&mut self.rng), to.choose(&mut self.rng))
+            else {
+                warn!(%plane.id, "No airports fit plane model");
+                return;
+            };
+            let flight = Arc::new(Flight {
                 airline: SmolStr::default(),
                 code: FlightCode::default(),
-                from: wd.airports.choose(&mut rng()).unwrap().code.clone(),
-                to: wd.airports.choose(&mut rng()).unwrap().code.clone(),
+                from: from.id.clone(),
+                to: to.code.clone(),
                 plane: Arc::new([plane.id.clone()]),
-            })
+            });
+            (plane, flight)
         };
-        let runway = self
-            .airport(&flight.from)
-            .unwrap()
-            .airport
-            .runways
-            .choose(&mut rng())
-            .unwrap();
-        let plane = Plane::new(plane, flight, runway, wd);
+        let Some(runway) = self.departure_runway(plane, &flight.from, config, wd) else {
+            return;
+        };
+        let mut plane = Plane::new(
+            plane,
+            &flight,
+            &runway,
+            config,
+            wd,
+            &self.clock,
+            &mut self.rng,
+        );
+        if !self.place_departure(&mut plane, &runway, wd) {
+            return;
+        }
+        self.journal.push(JournalEntry {
+            tick: self.clock.tick,
+            record: Record::Spawn {
+                plane: plane.id,
+                flight: flight.code.clone(),
+                from: flight.from.clone(),
+                to: flight.to.clone(),
+                model: plane.model.id.clone(),
+                runway: runway.name.clone(),
+            },
+        });
         info!(%plane.id, %plane.model.id, %plane.flight.code, %plane.flight.from, %plane.flight.to, "Creating plane");
+        self.index.planes.insert(plane.id, self.planes.len());
         self.planes.push(plane);
     }
 
+    /// Runway for a new departure of `plane` from `from`: one into the wind, and free unless the
+    /// plane starts at a gate.
+    fn departure_runway(
+        &mut self,
+        plane: &PlaneData,
+        from: &AirportCode,
+        config: &Config,
+        wd: &WorldData,
+    ) -> Option<Arc<Runway>> {
+        let runways = self
+            .airport(from)
+            .map(|a| {
+                a.airport
+                    .runways
+                    .iter()
+                    .filter(|r| wd.can_take_off(plane, r))
+                    .map(Arc::clone)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if runways.is_empty() {
+            warn!(%plane.id, %from, "No runway fits plane model");
+            return None;
+        }
+        let runway = self
+            .airport(from)
+            .map(|a| {
+                wd.runways_into_wind(
+                    plane,
+                    &runways,
+                    self.clock.elapsed,
+                    config.runway_headwind_margin,
+                )
+                .into_iter()
+                .filter(|r| {
+                    // planes at gates wait for their runway there, and elsewhere one plane
+                    // at a time waits at the start of each runway
+                    a.airport.has_ground()
+                        || (a.runway_free(r, config, self.clock.now(), |id| self.plane(id))
+                            && !self.holding_short(&a.id, r))
+                })
+                .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .choose(&mut self.rng)
+            .map(|a| Arc::clone(a));
+        if runway.is_none() {
+            debug!(%from, "No runway free for departure");
+        }
+        runway
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn tick(&mut self, config: &Config, wd: &WorldData) -> (Vec<PlaneStateId>, Bytes) {
-        let remove_list = self.tick_planes(config);
-        self.tick_airports(config);
+        let mut remove_list = self.tick_ground(config, wd);
+        remove_list.extend(self.tick_planes(config, wd));
+        self.tick_airports(config, wd);
+        self.tick_go_arounds();
         self.tick_spawn_planes(config, wd);
+        self.tick_reroutes(config, wd);
+        self.conflicts = conflict::detect(&self.planes, config, wd, self.clock.elapsed);
+        self.tick_avoidance(config, wd);
+        self.tick_infringements(wd);
+        self.tick_terrain_warnings(config, wd);
+        self.clock.advance(config.tick_duration);
 
         (remove_list, self.coord_state())
     }
@@ -162,3 +436,137 @@ impl State {
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::world,
+        util::Pos2,
+        world_data::{PlaneData, Runway},
+    };
+
+    #[test]
+    fn same_seed_same_trajectories() {
+        let wd = world();
+        let config = Config {
+            plane_spawn_chance: 0.2,
+            ..Config::default()
+        };
+        let mut a = State::new(&wd.airports, 42, Clock::default());
+        let mut b = State::new(&wd.airports, 42, Clock::default());
+        for _ in 0..200 {
+            assert_eq!(a.tick(&config, &wd), b.tick(&config, &wd));
+        }
+        assert!(!a.planes.is_empty());
+    }
+
+    #[test]
+    fn clock_stamps_planes() {
+        let wd = world();
+        let config = Config {
+            tick_duration: 0.5,
+            plane_spawn_chance: 1.0,
+            ..Config::default()
+        };
+        let mut state = State::new(&wd.airports, 0, Clock::new(Some(1000)));
+        for _ in 0..10 {
+            state.tick(&config, &wd);
+        }
+        assert_eq!(state.clock.tick, 10);
+        assert!((state.clock.now() - 1005.0).abs() < f64::EPSILON);
+        let first = state.planes.first().unwrap();
+        assert!((first.start_time - 1000.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn index_follows_spawns_and_removals() {
+        let wd = world();
+        let config = Config {
+            plane_spawn_chance: 0.5,
+            ..Config::default()
+        };
+        let mut state = State::new(&wd.airports, 7, Clock::default());
+        let mut removed = 0;
+        for _ in 0..300 {
+            removed += state.tick(&config, &wd).0.len();
+            let mut fresh = state.clone();
+            fresh.reindex();
+            assert_eq!(state.index, fresh.index);
+        }
+        assert!(removed > 0);
+        // without gates to taxi to, every plane removed has just landed
+        assert_eq!(
+            state
+                .journal
+                .iter()
+                .filter(|a| matches!(a.record, Record::Landed { .. }))
+                .count(),
+            removed
+        );
+        for (i, plane) in state.planes.iter().enumerate() {
+            assert_eq!(
+                state.index.planes.get(&state.planes, &plane.id, |a| &a.id),
+                Some(i)
+            );
+        }
+    }
+
+    #[test]
+    fn planes_only_use_runways_they_fit() {
+        // the short runways are too small for the only model, by class on FOO and length on BAR
+        let runway = |name: &str, class: &str, x: f32, len: f32| {
+            Arc::new(Runway {
+                name: name.into(),
+                start: Pos2::new(x, 0.0),
+                end: Pos2::new(x, len),
+                class: class.into(),
+                ..Runway::default()
+            })
+        };
+        let mut wd = world();
+        wd.classes = Arc::new([Arc::new(["small".into(), "large".into()])]);
+        wd.airports = Arc::new([
+            Arc::new(AirportData {
+                code: "FOO".into(),
+                runways: Arc::new([
+                    runway("S", "small", 0.0, 200.0),
+                    runway("L", "large", 20.0, 200.0),
+                ]),
+                ..AirportData::default()
+            }),
+            Arc::new(AirportData {
+                code: "BAR".into(),
+                runways: Arc::new([
+                    runway("S", "large", 500.0, 50.0),
+                    runway("L", "large", 520.0, 200.0),
+                ]),
+                ..AirportData::default()
+            }),
+        ]);
+        wd.planes = Arc::new([Arc::new(PlaneData {
+            class: "large".into(),
+            min_takeoff_distance: 100.0,
+            min_landing_distance: 100.0,
+            ..(*wd.planes[0]).clone()
+        })]);
+        let config = Config {
+            plane_spawn_chance: 0.5,
+            ..Config::default()
+        };
+        let mut state = State::new(&wd.airports, 3, Clock::default());
+        for _ in 0..300 {
+            state.tick(&config, &wd);
+        }
+        let runways = state
+            .journal
+            .iter()
+            .filter_map(|a| match &a.record {
+                Record::Spawn { runway, .. } | Record::Clearance { runway, .. } => Some(runway),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(runways.len() > 10);
+        assert!(runways.iter().all(|a| *a == "L"));
+    }
+}
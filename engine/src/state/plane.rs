@@ -1,26 +1,27 @@
-use std::{collections::VecDeque, sync::Arc, time::SystemTime};
+use std::{collections::VecDeque, f32::consts::PI, sync::Arc};
 
-use dubins_paths::f32::DubinsPath;
-use glam::Vec3Swizzles;
+use glam::{Vec2, Vec3Swizzles};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use ts_rs::TS;
-use uuid::Uuid;
 
 use crate::{
     config::Config,
     state::{
         airport::{AirportEvent, AirportEventPayload},
-        plane_pos::{FlightInstruction, FlightPlanner, PlanePos},
+        clock::Clock,
+        plane_pos::{FlightInstruction, FlightPlanner, PlanePos, Surroundings},
     },
+    terrain::Terrain,
     util::{
         angle::Angle,
         kinematics::{Kinematics, Target},
         pos::{Pos2Angle, Pos3Angle},
         ray::Ray,
-        AirportStateId, PlaneStateId, Pos2,
+        rng::SimRng,
+        AirportStateId, PlaneStateId,
     },
-    world_data::{Flight, PlaneData, Runway, WorldData},
+    world_data::{AirportData, Flight, Gate, HoldingFix, PlaneData, Runway, WorldData},
 };
 
 #[derive(
@@ -35,11 +36,22 @@ pub struct Plane {
     pub phase: PhaseData,
     #[ts(as = "Vec<PlaneEvent>")]
     pub events: VecDeque<PlaneEvent>,
-    pub start_time: u64,
+    pub start_time: f64,
+    pub phase_start: f64,
+    /// Manoeuvre ordered by collision avoidance, until clear of conflict
+    pub advisory: Option<Advisory>,
+    /// Plane ahead that this one has stopped behind while taxiing
+    #[ts(as = "Option<String>")]
+    pub taxi_hold: Option<PlaneStateId>,
 }
 
 struct PlaneEventsResult {
     landing_runway: Option<Arc<Runway>>,
+    takeoff_runway: Option<Arc<Runway>>,
+    hold: Option<(Arc<HoldingFix>, f32)>,
+    release: bool,
+    go_around: Option<GoAroundReason>,
+    divert: Option<Arc<AirportData>>,
 }
 
 enum PlanePhaseResult {
@@ -49,29 +61,57 @@ enum PlanePhaseResult {
 }
 
 impl Plane {
+    /// A plane about to take off from `runway`, routed around the weather and airspace that
+    /// are in the way when it spawns, and ground too high to climb over, if there is a way
+    /// around them. It flies one of the runway's SIDs onto the airways if it has any.
     #[must_use]
     pub fn new(
         model: &Arc<PlaneData>,
         flight: &Arc<Flight>,
         runway: &Arc<Runway>,
+        config: &Config,
         wd: &WorldData,
+        clock: &Clock,
+        rng: &mut SimRng,
     ) -> Self {
         let pos_ang_start = Pos3Angle(
             runway.start3(),
             Angle((runway.end - runway.start).to_angle()),
         );
         let pos_ang_end = Pos2Angle(runway.end, Angle((runway.end - runway.start).to_angle()));
+        let obstacles = wd.obstacles_at(clock.elapsed);
+        let sids = wd
+            .airport(&flight.from)
+            .map(|from| from.sids_from(runway).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let (sid, route) = wd
+            .airport(&flight.to)
+            .map(|to| {
+                let blocked =
+                    (!obstacles.is_empty()).then_some(|a, b| obstacles.blocks(a, b, config));
+                let (sid, route) =
+                    wd.find_departure_route(pos_ang_end, &sids, &model.motion, to, blocked);
+                if route.is_empty() && blocked.is_some() {
+                    wd.find_departure_route(
+                        pos_ang_end,
+                        &sids,
+                        &model.motion,
+                        to,
+                        None::<fn(_, _) -> bool>,
+                    )
+                } else {
+                    (sid, route)
+                }
+            })
+            .unwrap_or_default();
         let mut s = Self {
-            id: Uuid::new_v4(),
+            id: rng.uuid(),
             pos: PlanePos {
                 pos_ang: pos_ang_start,
                 kinematics: Kinematics::default(),
                 planner: FlightPlanner::new(
                     VecDeque::from([FlightInstruction::Straight(runway.ray())]),
-                    wd.find_waypoint_route(
-                        pos_ang_end,
-                        wd.airport(&flight.to).map_or(Pos2::ZERO, |a| a.centre()),
-                    ),
+                    route,
                 ),
             },
             model: Arc::clone(model),
@@ -80,11 +120,12 @@ impl Plane {
             phase: PhaseData::Takeoff {
                 runway: Arc::clone(runway),
             },
-            start_time: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            start_time: clock.now(),
+            phase_start: clock.now(),
+            advisory: None,
+            taxi_hold: None,
         };
+        s.pos.planner.procedure = sid;
         s.pos.kinematics.target_x(
             Some(s.model.motion.max_v.x),
             None,
@@ -94,57 +135,169 @@ impl Plane {
         );
         s
     }
+    /// Altitude to fly at towards the waypoint the plane is headed for, clear of `terrain` on the
+    /// way and within the constraints of the waypoint if it is a fix of a SID or STAR.
+    #[must_use]
+    pub fn cruising_altitude(&self, config: &Config, terrain: &Terrain) -> f32 {
+        let planner = &self.pos.planner;
+        planner
+            .past_route
+            .last()
+            .or_else(|| planner.route.front())
+            .map_or_else(
+                || config.min_cruising_altitude(),
+                |a| {
+                    let altitude =
+                        terrain.cruising_altitude(self.pos.pos_ang.0.xy(), a.pos, config);
+                    planner
+                        .constraint(a)
+                        .map_or(altitude, |a| a.constrain_altitude(altitude))
+                },
+            )
+    }
     fn handle_events(&mut self) -> PlaneEventsResult {
         let mut landing_runway = None;
+        let mut takeoff_runway = None;
+        let mut hold = None;
+        let mut release = false;
+        let mut go_around = None;
+        let mut divert = None;
         for event in self.events.drain(..) {
             match event.payload {
                 PlaneEventPayload::ClearForLanding(runway) => {
                     landing_runway = Some(runway);
                 }
+                PlaneEventPayload::ClearForTakeoff(runway) => {
+                    takeoff_runway = Some(runway);
+                }
+                PlaneEventPayload::Hold { fix, altitude } => {
+                    hold = Some((fix, altitude));
+                }
+                PlaneEventPayload::Release => {
+                    release = true;
+                }
+                PlaneEventPayload::GoAround(reason) => {
+                    go_around = Some(reason);
+                }
+                PlaneEventPayload::Divert(airport) => {
+                    divert = Some(airport);
+                }
             }
         }
 
-        PlaneEventsResult { landing_runway }
+        PlaneEventsResult {
+            landing_runway,
+            takeoff_runway,
+            hold,
+            release,
+            go_around,
+            divert,
+        }
+    }
+    pub(super) fn set_phase(&mut self, phase: PhaseData, now: f64) {
+        info!(phase=?phase.str(), time = now, "Changing phase");
+        self.phase = phase;
+        self.phase_start = now;
+    }
+    fn handle_taxi_out_phase(
+        &self,
+        runway: &Arc<Runway>,
+        clock: &Clock,
+        send: &mut Vec<(AirportStateId, AirportEvent)>,
+    ) -> PlanePhaseResult {
+        if self.taxi_hold.is_some() || !self.pos.kinematics.x_target.is_empty() {
+            return PlanePhaseResult::NoChange;
+        }
+        send.push((
+            self.flight.from.clone(),
+            AirportEvent {
+                from: self.id,
+                time: clock.now(),
+                payload: AirportEventPayload::RequestDeparture {
+                    runway: runway.name.clone(),
+                },
+            },
+        ));
+        PlanePhaseResult::NewPhase(PhaseData::HoldingPoint {
+            runway: Arc::clone(runway),
+        })
     }
-    fn handle_takeoff_phase(&mut self, config: &Config, runway: &Arc<Runway>) -> PlanePhaseResult {
-        let plane_pos = self.pos.pos_ang.0.xy();
-        let runway_progress = plane_pos.distance(runway.start) / runway.end.distance(runway.start);
+    /// Once cleared, turns onto the runway from the holding point and starts the takeoff roll
+    /// without stopping.
+    fn handle_holding_point_phase(
+        &mut self,
+        ev_result: &PlaneEventsResult,
+        config: &Config,
+    ) -> PlanePhaseResult {
+        let Some(runway) = &ev_result.takeoff_runway else {
+            return PlanePhaseResult::NoChange;
+        };
+        let pos = self.pos.pos_ang.0.truncate();
+        self.pos.planner.instructions =
+            FlightInstruction::taxi(&[pos, runway.start, runway.end], config.taxi_turning_radius)
+                .into();
+        self.pos.planner.instruction_s = 0.0;
+        self.pos.kinematics.target_x(
+            Some(self.model.motion.max_v.x),
+            None,
+            None,
+            None,
+            self.model.motion,
+        );
+        PlanePhaseResult::NewPhase(PhaseData::Takeoff {
+            runway: Arc::clone(runway),
+        })
+    }
+    fn handle_takeoff_phase(
+        &mut self,
+        config: &Config,
+        terrain: &Terrain,
+        runway: &Arc<Runway>,
+    ) -> PlanePhaseResult {
+        // negative while still turning onto the runway
+        let runway_progress =
+            (self.pos.pos_ang.0.xy() - runway.start).dot(runway.ray().vec) / runway.len().powi(2);
 
         if runway_progress < 0.75 {
             return PlanePhaseResult::NoChange;
         }
 
-        let cruising_altitude = self
-            .pos
-            .planner
-            .past_route
-            .last()
-            .or_else(|| self.pos.planner.route.front())
-            .map(|a| a.pos)
-            .map_or_else(
-                || config.min_cruising_altitude(),
-                |a| config.cruising_altitude(plane_pos, a),
-            );
         self.pos.kinematics.target_y(
             Some(0.0),
-            Some(cruising_altitude - self.pos.pos_ang.0.z),
+            Some(self.cruising_altitude(config, terrain) - self.pos.pos_ang.0.z),
             None,
             None,
             self.model.motion,
         );
         PlanePhaseResult::NewPhase(PhaseData::Cruise)
     }
+    /// At the end of the route, flies on along the STAR into the runways in use if there is one,
+    /// and otherwise asks for a runway to descend to.
     fn handle_cruise_phase(
         &mut self,
+        config: &Config,
+        wd: &WorldData,
+        clock: &Clock,
         send: &mut Vec<(AirportStateId, AirportEvent)>,
     ) -> PlanePhaseResult {
-        if !self.pos.planner.route.is_empty() || !self.pos.planner.instructions.is_empty() {
+        if !self.pos.planner.route.is_empty()
+            || !self.pos.planner.instructions.is_empty()
+            || self.join_star(config, wd, clock)
+        {
             return PlanePhaseResult::NoChange;
         }
+        self.request_runway(clock, send)
+    }
+    fn request_runway(
+        &mut self,
+        clock: &Clock,
+        send: &mut Vec<(AirportStateId, AirportEvent)>,
+    ) -> PlanePhaseResult {
         send.push((
             self.flight.to.clone(),
             AirportEvent {
                 from: self.id,
+                time: clock.now(),
                 payload: AirportEventPayload::RequestRunway,
             },
         ));
@@ -157,25 +310,32 @@ impl Plane {
         );
         PlanePhaseResult::NewPhase(PhaseData::Descent)
     }
-    fn handle_descent_phase(&mut self, ev_result: &PlaneEventsResult) -> PlanePhaseResult {
+    fn handle_descent_phase(
+        &mut self,
+        ev_result: &PlaneEventsResult,
+        config: &Config,
+        wd: &WorldData,
+    ) -> PlanePhaseResult {
+        if let Some(to) = &ev_result.divert {
+            return self.divert(to, config, wd);
+        }
         let Some(landing_runway) = &ev_result.landing_runway else {
-            return PlanePhaseResult::NoChange;
+            return ev_result
+                .hold
+                .as_ref()
+                .map_or(PlanePhaseResult::NoChange, |(fix, altitude)| {
+                    self.enter_hold(fix, *altitude)
+                });
         };
+        let gate = landing_runway.approach_gate();
         let landing_ray = Ray {
-            tail: landing_runway.start - landing_runway.ray().vec,
+            tail: gate.0,
             vec: landing_runway.ray().vec * 2.0,
         };
-        let dubins = FlightInstruction::Dubins(
-            DubinsPath::shortest_from(
-                self.pos.pos_ang.to_2().into(),
-                Pos2Angle(
-                    landing_ray.tail,
-                    Angle((landing_runway.end - landing_runway.start).to_angle()),
-                )
-                .into(),
-                self.model.motion.turning_radius,
-            )
-            .unwrap(),
+        let dubins = FlightInstruction::dubins(
+            self.pos.pos_ang.to_2(),
+            gate,
+            self.model.motion.turning_radius,
         );
         let straight = FlightInstruction::Straight(landing_ray);
         let touchdown_length = landing_runway.len() * 0.75;
@@ -196,18 +356,12 @@ impl Plane {
                     Some(self.model.motion.max_v.x * 0.75),
                     Some(ds),
                     None,
-                    None,
+                    Some(self.model.motion.max_v.x * 0.75),
                     self.model.motion,
                 )
                 .iter(),
         );
-        self.pos.kinematics.target_y(
-            Some(0.0),
-            Some(landing_runway.altitude - self.pos.pos_ang.0.z),
-            Some(dt),
-            None,
-            self.model.motion,
-        );
+        self.descend(landing_runway.altitude, ds, dt, config, &wd.terrain);
         self.pos.kinematics.x_target.push(Target {
             a: (self.model.motion.max_v.x * 0.75).mul_add(-(self.model.motion.max_v.x * 0.75), 1.0)
                 / touchdown_length
@@ -218,7 +372,114 @@ impl Plane {
             runway: Arc::clone(landing_runway),
         })
     }
-    fn handle_landing_phase(&self) -> PlanePhaseResult {
+    fn enter_hold(&mut self, fix: &Arc<HoldingFix>, altitude: f32) -> PlanePhaseResult {
+        let entry = FlightInstruction::dubins(
+            self.pos.pos_ang.to_2(),
+            Pos2Angle(fix.end, Angle((fix.end - fix.start).to_angle())),
+            self.model.motion.turning_radius,
+        );
+        self.pos.planner.instructions.clear();
+        self.pos.planner.instruction_s = 0.0;
+        self.pos.planner.instructions.push_back(entry);
+        self.pos.kinematics.target_y(
+            Some(0.0),
+            Some(altitude - self.pos.pos_ang.0.z),
+            None,
+            None,
+            self.model.motion,
+        );
+        PlanePhaseResult::NewPhase(PhaseData::Holding {
+            fix: Arc::clone(fix),
+            altitude,
+        })
+    }
+    /// Flies on along the airways to `to` instead of the destination of the flight.
+    fn divert(&mut self, to: &AirportData, config: &Config, wd: &WorldData) -> PlanePhaseResult {
+        info!(to = %to.code, "Diverting");
+        self.flight = Arc::new(Flight {
+            to: to.code.clone(),
+            ..(*self.flight).clone()
+        });
+        let planner = &mut self.pos.planner;
+        planner.instructions.clear();
+        planner.instruction_s = 0.0;
+        planner.procedure = None;
+        planner.route = wd.find_route(self.pos.pos_ang.to_2(), &self.model.motion, to);
+        self.pos.kinematics.target_x(
+            Some(self.model.motion.max_v.x),
+            None,
+            None,
+            None,
+            self.model.motion,
+        );
+        self.pos.kinematics.target_y(
+            Some(0.0),
+            Some(self.cruising_altitude(config, &wd.terrain) - self.pos.pos_ang.0.z),
+            None,
+            None,
+            self.model.motion,
+        );
+        PlanePhaseResult::NewPhase(PhaseData::Cruise)
+    }
+    fn handle_holding_phase(
+        &mut self,
+        ev_result: &PlaneEventsResult,
+        fix: &Arc<HoldingFix>,
+        config: &Config,
+        wd: &WorldData,
+    ) -> PlanePhaseResult {
+        if ev_result.landing_runway.is_some() || ev_result.release || ev_result.divert.is_some() {
+            self.pos.planner.instructions.clear();
+            self.pos.planner.instruction_s = 0.0;
+            return match self.handle_descent_phase(ev_result, config, wd) {
+                PlanePhaseResult::NoChange => PlanePhaseResult::NewPhase(PhaseData::Descent),
+                result => result,
+            };
+        }
+        if let Some((new_fix, altitude)) = &ev_result.hold {
+            // moved to another level of the stack
+            if new_fix != fix {
+                return self.enter_hold(new_fix, *altitude);
+            }
+            self.pos.kinematics.target_y(
+                Some(0.0),
+                Some(altitude - self.pos.pos_ang.0.z),
+                None,
+                None,
+                self.model.motion,
+            );
+            self.phase = PhaseData::Holding {
+                fix: Arc::clone(fix),
+                altitude: *altitude,
+            };
+        }
+        if self.pos.planner.instructions.len() <= 1 {
+            self.pos
+                .planner
+                .instructions
+                .extend(FlightInstruction::racetrack(
+                    fix,
+                    self.model.motion.turning_radius,
+                ));
+        }
+        PlanePhaseResult::NoChange
+    }
+    fn handle_landing_phase(
+        &mut self,
+        ev_result: &PlaneEventsResult,
+        runway: &Arc<Runway>,
+        config: &Config,
+        clock: &Clock,
+        send: &mut Vec<(AirportStateId, AirportEvent)>,
+    ) -> PlanePhaseResult {
+        if self.distance_to_threshold(runway) > 0.0 {
+            if let Some(reason) = ev_result
+                .go_around
+                .or_else(|| self.unstable_approach(runway, config))
+            {
+                return self.go_around(runway, reason, config, clock, send);
+            }
+        }
         if self.pos.planner.instructions.is_empty()
             || self.pos.kinematics.v.x < self.model.motion.max_v.x / 10.0
         {
@@ -226,29 +487,192 @@ impl Plane {
         }
         PlanePhaseResult::NoChange
     }
+    /// Whether the plane is off the ground, and so carried by the wind.
+    #[must_use]
+    pub fn airborne(&self) -> bool {
+        match &self.phase {
+            PhaseData::Landing { runway } => self.distance_to_threshold(runway) > 0.0,
+            PhaseData::Takeoff { .. } => false,
+            _ => !self.on_ground(),
+        }
+    }
+    /// Wind the plane flies through at `time`, or none while it is on the ground.
+    #[must_use]
+    pub fn wind(&self, wd: &WorldData, time: f64) -> Vec2 {
+        if self.airborne() {
+            wd.wind.at(self.pos.pos_ang.0, time)
+        } else {
+            Vec2::ZERO
+        }
+    }
+    /// Distance left to fly to the start of `runway`, negative once past it.
+    fn distance_to_threshold(&self, runway: &Runway) -> f32 {
+        (runway.start - self.pos.pos_ang.0.xy()).dot(runway.ray().vec.normalize())
+    }
+    /// Whether the plane is, within [`Config::stable_approach_distance`] of the runway, too fast,
+    /// or too high above the touchdown point.
+    fn unstable_approach(&self, runway: &Runway, config: &Config) -> Option<GoAroundReason> {
+        let distance = self.distance_to_threshold(runway);
+        if distance > config.stable_approach_distance {
+            return None;
+        }
+        if self.pos.kinematics.v.x > self.model.motion.max_v.x * config.stable_approach_speed {
+            return Some(GoAroundReason::TooFast);
+        }
+        let height = self.pos.pos_ang.0.z - runway.altitude;
+        let to_touchdown = runway.len().mul_add(0.75, distance);
+        (height > to_touchdown * config.stable_approach_slope).then_some(GoAroundReason::TooHigh)
+    }
+    /// Abandons the landing, climbing out over the runway and then flying its missed approach.
+    fn go_around(
+        &mut self,
+        runway: &Arc<Runway>,
+        reason: GoAroundReason,
+        config: &Config,
+        clock: &Clock,
+        send: &mut Vec<(AirportStateId, AirportEvent)>,
+    ) -> PlanePhaseResult {
+        info!(?reason, %runway.name, "Going around");
+        let (path, height) = runway.missed_approach(config.missed_approach_height);
+        let radius = self.model.motion.turning_radius;
+        let climb_out = Pos2Angle(runway.end, Angle(runway.ray().vec.to_angle()));
+        let mut from = climb_out;
+        let mut instructions = vec![FlightInstruction::dubins(
+            self.pos.pos_ang.to_2(),
+            climb_out,
+            radius,
+        )];
+        for pos in path {
+            let to = Pos2Angle(pos, Angle((pos - from.0).to_angle()));
+            instructions.push(FlightInstruction::dubins(from, to, radius));
+            from = to;
+        }
+        let ds = instructions
+            .iter()
+            .map(FlightInstruction::length)
+            .sum::<f32>();
+        self.pos.planner.instructions = instructions.into();
+        self.pos.planner.instruction_s = 0.0;
+
+        let dt = Target::sum_t(
+            self.pos
+                .kinematics
+                .target_x(
+                    Some(self.model.motion.max_v.x),
+                    Some(ds),
+                    None,
+                    None,
+                    self.model.motion,
+                )
+                .iter(),
+        );
+        self.pos.kinematics.target_y(
+            Some(0.0),
+            Some(runway.altitude + height - self.pos.pos_ang.0.z),
+            Some(dt),
+            None,
+            self.model.motion,
+        );
+        send.push((
+            self.flight.to.clone(),
+            AirportEvent {
+                from: self.id,
+                time: clock.now(),
+                payload: AirportEventPayload::GoAround {
+                    runway: runway.name.clone(),
+                    reason,
+                },
+            },
+        ));
+        PlanePhaseResult::NewPhase(PhaseData::GoAround {
+            runway: Arc::clone(runway),
+        })
+    }
+    fn handle_go_around_phase(&self, runway: &Arc<Runway>, config: &Config) -> PlanePhaseResult {
+        let (path, _) = runway.missed_approach(config.missed_approach_height);
+        if self.pos.planner.instructions.len() > path.len() {
+            return PlanePhaseResult::NoChange;
+        }
+        PlanePhaseResult::NewPhase(PhaseData::MissedApproach {
+            runway: Arc::clone(runway),
+        })
+    }
+    fn handle_missed_approach_phase(
+        &mut self,
+        clock: &Clock,
+        send: &mut Vec<(AirportStateId, AirportEvent)>,
+    ) -> PlanePhaseResult {
+        if !self.pos.planner.instructions.is_empty() {
+            return PlanePhaseResult::NoChange;
+        }
+        self.request_runway(clock, send)
+    }
+    fn handle_taxi_in_phase(&self, gate: &Arc<Gate>) -> PlanePhaseResult {
+        if self.taxi_hold.is_some() || !self.pos.kinematics.x_target.is_empty() {
+            return PlanePhaseResult::NoChange;
+        }
+        PlanePhaseResult::NewPhase(PhaseData::Parked {
+            gate: Arc::clone(gate),
+        })
+    }
     #[tracing::instrument(skip_all, fields(%self.id, %self.model.id, %self.flight.code, %self.flight.from, %self.flight.to))]
-    pub fn tick(&mut self, config: &Config) -> (bool, Vec<(AirportStateId, AirportEvent)>) {
+    pub fn tick(
+        &mut self,
+        config: &Config,
+        wd: &WorldData,
+        clock: &Clock,
+        rng: &mut SimRng,
+    ) -> (bool, Vec<(AirportStateId, AirportEvent)>) {
         let mut send = vec![];
         let ev_result = self.handle_events();
 
         let phase_handle_result = match self.phase.clone() {
-            PhaseData::Takeoff { runway } => self.handle_takeoff_phase(config, &runway),
-            PhaseData::Cruise => self.handle_cruise_phase(&mut send),
-            PhaseData::Descent => self.handle_descent_phase(&ev_result),
-            PhaseData::Landing { runway: _runway } => self.handle_landing_phase(),
+            // moved on to taxiing, or off as the next flight, by the state, which knows the
+            // taxiways and flights
+            PhaseData::Pushback { .. } | PhaseData::Parked { .. } => PlanePhaseResult::NoChange,
+            PhaseData::TaxiOut { runway } => self.handle_taxi_out_phase(&runway, clock, &mut send),
+            PhaseData::HoldingPoint { .. } => self.handle_holding_point_phase(&ev_result, config),
+            PhaseData::Takeoff { runway } => {
+                self.handle_takeoff_phase(config, &wd.terrain, &runway)
+            }
+            PhaseData::Cruise => self.handle_cruise_phase(config, wd, clock, &mut send),
+            PhaseData::Descent => self.handle_descent_phase(&ev_result, config, wd),
+            PhaseData::Holding { fix, .. } => {
+                self.handle_holding_phase(&ev_result, &fix, config, wd)
+            }
+            PhaseData::Landing { runway } => {
+                self.handle_landing_phase(&ev_result, &runway, config, clock, &mut send)
+            }
+            PhaseData::GoAround { runway } => self.handle_go_around_phase(&runway, config),
+            PhaseData::MissedApproach { .. } => self.handle_missed_approach_phase(clock, &mut send),
+            PhaseData::TaxiIn { gate } => self.handle_taxi_in_phase(&gate),
         };
         let remove = match phase_handle_result {
             PlanePhaseResult::NewPhase(new_phase) => {
-                info!(phase=?new_phase.str(), "Changing phase");
-                self.phase = new_phase;
+                self.set_phase(new_phase, clock.now());
                 false
             }
             PlanePhaseResult::Remove => true,
             PlanePhaseResult::NoChange => false,
         };
 
-        self.pos
-            .tick(config.tick_duration, self.model.motion, config);
+        let surroundings = Surroundings {
+            config,
+            wind: self.wind(wd, clock.elapsed),
+            airspace: &wd.airspace_at(clock.elapsed),
+            terrain: &wd.terrain,
+        };
+        self.pos.tick(
+            config.tick_duration,
+            self.model.motion,
+            self.advisory.is_none(),
+            &surroundings,
+            rng,
+        );
+        if matches!(self.phase, PhaseData::Pushback { .. }) {
+            // pushed tail first
+            self.pos.pos_ang.1 = Angle(self.pos.pos_ang.1 .0 + PI).clamp();
+        }
         (remove, send)
     }
 }
@@ -258,24 +682,131 @@ impl Plane {
 )]
 #[ts(export)]
 pub enum PhaseData {
-    Takeoff { runway: Arc<Runway> },
+    /// Being pushed back from `gate` onto its taxiway, before taxiing to `runway`
+    Pushback {
+        gate: Arc<Gate>,
+        runway: Arc<Runway>,
+    },
+    TaxiOut {
+        runway: Arc<Runway>,
+    },
+    /// Waiting short of `runway` for takeoff clearance
+    HoldingPoint {
+        runway: Arc<Runway>,
+    },
+    Takeoff {
+        runway: Arc<Runway>,
+    },
     Cruise,
     Descent,
-    Landing { runway: Arc<Runway> },
+    /// Flying a racetrack around `fix` at `altitude` until cleared to land
+    Holding {
+        fix: Arc<HoldingFix>,
+        altitude: f32,
+    },
+    Landing {
+        runway: Arc<Runway>,
+    },
+    /// Climbing out over `runway` after abandoning a landing on it
+    GoAround {
+        runway: Arc<Runway>,
+    },
+    /// Flying the missed approach procedure of `runway`, after which the plane asks for a runway
+    /// again
+    MissedApproach {
+        runway: Arc<Runway>,
+    },
+    /// Taxiing from the runway to `gate` after landing
+    TaxiIn {
+        gate: Arc<Gate>,
+    },
+    Parked {
+        gate: Arc<Gate>,
+    },
 }
 
 impl PhaseData {
     #[must_use]
     pub const fn str(&self) -> &'static str {
         match self {
+            Self::Pushback { .. } => "Pushback",
+            Self::TaxiOut { .. } => "TaxiOut",
+            Self::HoldingPoint { .. } => "HoldingPoint",
             Self::Takeoff { .. } => "Takeoff",
             Self::Cruise => "Cruise",
             Self::Descent => "Descent",
+            Self::Holding { .. } => "Holding",
             Self::Landing { .. } => "Landing",
+            Self::GoAround { .. } => "GoAround",
+            Self::MissedApproach { .. } => "MissedApproach",
+            Self::TaxiIn { .. } => "TaxiIn",
+            Self::Parked { .. } => "Parked",
         }
     }
 }
 
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+)]
+#[ts(export)]
+pub struct Advisory {
+    /// The plane this one is avoiding
+    #[ts(as = "String")]
+    pub against: PlaneStateId,
+    pub sense: Sense,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+)]
+#[ts(export)]
+pub enum Sense {
+    Climb,
+    Descend,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+)]
+#[ts(export)]
+pub enum GoAroundReason {
+    /// Another plane is on the runway
+    RunwayOccupied,
+    /// Too high to make the touchdown point
+    TooHigh,
+    TooFast,
+}
+
 #[derive(
     Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
 )]
@@ -283,6 +814,7 @@ impl PhaseData {
 pub struct PlaneEvent {
     #[ts(as = "String")]
     pub from: AirportStateId,
+    pub time: f64,
     pub payload: PlaneEventPayload,
 }
 
@@ -293,6 +825,18 @@ pub struct PlaneEvent {
 #[non_exhaustive]
 pub enum PlaneEventPayload {
     ClearForLanding(Arc<Runway>),
+    ClearForTakeoff(Arc<Runway>),
+    /// Hold at `fix`, at `altitude`. Sent again when the plane moves down the stack.
+    Hold {
+        fix: Arc<HoldingFix>,
+        altitude: f32,
+    },
+    /// Leave the holding stack
+    Release,
+    /// Abandon the landing
+    GoAround(GoAroundReason),
+    /// Fly on to this airport instead, as the destination has no runway the plane can land on
+    Divert(Arc<AirportData>),
 }
 
 #[cfg(test)]
@@ -301,6 +845,7 @@ mod tests {
 
     use super::*;
     use crate::{
+        journal::Record,
         state::{airport::Airport, State},
         util::{Pos2, WaypointId},
         world_data::{AirportData, Flight, ModelMotion, PlaneData, Runway, Waypoint},
@@ -308,7 +853,7 @@ mod tests {
 
     #[test]
     fn go_around() {
-        let mut state = State::new(&[]);
+        let mut state = State::new(&[], 0, Clock::default());
         let runway = Arc::new(Runway {
             start: Pos2::ZERO,
             end: Pos2::new(50.0, 0.0),
@@ -319,43 +864,78 @@ mod tests {
             runways: Arc::new([Arc::clone(&runway)]),
             ..AirportData::default()
         })));
-        state.planes.push(Plane::new(
-            &Arc::new(PlaneData {
-                motion: ModelMotion {
-                    max_a: Vec2::new(5.0, 2.5),
-                    max_v: Vec2::new(50.0, 10.0),
-                    turning_radius: 50.0,
-                },
-                ..PlaneData::default()
-            }),
-            &Arc::new(Flight {
-                to: "ABC".into(),
-                ..Flight::default()
-            }),
-            &runway,
-            &WorldData::default(),
-        ));
+        let model = Arc::new(PlaneData {
+            motion: ModelMotion {
+                max_a: Vec2::new(5.0, 2.5),
+                max_v: Vec2::new(50.0, 10.0),
+                turning_radius: 50.0,
+            },
+            ..PlaneData::default()
+        });
+        let flight = Arc::new(Flight {
+            from: "ABC".into(),
+            to: "ABC".into(),
+            ..Flight::default()
+        });
+        // one plane standing on the runway, and another about to land on it
+        for pos in [Pos2::ZERO, Pos2::new(-150.0, 0.0)] {
+            let mut plane = Plane::new(
+                &model,
+                &flight,
+                &runway,
+                &Config::default(),
+                &WorldData::default(),
+                &state.clock,
+                &mut state.rng,
+            );
+            plane.pos.pos_ang.0 = pos.extend(0.0);
+            plane.pos.kinematics.x_target.clear();
+            state.planes.push(plane);
+        }
+        let landing = &mut state.planes[1];
+        landing.pos.pos_ang.0.z = 30.0;
+        landing.pos.kinematics.v = Vec2::new(30.0, 0.0);
+        landing.pos.planner.instructions.clear();
+        landing.pos.planner.route.clear();
+        landing.phase = PhaseData::Descent;
+        landing.events.push_back(PlaneEvent {
+            from: "ABC".into(),
+            time: 0.0,
+            payload: PlaneEventPayload::ClearForLanding(Arc::clone(&runway)),
+        });
+        let id = landing.id;
+        state.reindex();
         let config = Config {
             tick_duration: 1.0,
             plane_spawn_chance: 0.0,
             ..Default::default()
         };
+
+        let mut phases = vec![];
         for _ in 0..100 {
             state.tick(&config, &WorldData::default());
-            if state.planes.is_empty() {
-                break;
+            let phase = state.plane(&id).unwrap().phase.str();
+            if phases.last() != Some(&phase) {
+                phases.push(phase);
             }
-            // eprintln!(
-            //     "{:?}\n{:?}\n{:?}\n",
-            //     state.planes[0].pos.pos_ang, state.planes[0].phase, state.planes[0].pos.kinematics
-            // );
-            // eprintln!("{} {} {}", state.planes[0].pos.pos_ang.0.x, state.planes[0].pos.pos_ang.0.y, state.planes[0].pos.pos_ang.0.z);
         }
+        assert_eq!(
+            phases[..4],
+            ["Landing", "GoAround", "MissedApproach", "Descent"]
+        );
+        assert!(matches!(
+            state.journal.iter().find_map(|a| match a.record {
+                Record::GoAround { reason, .. } => Some(reason),
+                _ => None,
+            }),
+            Some(GoAroundReason::RunwayOccupied)
+        ));
+        assert!(state.stats.go_arounds >= 1);
     }
 
     #[test]
     fn two_waypoints() {
-        let mut state = State::new(&[]);
+        let mut state = State::new(&[], 0, Clock::default());
         let runway = Arc::new(Runway {
             start: Pos2::ZERO,
             end: Pos2::new(50.0, 0.0),
@@ -380,7 +960,10 @@ mod tests {
                 ..Flight::default()
             }),
             &runway,
+            &Config::default(),
             &WorldData::default(),
+            &state.clock,
+            &mut state.rng,
         ));
         state.planes[0]
             .pos
@@ -445,8 +1028,9 @@ mod tests {
                 pos: Pos2::new(50.0, -100.0),
                 connections: Arc::new([]),
             })]),
+            ..WorldData::default()
         };
-        let mut state = State::new(&[]);
+        let mut state = State::new(&[], 0, Clock::default());
         state.airports.push(Airport::new(airport_data));
         state.planes.push(Plane::new(
             &Arc::new(PlaneData {
@@ -462,7 +1046,10 @@ mod tests {
                 ..Flight::default()
             }),
             &runway,
+            &Config::default(),
             &wd,
+            &state.clock,
+            &mut state.rng,
         ));
         let config = Config {
             tick_duration: 0.25,
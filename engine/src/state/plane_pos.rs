@@ -1,25 +1,46 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+    collections::VecDeque,
+    f32::consts::{FRAC_PI_2, FRAC_PI_4, PI},
+    sync::Arc,
+};
 
 use dubins_paths::f32::DubinsPath;
 use glam::Vec2;
-use rand::{rng, RngExt};
+use itertools::Itertools;
+use rand::RngExt;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, trace};
 use ts_rs::TS;
 
 use crate::{
+    airspace::Airspace,
     config::Config,
+    procedure::{Procedure, ProcedureFix},
+    terrain::Terrain,
     util::{
         angle::Angle,
         direction::{PerpRot, Rotation},
         kinematics::Kinematics,
         pos::{Pos2Angle, Pos3Angle},
         ray::Ray,
-        Pos3,
+        rng::SimRng,
+        Pos2, Pos3,
     },
-    world_data::{ModelMotion, Waypoint},
+    world_data::{HoldingFix, ModelMotion, Waypoint},
 };
 
+/// Headings, relative to the direct one, from which a plane may arrive at a waypoint to keep out
+/// of airspace on the way, in the order they are tried.
+const LEG_HEADINGS: [f32; 7] = [
+    0.0,
+    -FRAC_PI_4,
+    FRAC_PI_4,
+    -FRAC_PI_2,
+    FRAC_PI_2,
+    -3.0 * FRAC_PI_4,
+    3.0 * FRAC_PI_4,
+];
+
 #[derive(
     Clone, Debug, Deserialize, Serialize, rkyv::Serialize, rkyv::Deserialize, rkyv::Archive, TS,
 )]
@@ -53,6 +74,8 @@ pub struct FlightPlanner {
     pub past_route: Vec<Arc<Waypoint>>,
     #[ts(as = "Vec<(f32, f32, f32)>")]
     pub past_pos: Vec<Pos3>,
+    /// SID or STAR whose fixes are in the route, flown within their constraints
+    pub procedure: Option<Arc<Procedure>>,
 }
 
 #[derive(
@@ -68,18 +91,80 @@ pub enum FlightInstruction {
     },
 }
 
+/// What a plane flies through on a tick.
+#[derive(Clone, Copy)]
+pub struct Surroundings<'a> {
+    pub config: &'a Config,
+    /// Air movement at the plane
+    pub wind: Vec2,
+    /// Active airspace that new legs keep out of where they can
+    pub airspace: &'a [&'a Airspace],
+    /// Ground that cruising altitudes clear
+    pub terrain: &'a Terrain,
+}
+
 impl PlanePos {
-    pub fn tick(&mut self, dt: f32, model_motion: ModelMotion, config: &Config) {
+    /// Moves the plane along its planned path through its `surroundings`. `change_altitude` lets
+    /// the planner set the cruising altitude for each new waypoint.
+    pub fn tick(
+        &mut self,
+        dt: f32,
+        model_motion: ModelMotion,
+        change_altitude: bool,
+        surroundings: &Surroundings,
+        rng: &mut SimRng,
+    ) {
         let ds = self.kinematics.tick(dt, model_motion);
+        let wind = surroundings.wind;
+        let (dsx, drift) = if wind == Vec2::ZERO {
+            (ds.x, Vec2::ZERO)
+        } else if self.on_track() {
+            (
+                self.ground_speed(self.kinematics.v.x, wind) * dt,
+                Vec2::ZERO,
+            )
+        } else {
+            (ds.x, wind * dt)
+        };
 
         let xz = self.planner.tick(
-            ds.x,
-            self.pos_ang.to_2(),
+            dsx,
+            self.pos_ang,
             model_motion,
-            Some((&mut self.kinematics, config, self.pos_ang.0.z)),
+            change_altitude.then_some(&mut self.kinematics),
+            surroundings,
+            rng,
         );
         self.planner.past_pos.push(self.pos_ang.0);
-        self.pos_ang = Pos3Angle(xz.0.extend(self.pos_ang.0.z + ds.y), xz.1);
+        self.pos_ang = Pos3Angle((xz.0 + drift).extend(self.pos_ang.0.z + ds.y), xz.1);
+    }
+    /// Whether the plane has a path to follow, which it keeps to in a crosswind by pointing into
+    /// it. Planes without one drift with the wind.
+    fn on_track(&self) -> bool {
+        !self.planner.instructions.is_empty() || !self.planner.route.is_empty()
+    }
+    /// Speed along the track flying at `airspeed` through `wind`, the crosswind taken out by
+    /// pointing into it. A headwind as strong as the plane holds it still rather than blowing it
+    /// back along the track.
+    fn ground_speed(&self, airspeed: f32, wind: Vec2) -> f32 {
+        let track = self.pos_ang.1.vec();
+        let cross = wind.perp_dot(track);
+        let along = airspeed
+            .mul_add(airspeed, -cross * cross)
+            .max(0.0)
+            .sqrt()
+            .copysign(airspeed);
+        (wind.dot(track) + along).max(0.0)
+    }
+    /// Velocity over the ground, flying through `wind`.
+    #[must_use]
+    pub fn ground_velocity(&self, wind: Vec2) -> Vec2 {
+        let track = self.pos_ang.1.vec();
+        if self.on_track() {
+            track * self.ground_speed(self.kinematics.v.x, wind)
+        } else {
+            track * self.kinematics.v.x + wind
+        }
     }
 }
 
@@ -92,50 +177,97 @@ impl FlightPlanner {
             ..Default::default()
         }
     }
-    #[tracing::instrument(skip(model_motion))]
+    /// Constraints on flying to `waypoint`, if it is a fix of [`Self::procedure`].
+    #[must_use]
+    pub fn constraint(&self, waypoint: &Waypoint) -> Option<&ProcedureFix> {
+        self.procedure.as_ref()?.fix(&waypoint.name)
+    }
+    #[tracing::instrument(skip(model_motion, surroundings, rng))]
     pub fn tick(
         &mut self,
         dsx: f32,
-        pos_ang: Pos2Angle,
+        pos: Pos3Angle,
         model_motion: ModelMotion,
-        mut altitude_changing: Option<(&mut Kinematics, &Config, f32)>,
+        mut altitude_changing: Option<&mut Kinematics>,
+        surroundings: &Surroundings,
+        rng: &mut SimRng,
     ) -> Pos2Angle {
+        let (pos_ang, z) = (pos.to_2(), pos.0.z);
         if self.instructions.is_empty() {
             if let Some(waypoint) = self.route.pop_front() {
                 debug!(?waypoint.name, "Planning new instructions");
-                let randomness = Vec2::new(
-                    rng().random_range(
-                        -2.0 * model_motion.turning_radius..2.0 * model_motion.turning_radius,
-                    ),
-                    rng().random_range(
-                        -2.0 * model_motion.turning_radius..2.0 * model_motion.turning_radius,
-                    ),
-                );
+                let constraint = self.constraint(&waypoint).cloned();
+                // procedures are flown exactly, and at full speed again once past any limit
+                let slowed = self
+                    .past_route
+                    .last()
+                    .and_then(|a| self.constraint(a))
+                    .is_some_and(|a| a.max_speed.is_some());
+                let spread = 2.0 * model_motion.turning_radius;
+                let randomness = if constraint.is_none() && spread > 0.0 && spread.is_finite() {
+                    Vec2::new(
+                        rng.random_range(-spread..spread),
+                        rng.random_range(-spread..spread),
+                    )
+                } else {
+                    Vec2::ZERO
+                };
                 let waypoint_pos_ang = Pos2Angle(
                     waypoint.pos + randomness,
                     Angle((waypoint.pos - pos_ang.0).to_angle()),
                 );
-                let mut path = DubinsPath::shortest_from(
-                    pos_ang.into(),
-                    waypoint_pos_ang.into(),
-                    model_motion.turning_radius,
-                )
-                .unwrap();
-                path.param[2] = 0.0;
-
-                if let Some((kinematics, config, z)) = &mut altitude_changing {
-                    if !self.past_route.is_empty() {
-                        kinematics.target_y(
-                            Some(0.0),
-                            Some(config.cruising_altitude(pos_ang.0, waypoint.pos) - *z),
-                            None,
-                            None,
-                            model_motion,
-                        );
+
+                let altitude = match &mut altitude_changing {
+                    Some(kinematics) => {
+                        let speed = constraint
+                            .as_ref()
+                            .and_then(|a| a.max_speed)
+                            .or_else(|| slowed.then_some(model_motion.max_v.x));
+                        if let Some(speed) = speed {
+                            kinematics.target_x(
+                                Some(speed.min(model_motion.max_v.x)),
+                                None,
+                                None,
+                                None,
+                                model_motion,
+                            );
+                        }
+                        if self.past_route.is_empty() {
+                            z
+                        } else {
+                            let altitude = surroundings.terrain.cruising_altitude(
+                                pos_ang.0,
+                                waypoint.pos,
+                                surroundings.config,
+                            );
+                            let altitude = constraint
+                                .as_ref()
+                                .map_or(altitude, |a| a.constrain_altitude(altitude));
+                            kinematics.target_y(
+                                Some(0.0),
+                                Some(altitude - z),
+                                None,
+                                None,
+                                model_motion,
+                            );
+                            altitude
+                        }
                     }
-                }
+                    None => z,
+                };
+                let path = Self::leg(
+                    pos_ang,
+                    waypoint_pos_ang,
+                    waypoint.pos,
+                    model_motion.turning_radius,
+                    surroundings.airspace,
+                    (z.min(altitude), z.max(altitude)),
+                );
 
-                self.instructions.push_back(FlightInstruction::Dubins(path));
+                self.instructions.push_back(path.map_or_else(
+                    || FlightInstruction::Straight(Ray::new(pos_ang.0, waypoint.pos)),
+                    FlightInstruction::Dubins,
+                ));
                 self.past_route.push(waypoint);
             } else {
                 trace!("Lost");
@@ -153,12 +285,165 @@ impl FlightPlanner {
             self.instruction_s = 0.0;
             self.past_instructions
                 .push(self.instructions.pop_front().unwrap());
-            self.tick(dsx2, pos_ang2, model_motion, altitude_changing)
+            self.tick(
+                dsx2,
+                Pos3Angle(pos_ang2.0.extend(z), pos_ang2.1),
+                model_motion,
+                altitude_changing,
+                surroundings,
+                rng,
+            )
+        }
+    }
+    /// Dubins path from `pos_ang` to `target`, leaving off the final turn. If it passes through
+    /// any of `airspace` between the `altitudes` flown, arrivals at `waypoint` itself from other
+    /// headings are tried instead, and the first to stay clear is taken. If none does, the one
+    /// flying the least distance through the airspace is. `None` if there is no path at all.
+    #[expect(clippy::cast_sign_loss)]
+    fn leg(
+        pos_ang: Pos2Angle,
+        target: Pos2Angle,
+        waypoint: Pos2,
+        radius: f32,
+        airspace: &[&Airspace],
+        altitudes: (f32, f32),
+    ) -> Option<DubinsPath> {
+        let path = |to: Pos2Angle| {
+            let mut path = shortest_path(pos_ang, to, radius)?;
+            path.param[2] = 0.0;
+            Some(path)
+        };
+        // distance flown through the airspace
+        let penetration = |leg: &DubinsPath| {
+            // in chords short enough to follow the turns closely
+            let steps = (leg.length() / radius * 4.0).ceil().max(1.0) as usize;
+            (0..=steps)
+                .map(|i| Pos2Angle::from(leg.sample(leg.length() * i as f32 / steps as f32)).0)
+                .tuple_windows()
+                .filter(|(a, b)| {
+                    airspace
+                        .iter()
+                        .any(|airspace| airspace.blocks(*a, *b, altitudes.0, altitudes.1))
+                })
+                .map(|(a, b)| a.distance(b))
+                .sum::<f32>()
+        };
+
+        let first = path(target)?;
+        if airspace.is_empty() {
+            return Some(first);
+        }
+        let direct = (waypoint - pos_ang.0).to_angle();
+        let mut best = (penetration(&first), first);
+        for a in LEG_HEADINGS {
+            if best.0 <= 0.0 {
+                break;
+            }
+            let Some(leg) = path(Pos2Angle(waypoint, Angle(direct + a).clamp())) else {
+                continue;
+            };
+            let penetration = penetration(&leg);
+            if penetration < best.0 {
+                best = (penetration, leg);
+            }
         }
+        Some(best.1)
     }
 }
 
+/// Shortest Dubins path from `from` to `to`, if `radius` is a usable turning radius and there is
+/// one.
+fn shortest_path(from: Pos2Angle, to: Pos2Angle, radius: f32) -> Option<DubinsPath> {
+    (radius.is_finite() && radius > 0.0)
+        .then(|| DubinsPath::shortest_from(from.into(), to.into(), radius).ok())
+        .flatten()
+}
+
 impl FlightInstruction {
+    /// Shortest Dubins path from `from` to `to`, or a straight line between them where there is
+    /// none, as with a model whose turning radius isn't usable.
+    #[must_use]
+    pub fn dubins(from: Pos2Angle, to: Pos2Angle, radius: f32) -> Self {
+        shortest_path(from, to, radius)
+            .map_or_else(|| Self::Straight(Ray::new(from.0, to.0)), Self::Dubins)
+    }
+    /// One lap of the racetrack around `fix`, starting and ending at the fix on the inbound
+    /// heading.
+    #[must_use]
+    pub fn racetrack(fix: &HoldingFix, radius: f32) -> [Self; 4] {
+        let inbound = Ray::new(fix.start, fix.end);
+        let heading = Angle(inbound.vec.to_angle());
+        let (angle, side) = if fix.left_turns {
+            (Angle(PI), Rotation::Anticlockwise)
+        } else {
+            (Angle(-PI), Rotation::Clockwise)
+        };
+        let offset = heading.vec().perp_rot(side) * radius * 2.0;
+        [
+            Self::Turn {
+                origin: Pos2Angle(fix.end, heading),
+                angle,
+                radius,
+            },
+            Self::Straight(Ray {
+                tail: fix.end + offset,
+                vec: -inbound.vec,
+            }),
+            Self::Turn {
+                origin: Pos2Angle(fix.start + offset, Angle(heading.0 + PI).clamp()),
+                angle,
+                radius,
+            },
+            Self::Straight(inbound),
+        ]
+    }
+    /// Straights between consecutive `points`, with the corners between them rounded off by turns
+    /// of `radius`. Turns are tightened where the legs are too short for them.
+    #[must_use]
+    pub fn taxi(points: &[Pos2], radius: f32) -> Vec<Self> {
+        let legs = points
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| *b - *a)
+            .filter(|a| a.length() > f32::EPSILON)
+            .collect::<Vec<_>>();
+        // distance before and after each corner that its turn cuts off, and the turn
+        let corners = legs
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| {
+                let angle = a.angle_to(*b);
+                let tan = (angle.abs() / 2.0).tan();
+                let cut = (radius * tan).min(a.length().min(b.length()) / 2.0);
+                (cut, angle, cut / tan)
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = vec![];
+        let mut tail = points.first().copied().unwrap_or_default();
+        for (i, leg) in legs.iter().enumerate() {
+            let dir = leg.normalize();
+            let corner = tail + *leg;
+            let cut_before = i.checked_sub(1).map_or(0.0, |j| corners[j].0);
+            let cut_after = corners.get(i).map_or(0.0, |a| a.0);
+            let start = tail + dir * cut_before;
+            let end = corner - dir * cut_after;
+            if start.distance(end) > f32::EPSILON {
+                out.push(Self::Straight(Ray::new(start, end)));
+            }
+            if let Some(&(_, angle, radius)) = corners.get(i) {
+                if (radius * angle.abs()).is_normal() {
+                    out.push(Self::Turn {
+                        origin: Pos2Angle(end, Angle(dir.to_angle())),
+                        angle: Angle(angle),
+                        radius,
+                    });
+                }
+            }
+            tail = corner;
+        }
+        out
+    }
     #[must_use]
     pub fn length(&self) -> f32 {
         match self {
@@ -179,7 +464,7 @@ impl FlightInstruction {
         Some(match self {
             Self::Dubins(path) => path.sample(s).into(),
             Self::Straight(ray) => Pos2Angle(
-                ray.tail + ray.vec.normalize() * s,
+                ray.tail + ray.vec.normalize_or_zero() * s,
                 Angle(ray.vec.to_angle()),
             ),
             Self::Turn {
@@ -203,12 +488,12 @@ impl FlightInstruction {
 
 #[cfg(test)]
 mod tests {
-    use std::f32::consts::PI;
-
     use assertables::*;
 
+    use rand::SeedableRng;
+
     use super::*;
-    use crate::util::{Pos2, Pos3, WaypointId};
+    use crate::{airspace::AirspaceKind, util::WaypointId, weather::CellShape};
 
     #[test]
     fn waypoints() {
@@ -240,9 +525,20 @@ mod tests {
             turning_radius: 0.5,
         };
 
+        let mut rng = SimRng::seed_from_u64(0);
         for _ in 0..25 {
-            plane_pos.tick(1.0, model_motion, &Config::default());
-            // eprintln!("{:?}", plane_pos.pos_ang);
+            plane_pos.tick(
+                1.0,
+                model_motion,
+                true,
+                &Surroundings {
+                    config: &Config::default(),
+                    wind: Vec2::ZERO,
+                    airspace: &[],
+                    terrain: &Terrain::default(),
+                },
+                &mut rng,
+            );
             if plane_pos.planner.instructions.is_empty() {
                 assert_lt!(
                     plane_pos.pos_ang.to_2().0.distance(Pos2::new(10.0, 10.0)),
@@ -279,9 +575,20 @@ mod tests {
             turning_radius: 2.0,
         };
 
+        let mut rng = SimRng::seed_from_u64(0);
         for _ in 0..25 {
-            plane_pos.tick(1.0, model_motion, &Config::default());
-            // eprintln!("{:?}", plane_pos.pos_ang);
+            plane_pos.tick(
+                1.0,
+                model_motion,
+                true,
+                &Surroundings {
+                    config: &Config::default(),
+                    wind: Vec2::ZERO,
+                    airspace: &[],
+                    terrain: &Terrain::default(),
+                },
+                &mut rng,
+            );
             if plane_pos.planner.instructions.is_empty() {
                 assert_lt!(
                     plane_pos.pos_ang.to_2().0.distance(Pos2::new(10.0, 4.0)),
@@ -292,4 +599,250 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn wind() {
+        let plane_pos = |instructions: &[FlightInstruction]| PlanePos {
+            pos_ang: Pos3Angle(Pos3::ZERO, Angle(0.0)),
+            kinematics: Kinematics {
+                v: Vec2::new(1.0, 0.0),
+                ..Default::default()
+            },
+            planner: FlightPlanner::new(instructions.iter().cloned().collect(), VecDeque::new()),
+        };
+        let model_motion = ModelMotion {
+            max_a: Vec2::INFINITY,
+            max_v: Vec2::INFINITY,
+            turning_radius: 1.0,
+        };
+        let straight = [FlightInstruction::Straight(Ray::new(
+            Pos2::ZERO,
+            Pos2::new(100.0, 0.0),
+        ))];
+        let mut rng = SimRng::seed_from_u64(0);
+        let mut fly = |mut plane_pos: PlanePos, wind: Vec2| {
+            for _ in 0..10 {
+                plane_pos.tick(
+                    1.0,
+                    model_motion,
+                    false,
+                    &Surroundings {
+                        config: &Config::default(),
+                        wind,
+                        airspace: &[],
+                        terrain: &Terrain::default(),
+                    },
+                    &mut rng,
+                );
+            }
+            plane_pos.pos_ang.0.truncate()
+        };
+
+        // slowed by a headwind
+        let pos = fly(plane_pos(&straight), Vec2::new(-0.5, 0.0));
+        assert_lt!(pos.distance(Pos2::new(5.0, 0.0)), 1e-3);
+        // held still, not blown backwards, by one stronger than the plane
+        let headwind = Vec2::new(-1.5, 0.0);
+        assert_eq!(plane_pos(&straight).ground_velocity(headwind), Vec2::ZERO);
+        let pos = fly(plane_pos(&straight), headwind);
+        assert_lt!(pos.distance(Pos2::ZERO), 1e-3);
+        // and not moved at all by a tick that takes no time
+        let mut still = plane_pos(&straight);
+        still.tick(
+            0.0,
+            model_motion,
+            false,
+            &Surroundings {
+                config: &Config::default(),
+                wind: Vec2::new(0.3, 0.4),
+                airspace: &[],
+                terrain: &Terrain::default(),
+            },
+            &mut SimRng::seed_from_u64(0),
+        );
+        assert_eq!(still.pos_ang.0, Pos3::ZERO);
+        // kept on track in a crosswind, which takes some of the speed
+        let pos = fly(plane_pos(&straight), Vec2::new(0.0, 0.6));
+        assert_lt!(pos.distance(Pos2::new(8.0, 0.0)), 1e-3);
+        // blown off course with nothing to follow
+        let pos = fly(plane_pos(&[]), Vec2::new(0.0, 0.6));
+        assert_lt!(pos.distance(Pos2::new(10.0, 6.0)), 1e-3);
+    }
+
+    #[test]
+    fn leg_avoids_airspace() {
+        let airspace = Airspace {
+            name: "P1".into(),
+            kind: AirspaceKind::Prohibited,
+            shape: CellShape::Circle {
+                centre: Pos2::new(100.0, 0.0),
+                radius: 5.0,
+            },
+            floor: 0.0,
+            ceiling: 1000.0,
+            schedule: Arc::new([]),
+            period: None,
+        };
+        let inside = |path: &DubinsPath| {
+            (0..=1000).any(|i| {
+                let pos = Pos2Angle::from(path.sample(path.length() * i as f32 / 1000.0)).0;
+                airspace.contains(pos.extend(500.0))
+            })
+        };
+        let (from, waypoint) = (Pos2Angle(Pos2::ZERO, Angle(0.0)), Pos2::new(400.0, 0.0));
+        let target = Pos2Angle(waypoint, Angle(0.0));
+
+        // straight through the volume, unless it is to be kept out of
+        let leg = FlightPlanner::leg(from, target, waypoint, 50.0, &[], (500.0, 500.0));
+        assert!(inside(&leg.unwrap()));
+        let leg = FlightPlanner::leg(from, target, waypoint, 50.0, &[&airspace], (500.0, 500.0));
+        assert!(!inside(&leg.unwrap()));
+        // not diverted when flown above the volume
+        let leg = FlightPlanner::leg(from, target, waypoint, 50.0, &[&airspace], (1500.0, 1500.0));
+        assert!(inside(&leg.unwrap()));
+    }
+
+    #[test]
+    fn leg_least_through_airspace() {
+        // around the start and off to the left of it, so every leg flies through some of it
+        let airspace = Airspace {
+            name: "P1".into(),
+            kind: AirspaceKind::Prohibited,
+            shape: CellShape::Circle {
+                centre: Pos2::new(0.0, 30.0),
+                radius: 40.0,
+            },
+            floor: 0.0,
+            ceiling: 1000.0,
+            schedule: Arc::new([]),
+            period: None,
+        };
+        let through = |path: &DubinsPath| {
+            (0..=1000)
+                .map(|i| Pos2Angle::from(path.sample(path.length() * i as f32 / 1000.0)).0)
+                .tuple_windows()
+                .filter(|(a, b)| airspace.contains(a.midpoint(*b).extend(500.0)))
+                .map(|(a, b)| a.distance(b))
+                .sum::<f32>()
+        };
+        let (from, waypoint) = (Pos2Angle(Pos2::ZERO, Angle(0.0)), Pos2::new(400.0, 0.0));
+        let target = Pos2Angle(waypoint, Angle(0.0));
+
+        let direct = FlightPlanner::leg(from, target, waypoint, 50.0, &[], (500.0, 500.0)).unwrap();
+        let leg =
+            FlightPlanner::leg(from, target, waypoint, 50.0, &[&airspace], (500.0, 500.0)).unwrap();
+        // still through some, but less than flown directly
+        assert_gt!(through(&leg), 0.0);
+        assert_lt!(through(&leg), through(&direct));
+    }
+
+    #[test]
+    fn unusable_turning_radius() {
+        let (from, to) = (
+            Pos2Angle(Pos2::ZERO, Angle(0.0)),
+            Pos2Angle(Pos2::new(10.0, 10.0), Angle(PI)),
+        );
+        for radius in [0.0, -1.0, f32::NAN] {
+            let instruction = FlightInstruction::dubins(from, to, radius);
+            assert!(matches!(instruction, FlightInstruction::Straight(_)));
+            assert_lt!(instruction.end().0.distance(to.0), 1e-3);
+            assert!(FlightPlanner::leg(from, to, to.0, radius, &[], (0.0, 0.0)).is_none());
+
+            // flown straight to the waypoint
+            let mut plane_pos = PlanePos {
+                pos_ang: Pos3Angle(Pos3::ZERO, Angle(0.0)),
+                kinematics: Kinematics {
+                    v: Vec2::new(1.0, 0.0),
+                    ..Default::default()
+                },
+                planner: FlightPlanner::new(
+                    VecDeque::new(),
+                    VecDeque::from([Arc::new(Waypoint {
+                        name: WaypointId::default(),
+                        pos: to.0,
+                        connections: Arc::new([]),
+                    })]),
+                ),
+            };
+            let model_motion = ModelMotion {
+                max_a: Vec2::INFINITY,
+                max_v: Vec2::INFINITY,
+                turning_radius: radius,
+            };
+            let mut rng = SimRng::seed_from_u64(0);
+            for _ in 0..20 {
+                plane_pos.tick(
+                    1.0,
+                    model_motion,
+                    false,
+                    &Surroundings {
+                        config: &Config::default(),
+                        wind: Vec2::ZERO,
+                        airspace: &[],
+                        terrain: &Terrain::default(),
+                    },
+                    &mut rng,
+                );
+            }
+            assert!(plane_pos.planner.instructions.is_empty());
+            assert_eq!(plane_pos.planner.past_route.len(), 1);
+        }
+    }
+
+    #[test]
+    fn racetrack() {
+        for left_turns in [false, true] {
+            let fix = HoldingFix {
+                start: Pos2::new(0.0, 0.0),
+                end: Pos2::new(0.0, 100.0),
+                left_turns,
+                ..HoldingFix::default()
+            };
+            let lap = FlightInstruction::racetrack(&fix, 10.0);
+            let mut pos_ang = Pos2Angle(fix.end, Angle(PI / 2.0));
+            for instruction in &lap {
+                let start = instruction.sample(0.0).unwrap();
+                assert_lt!(start.0.distance(pos_ang.0), 1e-3);
+                assert_in_delta!((start.1 .0 - pos_ang.1 .0).cos(), 1.0, 1e-3);
+                pos_ang = instruction.end();
+            }
+            assert_lt!(pos_ang.0.distance(fix.end), 1e-3);
+            // the outbound leg is on the side the plane turns to
+            let outbound = lap[1].sample(0.0).unwrap().0;
+            assert_eq!(outbound.x > 0.0, !left_turns);
+        }
+    }
+
+    #[test]
+    fn taxi() {
+        let points = [
+            Pos2::new(0.0, 0.0),
+            Pos2::new(20.0, 0.0),
+            Pos2::new(20.0, 20.0),
+            Pos2::new(24.0, 20.0),
+            Pos2::new(24.0, 20.0),
+            Pos2::new(24.0, 40.0),
+        ];
+        let path = FlightInstruction::taxi(&points, 5.0);
+        let mut pos_ang = Pos2Angle(Pos2::ZERO, Angle(0.0));
+        for instruction in &path {
+            let start = instruction.sample(0.0).unwrap();
+            assert_lt!(start.0.distance(pos_ang.0), 1e-3);
+            assert_in_delta!((start.1 .0 - pos_ang.1 .0).cos(), 1.0, 1e-3);
+            pos_ang = instruction.end();
+        }
+        assert_lt!(pos_ang.0.distance(Pos2::new(24.0, 40.0)), 1e-3);
+        assert_in_delta!(pos_ang.1 .0, PI / 2.0, 1e-3);
+        // the turns onto and off the short leg are tightened to fit it
+        let radii = path
+            .iter()
+            .filter_map(|a| match a {
+                FlightInstruction::Turn { radius, .. } => Some(*radius),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(radii.len(), 3);
+        assert_in_delta!(radii[0], 5.0, 1e-3);
+        assert_in_delta!(radii[1], 2.0, 1e-3);
+    }
 }
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use tracing::info;
+
+use crate::{
+    config::Config,
+    state::{clock::Clock, plane::Plane},
+    world_data::WorldData,
+};
+
+impl Plane {
+    /// Adds the STAR from where the plane left the airways to the runways in use at its
+    /// destination to the end of its route, unless it has flown one already. Returns whether it
+    /// did.
+    pub(super) fn join_star(&mut self, config: &Config, wd: &WorldData, clock: &Clock) -> bool {
+        let planner = &self.pos.planner;
+        let (Some(to), Some(exit)) = (wd.airport(&self.flight.to), planner.past_route.last())
+        else {
+            return false;
+        };
+        if planner
+            .procedure
+            .as_ref()
+            .is_some_and(|a| to.stars.contains(a))
+        {
+            return false;
+        }
+        // the same runways the airport lands arrivals on
+        let runways = wd.runways_into_wind(
+            &self.model,
+            to.runways.iter().filter(|r| wd.can_land(&self.model, r)),
+            clock.elapsed,
+            config.runway_headwind_margin,
+        );
+        let Some(star) = to.star(&exit.name, &runways) else {
+            return false;
+        };
+        info!(%self.id, %star.name, "Joining STAR");
+        self.pos.planner.route.extend(star.waypoints());
+        self.pos.planner.procedure = Some(Arc::clone(star));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assertables::*;
+    use glam::Vec2;
+
+    use super::*;
+    use crate::{
+        fixtures::world,
+        procedure::{Procedure, ProcedureFix},
+        state::{plane::PhaseData, plane_pos::Surroundings, State},
+        terrain::Terrain,
+        util::{kinematics::Kinematics, pos::Pos3Angle, Pos2},
+        world_data::{AirportData, Flight, Waypoint},
+    };
+
+    /// FOO and BAR joined by airways, with a SID north from FOO to `W` and a STAR from `E` to the
+    /// approach of BAR. `X` is nearer to FOO than `W`, but off the SID.
+    fn world_with_procedures() -> WorldData {
+        let mut wd = world();
+        let waypoint = |name: &str, x: f32, y: f32, connections: &[&str]| {
+            Arc::new(Waypoint {
+                name: name.into(),
+                pos: Pos2::new(x, y),
+                connections: connections.iter().map(|a| (*a).into()).collect(),
+            })
+        };
+        wd.waypoints = Arc::new([
+            waypoint("X", 100.0, 100.0, &["E"]),
+            waypoint("W", 0.0, 600.0, &["E"]),
+            waypoint("E", 1000.0, -600.0, &["W", "X"]),
+        ]);
+        let procedure = |name: &str, waypoint: &str, fix: ProcedureFix| {
+            Arc::new(Procedure {
+                name: name.into(),
+                runways: Arc::new(["18/36".into()]),
+                waypoint: waypoint.into(),
+                fixes: Arc::new([fix]),
+            })
+        };
+        let sid = procedure(
+            "UP1",
+            "W",
+            ProcedureFix {
+                name: "UP1A".into(),
+                pos: Pos2::new(0.0, 300.0),
+                max_altitude: Some(300.0),
+                max_speed: Some(20.0),
+                ..ProcedureFix::default()
+            },
+        );
+        let star = procedure(
+            "DOWN1",
+            "E",
+            ProcedureFix {
+                name: "DOWN1A".into(),
+                pos: Pos2::new(1000.0, -300.0),
+                min_altitude: Some(2000.0),
+                ..ProcedureFix::default()
+            },
+        );
+        wd.airports = wd
+            .airports
+            .iter()
+            .zip([(Some(sid), None), (None, Some(star))])
+            .map(|(airport, (sid, star))| {
+                Arc::new(AirportData {
+                    sids: sid.into_iter().collect(),
+                    stars: star.into_iter().collect(),
+                    ..(**airport).clone()
+                })
+            })
+            .collect();
+        wd
+    }
+
+    fn names(route: impl IntoIterator<Item = Arc<Waypoint>>) -> Vec<String> {
+        route
+            .into_iter()
+            .map(|a| a.name.as_str().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn departure_and_arrival() {
+        let wd = world_with_procedures();
+        let config = Config::default();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let flight = Arc::new(Flight {
+            from: "FOO".into(),
+            to: "BAR".into(),
+            ..Flight::default()
+        });
+        let mut plane = Plane::new(
+            &wd.planes[0],
+            &flight,
+            &wd.airports[0].runways[0],
+            &config,
+            &wd,
+            &state.clock,
+            &mut state.rng,
+        );
+        // onto the airways by the SID, and off them at the start of the STAR
+        assert_eq!(
+            plane
+                .pos
+                .planner
+                .procedure
+                .as_ref()
+                .map(|a| a.name.as_str()),
+            Some("UP1")
+        );
+        assert_eq!(
+            names(plane.pos.planner.route.iter().cloned()),
+            ["UP1A", "W", "E"]
+        );
+
+        // at the end of the airways, the STAR is added, and only once
+        plane.phase = PhaseData::Cruise;
+        plane.pos.planner.past_route = plane.pos.planner.route.drain(..).collect();
+        assert!(plane.join_star(&config, &wd, &state.clock));
+        assert_eq!(
+            plane
+                .pos
+                .planner
+                .procedure
+                .as_ref()
+                .map(|a| a.name.as_str()),
+            Some("DOWN1")
+        );
+        assert_eq!(names(plane.pos.planner.route.iter().cloned()), ["DOWN1A"]);
+        plane
+            .pos
+            .planner
+            .past_route
+            .extend(plane.pos.planner.route.drain(..));
+        assert!(!plane.join_star(&config, &wd, &state.clock));
+    }
+
+    #[test]
+    fn constraints() {
+        let wd = world_with_procedures();
+        let config = Config::default();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let mut plane = Plane::new(
+            &wd.planes[0],
+            &Arc::new(Flight {
+                from: "FOO".into(),
+                to: "BAR".into(),
+                ..Flight::default()
+            }),
+            &wd.airports[0].runways[0],
+            &config,
+            &wd,
+            &state.clock,
+            &mut state.rng,
+        );
+        let motion = plane.model.motion;
+        let mut kinematics = Kinematics {
+            v: Vec2::new(motion.max_v.x, 0.0),
+            ..Kinematics::default()
+        };
+        let mut z = 1000.0;
+        // planning the leg to the next waypoint, then flying until the plane has settled
+        let mut fly = |plane: &mut Plane| {
+            plane.pos.planner.instructions.clear();
+            plane.pos.planner.tick(
+                0.0,
+                Pos3Angle(
+                    plane.pos.pos_ang.0.truncate().extend(z),
+                    plane.pos.pos_ang.1,
+                ),
+                motion,
+                Some(&mut kinematics),
+                &Surroundings {
+                    config: &config,
+                    wind: Vec2::ZERO,
+                    airspace: &[],
+                    terrain: &Terrain::default(),
+                },
+                &mut state.rng,
+            );
+            z += (0..1000)
+                .map(|_| kinematics.tick(1.0, motion).y)
+                .sum::<f32>();
+            (kinematics.v.x, z)
+        };
+
+        // past the runway, slowed and kept low towards the fix of the SID
+        plane
+            .pos
+            .planner
+            .past_route
+            .push(Arc::clone(&wd.waypoints[0]));
+        let (v, altitude) = fly(&mut plane);
+        assert_in_delta!(v, 20.0, 1e-3);
+        assert_in_delta!(altitude, 300.0, 1e-1);
+        // back to full speed beyond it
+        let (v, _) = fly(&mut plane);
+        assert_in_delta!(v, motion.max_v.x, 1e-3);
+
+        // kept high on the STAR, above where it would otherwise cruise
+        fly(&mut plane);
+        assert!(plane.join_star(&config, &wd, &Clock::default()));
+        let (_, altitude) = fly(&mut plane);
+        assert_in_delta!(altitude, 2000.0, 1e-1);
+    }
+}
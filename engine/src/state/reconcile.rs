@@ -0,0 +1,268 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+use derive_more::Display;
+use serde::Serialize;
+use smol_str::SmolStr;
+use tracing::warn;
+
+use crate::{
+    state::{
+        airport::Airport,
+        plane::{PhaseData, Plane, PlaneEventPayload},
+        plane_pos::FlightInstruction,
+        State,
+    },
+    util::{AirportCode, FlightCode, PlaneModelId, PlaneStateId},
+    world_data::{AirportData, Flight, Gate, Runway, WorldData},
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Reconciliation {
+    pub kept: Vec<PlaneStateId>,
+    pub replanned: Vec<PlaneStateId>,
+    pub dropped: Vec<(PlaneStateId, DropReason)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Display, Serialize)]
+pub enum DropReason {
+    #[display("plane model `{_0}` no longer exists")]
+    Model(PlaneModelId),
+    #[display("flight `{_0}` no longer exists")]
+    Flight(FlightCode),
+    #[display("airport `{_0}` no longer exists")]
+    Airport(AirportCode),
+    #[display("runway `{runway}` at `{airport}` no longer exists")]
+    Runway {
+        airport: AirportCode,
+        runway: SmolStr,
+    },
+    #[display("gate `{gate}` at `{airport}` no longer exists")]
+    Gate { airport: AirportCode, gate: SmolStr },
+}
+
+impl State {
+    /// Re-points every plane and airport at the objects in `wd`, dropping planes whose flight,
+    /// model, airports or runways no longer exist and replanning routes through removed waypoints.
+    pub fn reconcile(&mut self, wd: &WorldData) -> Reconciliation {
+        let mut out = Reconciliation::default();
+        self.planes.retain_mut(|plane| match plane.reconcile(wd) {
+            Ok(replanned) => {
+                out.kept.push(plane.id);
+                if replanned {
+                    out.replanned.push(plane.id);
+                }
+                true
+            }
+            Err(reason) => {
+                warn!(%plane.id, %reason, "Dropping plane");
+                out.dropped.push((plane.id, reason));
+                false
+            }
+        });
+
+        let kept = out.kept.iter().collect::<HashSet<_>>();
+        let old_airports = std::mem::take(&mut self.airports);
+        self.airports = wd
+            .airports
+            .iter()
+            .map(|data| {
+                let mut airport = Airport::new(Arc::clone(data));
+                if let Some(old) = old_airports.iter().find(|a| a.id == airport.id) {
+                    airport.events = old
+                        .events
+                        .iter()
+                        .filter(|e| kept.contains(&e.from))
+                        .cloned()
+                        .collect();
+                    airport.queue = old
+                        .queue
+                        .iter()
+                        .filter(|e| kept.contains(&e.from))
+                        .cloned()
+                        .collect();
+                    airport.holding = old
+                        .holding
+                        .iter()
+                        .filter(|a| kept.contains(&a.plane))
+                        .cloned()
+                        .collect();
+                    for runway in &mut airport.runways {
+                        if let Some(old) = old.runway_state(&runway.name) {
+                            runway.occupant = old.occupant.filter(|a| kept.contains(a));
+                            runway.vacated = old.vacated;
+                            runway.last_departure = old.last_departure.clone();
+                        }
+                    }
+                    for gate in &mut airport.gates {
+                        if let Some(old) = old.gates.iter().find(|a| a.name == gate.name) {
+                            gate.occupant = old.occupant.filter(|a| kept.contains(a));
+                        }
+                    }
+                }
+                airport
+            })
+            .collect();
+        self.reindex();
+        out
+    }
+}
+
+impl Plane {
+    fn reconcile(&mut self, wd: &WorldData) -> Result<bool, DropReason> {
+        let model = wd
+            .plane_model(&self.model.id)
+            .ok_or_else(|| DropReason::Model(self.model.id.clone()))?;
+        let flight = match &wd.flights {
+            Some(flights) => {
+                let same =
+                    |a: &&Arc<Flight>| a.code == self.flight.code && a.from == self.flight.from;
+                match flights.iter().filter(same).find(|a| a.to == self.flight.to) {
+                    Some(flight) => Arc::clone(flight),
+                    // diverted away from where the flight goes
+                    None => flights
+                        .iter()
+                        .find(same)
+                        .map(|a| {
+                            Arc::new(Flight {
+                                to: self.flight.to.clone(),
+                                ..(**a).clone()
+                            })
+                        })
+                        .ok_or_else(|| DropReason::Flight(self.flight.code.clone()))?,
+                }
+            }
+            None => Arc::clone(&self.flight),
+        };
+        let from = wd
+            .airport(&flight.from)
+            .ok_or_else(|| DropReason::Airport(flight.from.clone()))?;
+        let to = wd
+            .airport(&flight.to)
+            .ok_or_else(|| DropReason::Airport(flight.to.clone()))?;
+
+        let phase = match &self.phase {
+            PhaseData::Pushback { gate, runway } => PhaseData::Pushback {
+                gate: reconcile_gate(from, gate)?,
+                runway: reconcile_runway(from, runway)?,
+            },
+            PhaseData::TaxiOut { runway } => PhaseData::TaxiOut {
+                runway: reconcile_runway(from, runway)?,
+            },
+            PhaseData::HoldingPoint { runway } => PhaseData::HoldingPoint {
+                runway: reconcile_runway(from, runway)?,
+            },
+            PhaseData::Takeoff { runway } => PhaseData::Takeoff {
+                runway: reconcile_runway(from, runway)?,
+            },
+            PhaseData::Landing { runway } => PhaseData::Landing {
+                runway: reconcile_runway(to, runway)?,
+            },
+            PhaseData::GoAround { runway } => PhaseData::GoAround {
+                runway: reconcile_runway(to, runway)?,
+            },
+            PhaseData::MissedApproach { runway } => PhaseData::MissedApproach {
+                runway: reconcile_runway(to, runway)?,
+            },
+            PhaseData::TaxiIn { gate } => PhaseData::TaxiIn {
+                gate: reconcile_gate(to, gate)?,
+            },
+            PhaseData::Parked { gate } => PhaseData::Parked {
+                gate: reconcile_gate(to, gate)?,
+            },
+            phase => phase.clone(),
+        };
+        let mut events = self.events.clone();
+        for event in &mut events {
+            match &mut event.payload {
+                PlaneEventPayload::ClearForLanding(runway) => {
+                    *runway = reconcile_runway(to, runway)?;
+                }
+                PlaneEventPayload::ClearForTakeoff(runway) => {
+                    *runway = reconcile_runway(from, runway)?;
+                }
+                PlaneEventPayload::Divert(airport) => {
+                    *airport = wd
+                        .airport(&airport.code)
+                        .map(Arc::clone)
+                        .ok_or_else(|| DropReason::Airport(airport.code.clone()))?;
+                }
+                PlaneEventPayload::Hold { .. }
+                | PlaneEventPayload::Release
+                | PlaneEventPayload::GoAround(_) => {}
+            }
+        }
+
+        self.model = Arc::clone(model);
+        self.flight = flight;
+        self.phase = phase;
+        self.events = events;
+
+        Ok(self.reconcile_route(wd, from, to))
+    }
+    /// Re-points the route at the waypoints in `wd`, and the SID or STAR being flown at the one of
+    /// the same name, replanning the route if any of its waypoints no longer exist. Returns
+    /// whether it was replanned.
+    fn reconcile_route(&mut self, wd: &WorldData, from: &AirportData, to: &AirportData) -> bool {
+        // the fixes of a SID or STAR are kept as long as it still has them
+        let procedure = self.pos.planner.procedure.as_ref().and_then(|procedure| {
+            to.stars
+                .iter()
+                .chain(from.sids.iter())
+                .find(|a| a.name == procedure.name)
+                .map(Arc::clone)
+        });
+        let route = self
+            .pos
+            .planner
+            .route
+            .iter()
+            .filter_map(|a| {
+                procedure
+                    .as_ref()
+                    .and_then(|p| p.waypoints().find(|b| b.name == a.name))
+                    .or_else(|| wd.waypoint(&a.name).map(Arc::clone))
+            })
+            .collect::<VecDeque<_>>();
+        self.pos.planner.procedure = procedure;
+        if route.len() == self.pos.planner.route.len() {
+            self.pos.planner.route = route;
+            return false;
+        }
+        self.pos.planner.procedure = None;
+        let pos = self
+            .pos
+            .planner
+            .instructions
+            .back()
+            .map_or_else(|| self.pos.pos_ang.to_2(), FlightInstruction::end);
+        self.pos.planner.route = wd.find_route(pos, &self.model.motion, to);
+        true
+    }
+}
+
+fn reconcile_runway(airport: &AirportData, runway: &Runway) -> Result<Arc<Runway>, DropReason> {
+    airport
+        .runways
+        .iter()
+        .find(|a| a.name == runway.name)
+        .map(Arc::clone)
+        .ok_or_else(|| DropReason::Runway {
+            airport: airport.code.clone(),
+            runway: runway.name.clone(),
+        })
+}
+
+fn reconcile_gate(airport: &AirportData, gate: &Gate) -> Result<Arc<Gate>, DropReason> {
+    airport
+        .gates
+        .iter()
+        .find(|a| a.name == gate.name)
+        .map(Arc::clone)
+        .ok_or_else(|| DropReason::Gate {
+            airport: airport.code.clone(),
+            gate: gate.name.clone(),
+        })
+}
@@ -0,0 +1,185 @@
+use std::iter::once;
+
+use glam::Vec3Swizzles;
+use itertools::Itertools;
+use tracing::info;
+
+use crate::{
+    airspace::Obstacles,
+    config::Config,
+    journal::{JournalEntry, Record},
+    state::{
+        plane::{PhaseData, Plane},
+        State,
+    },
+    world_data::WorldData,
+};
+
+impl Plane {
+    /// Whether any of `obstacles` lies across what is left of the plane's route, flown at the
+    /// cruising altitude of each leg.
+    fn route_blocked(&self, obstacles: &Obstacles, config: &Config) -> bool {
+        let planner = &self.pos.planner;
+        once(self.pos.pos_ang.0.xy())
+            .chain(planner.instructions.back().map(|a| a.end().0))
+            .chain(planner.route.iter().map(|a| a.pos))
+            .tuple_windows()
+            .any(|(a, b)| obstacles.blocks(a, b, config))
+    }
+}
+
+impl State {
+    /// Re-routes cruising planes around the weather cells and active airspace lying across their
+    /// remaining route, leaving any SID or STAR they are on. A plane keeps its route if there is
+    /// no way around.
+    pub(super) fn tick_reroutes(&mut self, config: &Config, wd: &WorldData) {
+        let obstacles = wd.obstacles_at(self.clock.elapsed);
+        if obstacles.is_empty() {
+            return;
+        }
+        for plane in &mut self.planes {
+            if !matches!(plane.phase, PhaseData::Cruise)
+                || plane.pos.planner.route.is_empty()
+                || !plane.route_blocked(&obstacles, config)
+            {
+                continue;
+            }
+            let Some(to) = wd.airport(&plane.flight.to) else {
+                continue;
+            };
+            let route = wd.find_route_avoiding(
+                plane.pos.pos_ang.to_2(),
+                &plane.model.motion,
+                to,
+                |a, b| obstacles.blocks(a, b, config),
+            );
+            if route.is_empty() {
+                continue;
+            }
+            info!(id = %plane.id, "Re-routing around weather or airspace");
+            plane.pos.planner.instructions.clear();
+            plane.pos.planner.instruction_s = 0.0;
+            plane.pos.planner.route = route;
+            // any STAR is joined afresh at the end of the new route
+            plane.pos.planner.procedure = None;
+            self.stats.reroutes += 1;
+            self.journal.push(JournalEntry {
+                tick: self.clock.tick,
+                record: Record::Reroute { plane: plane.id },
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        airspace::{Activation, Airspace, AirspaceKind},
+        fixtures::world,
+        state::clock::Clock,
+        util::{angle::Angle, pos::Pos3Angle, Pos2},
+        weather::{CellShape, WeatherCell},
+        world_data::{Flight, Waypoint},
+    };
+
+    #[test]
+    fn reroute() {
+        let waypoint = |name: &str, x: f32, y: f32, connections: &[&str]| {
+            Arc::new(Waypoint {
+                name: name.into(),
+                pos: Pos2::new(x, y),
+                connections: connections.iter().map(|a| (*a).into()).collect(),
+            })
+        };
+        let shape = CellShape::Circle {
+            centre: Pos2::new(500.0, 100.0),
+            radius: 100.0,
+        };
+        let mut weather = world();
+        // the direct way from A to C passes over the obstacle, and the way round by B does not
+        weather.waypoints = Arc::new([
+            waypoint("A", 300.0, 100.0, &["B", "C"]),
+            waypoint("B", 500.0, 600.0, &["A", "C"]),
+            waypoint("C", 700.0, 100.0, &["A", "B"]),
+        ]);
+        let mut airspace = weather.clone();
+        weather.weather = Arc::new([Arc::new(WeatherCell {
+            name: "CB".into(),
+            shape: shape.clone(),
+            floor: 0.0,
+            ceiling: 10000.0,
+            velocity: Pos2::ZERO,
+            start: 10.0,
+            growth: 0.0,
+            mature: 1000.0,
+            decay: 0.0,
+        })]);
+        airspace.airspace = Arc::new([Arc::new(Airspace {
+            name: "R1".into(),
+            kind: AirspaceKind::Military,
+            shape,
+            floor: 0.0,
+            ceiling: 10000.0,
+            schedule: Arc::new([Activation {
+                start: 10.0,
+                end: 1000.0,
+            }]),
+            period: None,
+        })]);
+        let config = Config {
+            plane_spawn_chance: 0.0,
+            ..Config::default()
+        };
+
+        for wd in [weather, airspace] {
+            let mut state = State::new(&wd.airports, 0, Clock::default());
+            let flight = Arc::new(Flight {
+                from: "FOO".into(),
+                to: "BAR".into(),
+                ..Flight::default()
+            });
+            let mut plane = Plane::new(
+                &wd.planes[0],
+                &flight,
+                &wd.airports[0].runways[0],
+                &config,
+                &wd,
+                &state.clock,
+                &mut state.rng,
+            );
+            plane.pos.pos_ang = Pos3Angle(Pos2::new(100.0, 100.0).extend(1000.0), Angle(0.0));
+            plane.pos.planner.instructions.clear();
+            plane.pos.planner.route = wd.waypoints.iter().step_by(2).cloned().collect();
+            plane.phase = PhaseData::Cruise;
+            state.planes.push(plane);
+            state.reindex();
+
+            let names = |state: &State| {
+                state.planes[0]
+                    .pos
+                    .planner
+                    .route
+                    .iter()
+                    .map(|a| a.name.as_str().to_owned())
+                    .collect::<Vec<_>>()
+            };
+            // the cell has not formed, or the airspace is not active, yet
+            state.tick_reroutes(&config, &wd);
+            assert_eq!(names(&state), ["A", "C"]);
+            state.clock.elapsed = 20.0;
+            state.tick_reroutes(&config, &wd);
+            assert_eq!(names(&state), ["B"]);
+            assert_eq!(state.stats.reroutes, 1);
+            assert!(state
+                .journal
+                .iter()
+                .any(|a| matches!(a.record, Record::Reroute { .. })));
+            // the new route is clear of the obstacle
+            state.tick_reroutes(&config, &wd);
+            assert_eq!(state.stats.reroutes, 1);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Running counts of how the simulation went. They are kept with the state, so saves and rewinds
+/// restore them too.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+)]
+#[ts(export)]
+pub struct Stats {
+    /// Resolution advisories issued by collision avoidance
+    #[ts(type = "number")]
+    pub advisories: u64,
+    /// Landings abandoned by going around
+    #[ts(type = "number")]
+    pub go_arounds: u64,
+    /// Times a taxiing plane stopped to wait for the plane ahead of it
+    #[ts(type = "number")]
+    pub taxi_holds: u64,
+    /// Planes that left their gate as their next flight
+    #[ts(type = "number")]
+    pub turnarounds: u64,
+    /// Planes cleared to take off
+    #[ts(type = "number")]
+    pub departures: u64,
+    /// Seconds departures waited at the holding point for takeoff clearance, in total
+    pub departure_delay: f64,
+    /// Times a plane was re-routed around weather or active airspace
+    #[ts(type = "number")]
+    pub reroutes: u64,
+    /// Times a plane entered active airspace
+    #[ts(type = "number")]
+    pub infringements: u64,
+    /// Times an airborne plane came too close to the ground
+    #[ts(type = "number")]
+    pub terrain_warnings: u64,
+}
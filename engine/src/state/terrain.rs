@@ -0,0 +1,330 @@
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use ts_rs::TS;
+
+use crate::{
+    config::Config,
+    journal::{JournalEntry, Record},
+    state::{
+        plane::{PhaseData, Plane},
+        State,
+    },
+    terrain::Terrain,
+    util::{kinematics::Target, PlaneStateId},
+    world_data::WorldData,
+};
+
+/// An airborne plane closer to the ground than [`Config::terrain_warning_height`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TerrainWarning {
+    #[ts(as = "String")]
+    pub plane: PlaneStateId,
+    /// Height of the plane above the ground under it
+    pub height: f32,
+}
+
+impl Plane {
+    /// Whether the plane may come close to the ground without being warned: on the ground, on its
+    /// way down to a runway, or climbing away from the ground.
+    fn expected_near_ground(&self) -> bool {
+        !self.airborne()
+            || matches!(self.phase, PhaseData::Landing { .. })
+            || self.pos.kinematics.v.y > 0.0
+    }
+    /// Descends to `altitude` over the `ds` left of the plane's instructions, taking `dt`. Where
+    /// descending straight there would take the plane too close to the ground before its final
+    /// instruction, it first climbs or descends to clear that ground, and only descends the rest
+    /// of the way once past it. Ground no higher than `altitude` is left to the approach.
+    #[expect(clippy::cast_sign_loss)]
+    pub(super) fn descend(
+        &mut self,
+        altitude: f32,
+        ds: f32,
+        dt: f32,
+        config: &Config,
+        terrain: &Terrain,
+    ) {
+        let motion = self.model.motion;
+        let z = self.pos.pos_ang.0.z;
+        let profile = |s: f32| (altitude - z).mul_add(s / ds, z);
+
+        // distance to the last stretch of ground the straight descent is too low over, and the
+        // altitude that clears everything up to it
+        let mut clear = None;
+        let mut level = f32::MIN;
+        let mut s = 0.0;
+        let mut from = self.pos.pos_ang.0.truncate();
+        let instructions = &self.pos.planner.instructions;
+        for instruction in instructions
+            .iter()
+            .take(instructions.len().saturating_sub(1))
+        {
+            let length = instruction.length();
+            // in chords short enough to follow the turns closely
+            let steps = (length / motion.turning_radius).ceil().max(1.0) as usize;
+            for i in 1..=steps {
+                let along = (length * i as f32 / steps as f32).min(length);
+                let to = instruction
+                    .sample(along)
+                    .unwrap_or_else(|| instruction.end())
+                    .0;
+                let floor = terrain.safe_altitude(from, to, config);
+                level = level.max(floor);
+                if terrain.highest(from, to) > altitude && profile(s + along) < floor {
+                    clear = Some((s + along, level));
+                }
+                from = to;
+            }
+            s += length;
+        }
+
+        let Some((clear, level)) = clear else {
+            self.pos
+                .kinematics
+                .target_y(Some(0.0), Some(altitude - z), Some(dt), None, motion);
+            return;
+        };
+        let level_dt = Target::sum_t(
+            self.pos
+                .kinematics
+                .target_y(Some(0.0), Some(level - z), None, None, motion)
+                .iter(),
+        );
+        let clear_dt = (dt * clear / ds).max(level_dt);
+        self.pos.kinematics.y_target.push(Target {
+            a: 0.0,
+            dt: clear_dt - level_dt,
+        });
+        self.pos.kinematics.y_target.extend(Target::new(
+            Some(0.0),
+            Some(altitude - level),
+            Some((dt - clear_dt).max(config.tick_duration)),
+            motion.max_v.y,
+            motion.max_a.y,
+            0.0,
+        ));
+    }
+}
+
+/// Finds every airborne plane closer to the ground than [`Config::terrain_warning_height`],
+/// other than those landing or climbing.
+#[must_use]
+pub fn detect(planes: &[Plane], config: &Config, wd: &WorldData) -> Vec<TerrainWarning> {
+    planes
+        .iter()
+        .filter(|plane| !plane.expected_near_ground())
+        .filter_map(|plane| {
+            let pos = plane.pos.pos_ang.0;
+            let height = pos.z - wd.terrain.elevation(pos.truncate());
+            (height < config.terrain_warning_height).then_some(TerrainWarning {
+                plane: plane.id,
+                height,
+            })
+        })
+        .collect()
+}
+
+impl State {
+    /// Replaces [`Self::terrain_warnings`] with the ones found now, counting and journaling those
+    /// for planes that were not warned on the last tick.
+    pub(super) fn tick_terrain_warnings(&mut self, config: &Config, wd: &WorldData) {
+        let warnings = detect(&self.planes, config, wd);
+        for warning in &warnings {
+            if self
+                .terrain_warnings
+                .iter()
+                .any(|a| a.plane == warning.plane)
+            {
+                continue;
+            }
+            warn!(%warning.plane, warning.height, "Terrain warning");
+            self.stats.terrain_warnings += 1;
+            self.journal.push(JournalEntry {
+                tick: self.clock.tick,
+                record: Record::TerrainWarning {
+                    plane: warning.plane,
+                },
+            });
+        }
+        self.terrain_warnings = warnings;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assertables::*;
+
+    use super::*;
+    use crate::{
+        fixtures::world,
+        state::{clock::Clock, plane_pos::FlightInstruction},
+        terrain::TerrainArea,
+        util::{angle::Angle, pos::Pos3Angle, ray::Ray, Pos2},
+        weather::CellShape,
+        world_data::Flight,
+    };
+
+    #[test]
+    fn terrain_warnings() {
+        let mut wd = world();
+        wd.terrain = Terrain {
+            grid: None,
+            areas: Arc::new([TerrainArea {
+                name: "Hill".into(),
+                shape: CellShape::Circle {
+                    centre: Pos2::new(500.0, 100.0),
+                    radius: 100.0,
+                },
+                elevation: 500.0,
+            }]),
+        };
+        let config = Config::default();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let flight = Arc::new(Flight {
+            from: "FOO".into(),
+            to: "BAR".into(),
+            ..Flight::default()
+        });
+        // over the hill, clear of it, and climbing away from it
+        for (z, vz) in [(520.0, 0.0), (1000.0, 0.0), (520.0, 5.0)] {
+            let mut plane = Plane::new(
+                &wd.planes[0],
+                &flight,
+                &wd.airports[0].runways[0],
+                &config,
+                &wd,
+                &state.clock,
+                &mut state.rng,
+            );
+            plane.pos.pos_ang = Pos3Angle(Pos2::new(500.0, 100.0).extend(z), Angle(0.0));
+            plane.pos.kinematics.v.y = vz;
+            plane.phase = PhaseData::Cruise;
+            state.planes.push(plane);
+        }
+        state.reindex();
+        let low = state.planes[0].id;
+
+        // only counted once for as long as the plane stays low
+        state.tick_terrain_warnings(&config, &wd);
+        state.tick_terrain_warnings(&config, &wd);
+        assert_eq!(
+            state.terrain_warnings,
+            [TerrainWarning {
+                plane: low,
+                height: 20.0,
+            }]
+        );
+        assert_eq!(state.stats.terrain_warnings, 1);
+        assert_eq!(
+            state
+                .journal
+                .iter()
+                .filter(|a| matches!(a.record, Record::TerrainWarning { .. }))
+                .count(),
+            1
+        );
+
+        state.planes[0].pos.pos_ang.0.z = 1000.0;
+        state.tick_terrain_warnings(&config, &wd);
+        assert!(state.terrain_warnings.is_empty());
+    }
+
+    #[test]
+    fn descent_clears_terrain() {
+        let mut wd = world();
+        let runway = Arc::clone(&wd.airports[1].runways[0]);
+        let gate = runway.approach_gate();
+        let config = Config::default();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let mut plane = Plane::new(
+            &wd.planes[0],
+            &Arc::new(Flight::default()),
+            &runway,
+            &config,
+            &wd,
+            &state.clock,
+            &mut state.rng,
+        );
+        // high and far enough out to descend at an easy rate, with a hill halfway to the gate
+        let start = gate.0 - runway.ray().vec.normalize() * 4000.0;
+        let hill = start.lerp(gate.0, 0.5);
+        wd.terrain = Terrain {
+            grid: None,
+            areas: Arc::new([TerrainArea {
+                name: "Hill".into(),
+                shape: CellShape::Circle {
+                    centre: hill,
+                    radius: 200.0,
+                },
+                elevation: 900.0,
+            }]),
+        };
+        plane.pos.pos_ang = Pos3Angle(start.extend(1024.0), gate.1);
+        plane.pos.kinematics.v.y = 0.0;
+        plane.pos.planner.route.clear();
+        plane.pos.planner.instructions = [
+            FlightInstruction::Straight(Ray::new(start, gate.0)),
+            FlightInstruction::Straight(runway.ray()),
+        ]
+        .into();
+        plane.descend(runway.altitude, 5000.0, 500.0, &config, &wd.terrain);
+
+        // flown until past the hill, the plane never goes below the safe altitude over it
+        let mut lowest = f32::MAX;
+        let dt = 500.0 * 2000.0 / 5000.0 / 10.0;
+        for _ in 0..10 {
+            lowest = lowest.min(plane.pos.pos_ang.0.z);
+            plane.pos.pos_ang.0.z += plane.pos.kinematics.tick(dt, plane.model.motion).y;
+        }
+        assert!(lowest >= 900.0 + config.terrain_clearance - 1.0);
+    }
+
+    #[test]
+    fn descent_to_the_runway_over_low_ground() {
+        let wd = world();
+        let runway = Arc::clone(&wd.airports[1].runways[0]);
+        let gate = runway.approach_gate();
+        let config = Config::default();
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let mut plane = Plane::new(
+            &wd.planes[0],
+            &Arc::new(Flight::default()),
+            &runway,
+            &config,
+            &wd,
+            &state.clock,
+            &mut state.rng,
+        );
+        // low enough for the straight descent to pass within the clearance of the flat ground
+        // well before the gate
+        let start = gate.0 - runway.ray().vec.normalize() * 4000.0;
+        plane.pos.pos_ang = Pos3Angle(start.extend(300.0), gate.1);
+        plane.pos.kinematics.v.y = 0.0;
+        plane.pos.planner.route.clear();
+        plane.pos.planner.instructions = [
+            FlightInstruction::Straight(Ray::new(start, gate.0)),
+            FlightInstruction::Straight(runway.ray()),
+        ]
+        .into();
+        let mut straight = plane.pos.kinematics.clone();
+        straight.target_y(
+            Some(0.0),
+            Some(runway.altitude - 300.0),
+            Some(500.0),
+            None,
+            plane.model.motion,
+        );
+        plane.descend(runway.altitude, 5000.0, 500.0, &config, &wd.terrain);
+
+        // the ground is no higher than the runway, so it is left to the approach
+        let targets = &plane.pos.kinematics.y_target;
+        assert_eq!(targets.len(), straight.y_target.len());
+        for (a, b) in targets.iter().zip(&straight.y_target) {
+            assert_in_delta!(a.a, b.a, 1e-6);
+            assert_in_delta!(a.dt, b.dt, 1e-6);
+        }
+    }
+}
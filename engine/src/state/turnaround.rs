@@ -0,0 +1,240 @@
+use std::sync::Arc;
+
+use rand::prelude::*;
+use smol_str::SmolStr;
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::Config,
+    journal::{JournalEntry, Record},
+    state::{
+        plane::{PhaseData, Plane},
+        State,
+    },
+    util::{FlightCode, PlaneStateId},
+    world_data::{AirportData, Flight, PlaneData, WorldData},
+};
+
+impl State {
+    /// Sends planes parked for [`Config::turnaround_time`] off as their next flight from the same
+    /// gate. Planes with no flight to take are removed, and returned.
+    pub(super) fn tick_turnarounds(
+        &mut self,
+        config: &Config,
+        wd: &WorldData,
+    ) -> Vec<PlaneStateId> {
+        let now = self.clock.now();
+        let due = self
+            .planes
+            .iter()
+            .filter(|a| {
+                matches!(a.phase, PhaseData::Parked { .. })
+                    && now - a.phase_start >= f64::from(config.turnaround_time)
+            })
+            .map(|a| a.id)
+            .collect::<Vec<_>>();
+        let mut remove_list = vec![];
+        for id in due {
+            if !self.turn_around(id, config, wd) {
+                remove_list.push(id);
+            }
+        }
+        if !remove_list.is_empty() {
+            self.planes.retain(|plane| !remove_list.contains(&plane.id));
+            self.reindex();
+        }
+        remove_list
+    }
+    /// Replaces a parked plane with the same plane, at the same gate, as a flight leaving from
+    /// there. Returns `false` if there is no such flight. The plane stays parked while the wind
+    /// rules out every runway it could take off from.
+    fn turn_around(&mut self, id: PlaneStateId, config: &Config, wd: &WorldData) -> bool {
+        let Some(plane) = self.plane(&id) else {
+            return true;
+        };
+        let PhaseData::Parked { gate } = &plane.phase else {
+            return true;
+        };
+        let (model, airline, gate) = (
+            Arc::clone(&plane.model),
+            plane.flight.airline.clone(),
+            Arc::clone(gate),
+        );
+        let Some(data) = self
+            .airport(&plane.flight.to)
+            .map(|a| Arc::clone(&a.airport))
+        else {
+            return false;
+        };
+        let Some(flight) = self.next_flight(&model, &data, airline, wd) else {
+            info!(%id, "No next flight");
+            return false;
+        };
+        let runways = data
+            .runways
+            .iter()
+            .filter(|r| wd.can_take_off(&model, r))
+            .collect::<Vec<_>>();
+        if runways.is_empty() {
+            warn!(%id, %flight.from, "No runway fits plane model");
+            return false;
+        }
+        let Some(runway) = wd
+            .runways_into_wind(
+                &model,
+                runways,
+                self.clock.elapsed,
+                config.runway_headwind_margin,
+            )
+            .choose(&mut self.rng)
+            .map(|a| Arc::clone(a))
+        else {
+            debug!(%id, %flight.from, "Wind too strong for departure");
+            return true;
+        };
+        let Some(taxiway) = data.taxiway(&gate.taxiway).map(|a| a.pos) else {
+            return false;
+        };
+
+        let mut next = Plane::new(
+            &model,
+            &flight,
+            &runway,
+            config,
+            wd,
+            &self.clock,
+            &mut self.rng,
+        );
+        next.id = id;
+        next.park(&gate, taxiway, &runway);
+        info!(%id, %flight.code, %flight.from, %flight.to, "Turnaround");
+        self.stats.turnarounds += 1;
+        self.journal.push(JournalEntry {
+            tick: self.clock.tick,
+            record: Record::Turnaround {
+                plane: id,
+                flight: flight.code.clone(),
+                from: flight.from.clone(),
+                to: flight.to.clone(),
+                runway: runway.name.clone(),
+            },
+        });
+        if let Some(plane) = self.plane_mut(&id) {
+            *plane = next;
+        }
+        true
+    }
+    /// A flight from `from` for the model, or one to a random airport it can land at if the world
+    /// has no flights.
+    fn next_flight(
+        &mut self,
+        model: &PlaneData,
+        from: &AirportData,
+        airline: SmolStr,
+        wd: &WorldData,
+    ) -> Option<Arc<Flight>> {
+        if let Some(flights) = &wd.flights {
+            return flights
+                .iter()
+                .filter(|a| a.from == from.code && a.plane.contains(&model.id))
+                .collect::<Vec<_>>()
+                .choose(&mut self.rng)
+                .map(|a| Arc::clone(a));
+        }
+        let to = wd
+            .airports
+            .iter()
+            .filter(|a| a.runways.iter().any(|r| wd.can_land(model, r)))
+            .collect::<Vec<_>>()
+            .choose(&mut self.rng)
+            .map(|a| a.code.clone())?;
+        Some(Arc::new(Flight {
+            airline,
+            code: FlightCode::default(),
+            from: from.code.clone(),
+            to,
+            plane: Arc::new([model.id.clone()]),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::{clock::Clock, ground::tests::ground_world},
+        util::pos::Pos3Angle,
+    };
+
+    fn parked(wd: &WorldData) -> State {
+        let mut state = State::new(&wd.airports, 0, Clock::default());
+        let foo = &wd.airports[0];
+        let (runway, gate) = (&foo.runways[0], &foo.gates[0]);
+        let mut plane = Plane::new(
+            &wd.planes[0],
+            &Arc::new(Flight {
+                from: "BAR".into(),
+                to: "FOO".into(),
+                ..Flight::default()
+            }),
+            runway,
+            &Config::default(),
+            wd,
+            &state.clock,
+            &mut state.rng,
+        );
+        plane.pos.pos_ang = Pos3Angle(gate.pos.extend(0.0), plane.pos.pos_ang.1);
+        plane.phase = PhaseData::Parked {
+            gate: Arc::clone(gate),
+        };
+        state.airports[0].occupy_gate(&gate.name, plane.id);
+        state.planes.push(plane);
+        state.reindex();
+        state
+    }
+
+    #[test]
+    fn turnaround() {
+        let wd = ground_world();
+        let config = Config {
+            plane_spawn_chance: 0.0,
+            turnaround_time: 60.0,
+            ..Config::default()
+        };
+        let mut state = parked(&wd);
+        let id = state.planes[0].id;
+        for _ in 0..60 {
+            assert!(state.tick(&config, &wd).0.is_empty());
+        }
+        assert!(matches!(state.planes[0].phase, PhaseData::Parked { .. }));
+
+        state.tick(&config, &wd);
+        let plane = &state.planes[0];
+        assert_eq!(plane.id, id);
+        assert_eq!(plane.flight.from, "FOO");
+        assert!(matches!(plane.phase, PhaseData::Pushback { .. }));
+        assert_eq!(state.airports[0].gate_of(&id).unwrap().name, "1");
+        assert_eq!(state.stats.turnarounds, 1);
+        assert!(state
+            .journal
+            .iter()
+            .any(|a| matches!(a.record, Record::Turnaround { plane, .. } if plane == id)));
+    }
+
+    #[test]
+    fn no_next_flight() {
+        let mut wd = ground_world();
+        wd.flights = Some(Arc::new([]));
+        let config = Config {
+            plane_spawn_chance: 0.0,
+            turnaround_time: 0.0,
+            ..Config::default()
+        };
+        let mut state = parked(&wd);
+        let id = state.planes[0].id;
+        assert_eq!(state.tick(&config, &wd).0, [id]);
+        assert!(state.planes.is_empty());
+        state.tick(&config, &wd);
+        assert!(state.airports[0].gates.iter().all(|a| a.occupant.is_none()));
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use ts_rs::TS;
+
+use crate::{config::Config, util::Pos2, weather::CellShape, world_data::empty};
+
+/// Height of the ground, which planes keep [`Config::terrain_clearance`] above. Flat at zero
+/// everywhere if unset.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+#[serde(default)]
+pub struct Terrain {
+    pub grid: Option<TerrainGrid>,
+    /// Areas of high ground, e.g. ridges or masts, raising the ground under them to their
+    /// elevation
+    pub areas: Arc<[TerrainArea]>,
+}
+
+impl Default for Terrain {
+    fn default() -> Self {
+        Self {
+            grid: None,
+            areas: empty(),
+        }
+    }
+}
+
+/// Elevations at points spaced evenly over a rectangle, in rows from `origin` along the y axis,
+/// each of `columns` points along the x axis.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct TerrainGrid {
+    #[ts(as = "(f32, f32)")]
+    pub origin: Pos2,
+    /// Distance between neighbouring points
+    pub spacing: f32,
+    #[ts(type = "number")]
+    pub columns: usize,
+    pub elevations: Arc<[f32]>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct TerrainArea {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    pub shape: CellShape,
+    pub elevation: f32,
+}
+
+impl Terrain {
+    /// Height of the ground at `pos`.
+    #[must_use]
+    pub fn elevation(&self, pos: Pos2) -> f32 {
+        self.areas
+            .iter()
+            .filter(|a| a.shape.contains(pos))
+            .map(|a| a.elevation)
+            .fold(self.grid.as_ref().map_or(0.0, |a| a.at(pos)), f32::max)
+    }
+    /// Height of the highest ground under the straight line from `a` to `b`.
+    #[must_use]
+    #[expect(clippy::cast_sign_loss)]
+    pub fn highest(&self, a: Pos2, b: Pos2) -> f32 {
+        let grid = self.grid.as_ref().map_or(0.0, |grid| {
+            // close enough together not to step over a point of the grid
+            let steps = (a.distance(b) / grid.spacing * 2.0).ceil().max(1.0) as usize;
+            (0..=steps)
+                .map(|i| grid.at(a.lerp(b, i as f32 / steps as f32)))
+                .fold(0.0, f32::max)
+        });
+        self.areas
+            .iter()
+            .filter(|area| area.shape.crosses(a, b))
+            .map(|area| area.elevation)
+            .fold(grid, f32::max)
+    }
+    /// Lowest altitude the straight line from `a` to `b` is safe to fly at, clear of the ground
+    /// under it by [`Config::terrain_clearance`].
+    #[must_use]
+    pub fn safe_altitude(&self, a: Pos2, b: Pos2, config: &Config) -> f32 {
+        self.highest(a, b) + config.terrain_clearance
+    }
+    /// [`Config::cruising_altitude`] from `a` to `b`, raised to [`Self::safe_altitude`] over high
+    /// ground.
+    #[must_use]
+    pub fn cruising_altitude(&self, a: Pos2, b: Pos2, config: &Config) -> f32 {
+        config
+            .cruising_altitude(a, b)
+            .max(self.safe_altitude(a, b, config))
+    }
+    /// Whether the ground under the straight line from `a` to `b` is too high to clear below
+    /// [`Config::max_altitude`], so the line must be flown around instead.
+    #[must_use]
+    pub fn blocks(&self, a: Pos2, b: Pos2, config: &Config) -> bool {
+        self.safe_altitude(a, b, config) > config.max_altitude
+    }
+}
+
+impl TerrainGrid {
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.elevations.len().checked_div(self.columns).unwrap_or(0)
+    }
+    /// Elevation at `pos`, interpolated between the four points around it. Zero outside the grid.
+    #[expect(clippy::cast_sign_loss)]
+    fn at(&self, pos: Pos2) -> f32 {
+        let (columns, rows) = (self.columns, self.rows());
+        let cell = (pos - self.origin) / self.spacing;
+        if !cell.is_finite()
+            || rows == 0
+            || cell.min_element() < 0.0
+            || cell.x > (columns - 1) as f32
+            || cell.y > (rows - 1) as f32
+        {
+            return 0.0;
+        }
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        let (x1, y1) = ((x + 1).min(columns - 1), (y + 1).min(rows - 1));
+        let e = |x: usize, y: usize| self.elevations[y * columns + x];
+        let t: Vec2 = cell - cell.floor();
+        let below = (e(x1, y) - e(x, y)).mul_add(t.x, e(x, y));
+        let above = (e(x1, y1) - e(x, y1)).mul_add(t.x, e(x, y1));
+        (above - below).mul_add(t.y, below)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assertables::*;
+
+    use super::*;
+
+    fn terrain() -> Terrain {
+        Terrain {
+            grid: Some(TerrainGrid {
+                origin: Pos2::ZERO,
+                spacing: 100.0,
+                columns: 3,
+                elevations: Arc::new([0.0, 0.0, 0.0, 0.0, 400.0, 0.0, 0.0, 0.0, 0.0]),
+            }),
+            areas: Arc::new([TerrainArea {
+                name: "Mast".into(),
+                shape: CellShape::Circle {
+                    centre: Pos2::new(500.0, 100.0),
+                    radius: 10.0,
+                },
+                elevation: 300.0,
+            }]),
+        }
+    }
+
+    #[test]
+    fn elevation() {
+        let terrain = terrain();
+        let at = |x: f32, y: f32| terrain.elevation(Pos2::new(x, y));
+        assert_in_delta!(at(100.0, 100.0), 400.0, 1e-3);
+        assert_in_delta!(at(50.0, 100.0), 200.0, 1e-3);
+        assert_in_delta!(at(150.0, 150.0), 100.0, 1e-3);
+        assert_in_delta!(at(500.0, 100.0), 300.0, 1e-3);
+        assert_in_delta!(at(-100.0, 100.0), 0.0, 1e-3);
+    }
+
+    #[test]
+    fn along_a_line() {
+        let terrain = terrain();
+        let config = Config::default();
+        let highest = |a: (f32, f32), b: (f32, f32)| {
+            terrain.highest(Pos2::new(a.0, a.1), Pos2::new(b.0, b.1))
+        };
+        // over the peak, past the mast, and clear of both
+        assert_in_delta!(highest((-100.0, 100.0), (300.0, 100.0)), 400.0, 1e-3);
+        assert_in_delta!(highest((400.0, 0.0), (600.0, 200.0)), 300.0, 1e-3);
+        assert_in_delta!(highest((-100.0, 300.0), (600.0, 300.0)), 0.0, 1e-3);
+
+        let (a, b) = (Pos2::new(-100.0, 100.0), Pos2::new(300.0, 100.0));
+        let safe = 400.0 + config.terrain_clearance;
+        assert_in_delta!(terrain.safe_altitude(a, b, &config), safe, 1e-3);
+        assert_in_delta!(
+            terrain.cruising_altitude(a, b, &config),
+            config.cruising_altitude(a, b).max(safe),
+            1e-3
+        );
+        assert!(!terrain.blocks(a, b, &config));
+        assert!(terrain.blocks(
+            a,
+            b,
+            &Config {
+                max_altitude: safe - 1.0,
+                ..Config::default()
+            }
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Position of each item of a list by its key. Lookups are checked against the list they are
+/// given, so an index that has gone stale falls back to a linear scan instead of returning the
+/// wrong item.
+#[derive(Clone, Debug)]
+pub struct Index<K>(HashMap<K, usize>);
+
+impl<K> Default for Index<K> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<K: Hash + Eq> PartialEq for Index<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Hash + Eq + Clone> Index<K> {
+    pub fn new<T, F: Fn(&T) -> &K>(items: &[T], key: F) -> Self {
+        let mut map = HashMap::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            // keep the first of duplicate keys, as a scan would find
+            map.entry(key(item).clone()).or_insert(i);
+        }
+        Self(map)
+    }
+    pub fn insert(&mut self, k: K, i: usize) {
+        self.0.insert(k, i);
+    }
+    pub fn get<T, F: Fn(&T) -> &K>(&self, items: &[T], k: &K, key: F) -> Option<usize> {
+        self.0
+            .get(k)
+            .copied()
+            .filter(|i| items.get(*i).is_some_and(|a| key(a) == k))
+            .or_else(|| items.iter().position(|a| key(a) == k))
+    }
+}
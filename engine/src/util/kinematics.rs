@@ -102,7 +102,7 @@ impl Target {
                 let decelerate_a = max_a.copysign(v - max_v);
                 let max_accelerate_ds = u.mul_add(-u, max_v.powi(2)) / accelerate_a / 2.0;
                 let max_decelerate_ds = max_v.mul_add(-max_v, v.powi(2)) / decelerate_a / 2.0;
-                if (max_accelerate_ds + max_decelerate_ds > -0.0
+                if (max_accelerate_ds + max_decelerate_ds >= 0.0
                     && ds > max_accelerate_ds + max_decelerate_ds)
                     || (max_accelerate_ds + max_decelerate_ds < 0.0
                         && ds < max_accelerate_ds + max_decelerate_ds)
@@ -132,6 +132,10 @@ impl Target {
                     ) / (accelerate_a - decelerate_a))
                         .sqrt()
                         .copysign(ds);
+                    if w.is_nan() {
+                        // too fast to stop within `ds`, so stop as soon as possible instead
+                        return Self::new(Some(v), None, None, max_v, max_a, u);
+                    }
                     let accelerate_dt = (w - u) / accelerate_a;
                     let decelerate_dt = (v - w) / decelerate_a;
                     vec![
@@ -389,4 +393,16 @@ mod tests {
         assert_in_delta!(k.v.x, 10.0, 1.0);
         assert_gt!(pos_ang.0.x, 50.0);
     }
+
+    #[test]
+    fn too_fast_to_stop() {
+        // no distance left to cover, and too little to slow down in, so the target speed is
+        // reached as soon as it can be instead
+        for (u, v, ds) in [(30.0, 30.0, 0.0), (-30.0, -10.0, -5.0)] {
+            let targets = Target::new(Some(v), Some(ds), None, 30.0, 5.0, u);
+            assert!(targets.iter().all(|a| a.a.is_finite() && a.dt.is_finite()));
+            assert_eq!(targets.len(), 1);
+            assert_in_delta!(targets[0].a.mul_add(targets[0].dt, u), v, 1e-3);
+        }
+    }
 }
@@ -4,9 +4,11 @@ use uuid::Uuid;
 
 pub mod angle;
 pub mod direction;
+pub mod index;
 pub mod kinematics;
 pub mod pos;
 pub mod ray;
+pub mod rng;
 
 pub type AirportCode = SmolStr;
 pub type FlightCode = SmolStr;
@@ -0,0 +1,756 @@
+use std::{collections::HashMap, fmt};
+
+use derive_more::Display;
+use itertools::Itertools;
+use serde::Serialize;
+use smol_str::SmolStr;
+
+use crate::{
+    util::{AirportCode, PlaneModelId, WaypointId},
+    weather::CellShape,
+    world_data::{AirportData, WorldData},
+};
+
+/// Something wrong with world data, found at `path` in the document, e.g. `flights[3].from`.
+#[derive(Clone, Debug, PartialEq, Serialize, Display)]
+#[display("{path}: {kind}")]
+pub struct Problem {
+    pub path: String,
+    pub kind: ProblemKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Display)]
+pub enum ProblemKind {
+    #[display("must not be empty")]
+    Empty,
+    #[display("duplicate `{_0}`, first defined at {_1}")]
+    Duplicate(SmolStr, String),
+    #[display("unknown airport `{_0}`")]
+    UnknownAirport(AirportCode),
+    #[display("unknown plane model `{_0}`")]
+    UnknownPlaneModel(PlaneModelId),
+    #[display("unknown waypoint `{_0}`")]
+    UnknownWaypoint(WaypointId),
+    #[display("unknown taxiway node `{_0}`")]
+    UnknownTaxiway(WaypointId),
+    #[display("unknown runway `{_0}`")]
+    UnknownRunway(SmolStr),
+    #[display("airport `{_0}` has no runways")]
+    NoRunways(AirportCode),
+    #[display("runway `{_0}` has no length")]
+    ZeroLengthRunway(SmolStr),
+    #[display("holding fix `{_0}` has no inbound leg")]
+    ZeroLengthHoldingFix(SmolStr),
+    #[display("no runway at `{_0}` fits plane model `{_1}`")]
+    NoCompatibleRunway(AirportCode, PlaneModelId),
+    #[display("must be positive, got {_0}")]
+    NotPositive(f32),
+    #[display("must be above the layer below, at {_0}")]
+    NotAscending(f32),
+    #[display("must have a multiple of {_0} entries, got {_1}")]
+    NotMultiple(usize, usize),
+    #[display("must be above the floor, at {_0}")]
+    BelowFloor(f32),
+    #[display("must have at least {_0} entries, got {_1}")]
+    TooFew(usize, usize),
+    #[display("must be after the start, at {_0}")]
+    NotAfterStart(f32),
+    #[display("must be within the period of {_0}")]
+    OutsidePeriod(f32),
+    #[display("must not be below the minimum, at {_0}")]
+    BelowMinimum(f32),
+}
+
+/// Every [`Problem`] found by [`WorldData::validate`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ValidationReport(pub Vec<Problem>);
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} problem(s) in world data", self.0.len())?;
+        for problem in &self.0 {
+            write!(f, "\n  {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl ValidationReport {
+    fn push(&mut self, path: impl Into<String>, kind: ProblemKind) {
+        self.0.push(Problem {
+            path: path.into(),
+            kind,
+        });
+    }
+    fn check_unique<'a>(
+        &mut self,
+        list: &str,
+        field: &str,
+        keys: impl IntoIterator<Item = &'a SmolStr>,
+    ) {
+        let mut seen = HashMap::new();
+        for (i, key) in keys.into_iter().enumerate() {
+            let path = format!("{list}[{i}].{field}");
+            if let Some(first) = seen.get(key) {
+                self.push(
+                    path,
+                    ProblemKind::Duplicate(key.clone(), String::clone(first)),
+                );
+            } else {
+                seen.insert(key, path);
+            }
+        }
+    }
+    fn check_positive(&mut self, path: impl Into<String>, value: f32) {
+        if value.partial_cmp(&0.0) != Some(std::cmp::Ordering::Greater) {
+            self.push(path, ProblemKind::NotPositive(value));
+        }
+    }
+    fn check_shape(&mut self, path: &str, shape: &CellShape) {
+        match shape {
+            CellShape::Circle { radius, .. } => {
+                self.check_positive(format!("{path}.radius"), *radius);
+            }
+            CellShape::Polygon { points } => {
+                if points.len() < 3 {
+                    self.push(
+                        format!("{path}.points"),
+                        ProblemKind::TooFew(3, points.len()),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl WorldData {
+    /// Checks everything the engine relies on without checking itself, returning every problem
+    /// found rather than just the first.
+    pub fn validate(&self) -> Result<(), ValidationReport> {
+        let mut report = ValidationReport::default();
+
+        self.validate_airports(&mut report);
+
+        if self.planes.is_empty() {
+            report.push("planes", ProblemKind::Empty);
+        }
+        report.check_unique("planes", "id", self.planes.iter().map(|a| &a.id));
+        for (i, plane) in self.planes.iter().enumerate() {
+            let path = format!("planes[{i}].motion");
+            report.check_positive(
+                format!("{path}.turning_radius"),
+                plane.motion.turning_radius,
+            );
+            report.check_positive(format!("{path}.max_v[0]"), plane.motion.max_v.x);
+            report.check_positive(format!("{path}.max_a[0]"), plane.motion.max_a.x);
+        }
+
+        self.validate_flights(&mut report);
+
+        report.check_unique("waypoints", "name", self.waypoints.iter().map(|a| &a.name));
+        for (i, waypoint) in self.waypoints.iter().enumerate() {
+            for (j, connection) in waypoint.connections.iter().enumerate() {
+                if self.waypoint(connection).is_none() {
+                    report.push(
+                        format!("waypoints[{i}].connections[{j}]"),
+                        ProblemKind::UnknownWaypoint(connection.clone()),
+                    );
+                }
+            }
+        }
+
+        self.validate_wind(&mut report);
+        self.validate_weather(&mut report);
+        self.validate_airspace(&mut report);
+        self.validate_terrain(&mut report);
+
+        if report.0.is_empty() {
+            Ok(())
+        } else {
+            Err(report)
+        }
+    }
+    fn validate_airports(&self, report: &mut ValidationReport) {
+        if self.airports.is_empty() {
+            report.push("airports", ProblemKind::Empty);
+        }
+        report.check_unique("airports", "code", self.airports.iter().map(|a| &a.code));
+        for (i, airport) in self.airports.iter().enumerate() {
+            if airport.runways.is_empty() {
+                report.push(
+                    format!("airports[{i}].runways"),
+                    ProblemKind::NoRunways(airport.code.clone()),
+                );
+            }
+            report.check_unique(
+                &format!("airports[{i}].runways"),
+                "name",
+                airport.runways.iter().map(|a| &a.name),
+            );
+            for (j, runway) in airport.runways.iter().enumerate() {
+                if runway.len() <= 0.0 {
+                    report.push(
+                        format!("airports[{i}].runways[{j}]"),
+                        ProblemKind::ZeroLengthRunway(runway.name.clone()),
+                    );
+                }
+            }
+            for (j, fix) in airport.holding_fixes.iter().enumerate() {
+                if fix.start == fix.end {
+                    report.push(
+                        format!("airports[{i}].holding_fixes[{j}]"),
+                        ProblemKind::ZeroLengthHoldingFix(fix.name.clone()),
+                    );
+                }
+            }
+            Self::validate_ground(i, airport, report);
+            self.validate_procedures(i, airport, report);
+        }
+    }
+    fn validate_wind(&self, report: &mut ValidationReport) {
+        for (i, (below, above)) in self.wind.layers.iter().tuple_windows().enumerate() {
+            if above.altitude <= below.altitude {
+                report.push(
+                    format!("wind.layers[{}].altitude", i + 1),
+                    ProblemKind::NotAscending(below.altitude),
+                );
+            }
+        }
+        if let Some(grid) = &self.wind.grid {
+            report.check_positive("wind.grid.spacing", grid.spacing);
+            report.check_positive("wind.grid.columns", grid.columns as f32);
+            if grid.velocities.len().checked_rem(grid.columns).unwrap_or(0) != 0 {
+                report.push(
+                    "wind.grid.velocities",
+                    ProblemKind::NotMultiple(grid.columns, grid.velocities.len()),
+                );
+            }
+        }
+        if let Some(variation) = &self.wind.variation {
+            report.check_positive("wind.variation.period", variation.period);
+        }
+    }
+    fn validate_weather(&self, report: &mut ValidationReport) {
+        report.check_unique("weather", "name", self.weather.iter().map(|a| &a.name));
+        for (i, cell) in self.weather.iter().enumerate() {
+            report.check_shape(&format!("weather[{i}].shape"), &cell.shape);
+            if cell.ceiling <= cell.floor {
+                report.push(
+                    format!("weather[{i}].ceiling"),
+                    ProblemKind::BelowFloor(cell.floor),
+                );
+            }
+        }
+    }
+    fn validate_airspace(&self, report: &mut ValidationReport) {
+        report.check_unique("airspace", "name", self.airspace.iter().map(|a| &a.name));
+        for (i, airspace) in self.airspace.iter().enumerate() {
+            report.check_shape(&format!("airspace[{i}].shape"), &airspace.shape);
+            if airspace.ceiling <= airspace.floor {
+                report.push(
+                    format!("airspace[{i}].ceiling"),
+                    ProblemKind::BelowFloor(airspace.floor),
+                );
+            }
+            if let Some(period) = airspace.period {
+                report.check_positive(format!("airspace[{i}].period"), period);
+            }
+            for (j, activation) in airspace.schedule.iter().enumerate() {
+                let path = format!("airspace[{i}].schedule[{j}]");
+                if activation.end <= activation.start {
+                    report.push(
+                        format!("{path}.end"),
+                        ProblemKind::NotAfterStart(activation.start),
+                    );
+                }
+                if let Some(period) = airspace.period {
+                    if activation.start < 0.0 || activation.end > period {
+                        report.push(path, ProblemKind::OutsidePeriod(period));
+                    }
+                }
+            }
+        }
+    }
+    fn validate_terrain(&self, report: &mut ValidationReport) {
+        if let Some(grid) = &self.terrain.grid {
+            report.check_positive("terrain.grid.spacing", grid.spacing);
+            report.check_positive("terrain.grid.columns", grid.columns as f32);
+            if grid.elevations.len().checked_rem(grid.columns).unwrap_or(0) != 0 {
+                report.push(
+                    "terrain.grid.elevations",
+                    ProblemKind::NotMultiple(grid.columns, grid.elevations.len()),
+                );
+            }
+        }
+        report.check_unique(
+            "terrain.areas",
+            "name",
+            self.terrain.areas.iter().map(|a| &a.name),
+        );
+        for (i, area) in self.terrain.areas.iter().enumerate() {
+            report.check_shape(&format!("terrain.areas[{i}].shape"), &area.shape);
+        }
+    }
+    fn validate_ground(i: usize, airport: &AirportData, report: &mut ValidationReport) {
+        report.check_unique(
+            &format!("airports[{i}].taxiways"),
+            "name",
+            airport.taxiways.iter().map(|a| &a.name),
+        );
+        for (j, node) in airport.taxiways.iter().enumerate() {
+            for (k, connection) in node.connections.iter().enumerate() {
+                if airport.taxiway(connection).is_none() {
+                    report.push(
+                        format!("airports[{i}].taxiways[{j}].connections[{k}]"),
+                        ProblemKind::UnknownTaxiway(connection.clone()),
+                    );
+                }
+            }
+        }
+        report.check_unique(
+            &format!("airports[{i}].gates"),
+            "name",
+            airport.gates.iter().map(|a| &a.name),
+        );
+        for (j, gate) in airport.gates.iter().enumerate() {
+            if airport.taxiway(&gate.taxiway).is_none() {
+                report.push(
+                    format!("airports[{i}].gates[{j}].taxiway"),
+                    ProblemKind::UnknownTaxiway(gate.taxiway.clone()),
+                );
+            }
+        }
+    }
+    fn validate_procedures(&self, i: usize, airport: &AirportData, report: &mut ValidationReport) {
+        for (field, procedures) in [("sids", &airport.sids), ("stars", &airport.stars)] {
+            let list = format!("airports[{i}].{field}");
+            report.check_unique(&list, "name", procedures.iter().map(|a| &a.name));
+            for (j, procedure) in procedures.iter().enumerate() {
+                let path = format!("{list}[{j}]");
+                for (k, runway) in procedure.runways.iter().enumerate() {
+                    if !airport.runways.iter().any(|a| a.name == *runway) {
+                        report.push(
+                            format!("{path}.runways[{k}]"),
+                            ProblemKind::UnknownRunway(runway.clone()),
+                        );
+                    }
+                }
+                if self.waypoint(&procedure.waypoint).is_none() {
+                    report.push(
+                        format!("{path}.waypoint"),
+                        ProblemKind::UnknownWaypoint(procedure.waypoint.clone()),
+                    );
+                }
+                for (k, fix) in procedure.fixes.iter().enumerate() {
+                    if let (Some(min), Some(max)) = (fix.min_altitude, fix.max_altitude) {
+                        if max < min {
+                            report.push(
+                                format!("{path}.fixes[{k}].max_altitude"),
+                                ProblemKind::BelowMinimum(min),
+                            );
+                        }
+                    }
+                    if let Some(max_speed) = fix.max_speed {
+                        report.check_positive(format!("{path}.fixes[{k}].max_speed"), max_speed);
+                    }
+                }
+            }
+        }
+    }
+    fn validate_flights(&self, report: &mut ValidationReport) {
+        if let Some(flights) = &self.flights {
+            if flights.is_empty() {
+                report.push("flights", ProblemKind::Empty);
+            }
+            for (i, flight) in flights.iter().enumerate() {
+                for (field, code) in [("from", &flight.from), ("to", &flight.to)] {
+                    if self.airport(code).is_none() {
+                        report.push(
+                            format!("flights[{i}].{field}"),
+                            ProblemKind::UnknownAirport(code.clone()),
+                        );
+                    }
+                }
+                if flight.plane.is_empty() {
+                    report.push(format!("flights[{i}].plane"), ProblemKind::Empty);
+                }
+                for (j, model) in flight.plane.iter().enumerate() {
+                    let path = format!("flights[{i}].plane[{j}]");
+                    let Some(model) = self.plane_model(model) else {
+                        report.push(path, ProblemKind::UnknownPlaneModel(model.clone()));
+                        continue;
+                    };
+                    let no_takeoff = self
+                        .airport(&flight.from)
+                        .is_some_and(|a| !a.runways.iter().any(|r| self.can_take_off(model, r)));
+                    let no_landing = self
+                        .airport(&flight.to)
+                        .is_some_and(|a| !a.runways.iter().any(|r| self.can_land(model, r)));
+                    if no_takeoff {
+                        report.push(
+                            path.clone(),
+                            ProblemKind::NoCompatibleRunway(flight.from.clone(), model.id.clone()),
+                        );
+                    }
+                    if no_landing {
+                        report.push(
+                            path,
+                            ProblemKind::NoCompatibleRunway(flight.to.clone(), model.id.clone()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        airspace::{Activation, Airspace, AirspaceKind},
+        fixtures::world,
+        procedure::{Procedure, ProcedureFix},
+        terrain::{Terrain, TerrainArea, TerrainGrid},
+        util::Pos2,
+        weather::{CellShape, WeatherCell},
+        wind::{Wind, WindGrid, WindLayer},
+        world_data::{AirportData, Flight, Gate, HoldingFix, Runway, Waypoint, WorldData},
+    };
+
+    /// Every problem [`WorldData::validate`] finds in `world`, as displayed.
+    fn problems(world: &WorldData) -> Vec<String> {
+        let report = world.validate().unwrap_err();
+        report.0.iter().map(ToString::to_string).collect()
+    }
+
+    /// Adds `airport` to `world`, after FOO and BAR at `airports[2]`.
+    fn add_airport(world: &mut WorldData, airport: AirportData) {
+        world.airports = world
+            .airports
+            .iter()
+            .cloned()
+            .chain([Arc::new(airport)])
+            .collect();
+    }
+
+    #[test]
+    fn valid() {
+        world().validate().unwrap();
+    }
+
+    #[test]
+    fn airports() {
+        let mut world = world();
+        add_airport(
+            &mut world,
+            AirportData {
+                code: "FOO".into(),
+                runways: Arc::new([]),
+                ..AirportData::default()
+            },
+        );
+        add_airport(
+            &mut world,
+            AirportData {
+                code: "BAZ".into(),
+                runways: Arc::new([Arc::new(Runway {
+                    start: Pos2::new(1.0, 1.0),
+                    end: Pos2::new(1.0, 1.0),
+                    ..Runway::default()
+                })]),
+                ..AirportData::default()
+            },
+        );
+        assert_eq!(
+            problems(&world),
+            [
+                "airports[2].code: duplicate `FOO`, first defined at airports[0].code",
+                "airports[2].runways: airport `FOO` has no runways",
+                "airports[3].runways[0]: runway `` has no length",
+            ]
+        );
+    }
+
+    #[test]
+    fn holding_fixes() {
+        let mut world = world();
+        let baz = AirportData {
+            code: "BAZ".into(),
+            holding_fixes: Arc::new([Arc::new(HoldingFix {
+                name: "BAZHL".into(),
+                ..HoldingFix::default()
+            })]),
+            ..(*world.airports[0]).clone()
+        };
+        add_airport(&mut world, baz);
+        assert_eq!(
+            problems(&world),
+            ["airports[2].holding_fixes[0]: holding fix `BAZHL` has no inbound leg"]
+        );
+    }
+
+    #[test]
+    fn ground() {
+        let mut world = world();
+        let baz = AirportData {
+            code: "BAZ".into(),
+            taxiways: Arc::new([Arc::new(Waypoint {
+                name: "T1".into(),
+                pos: Pos2::ZERO,
+                connections: Arc::new(["T2".into()]),
+            })]),
+            gates: Arc::new([Arc::new(Gate {
+                name: "G1".into(),
+                pos: Pos2::ZERO,
+                taxiway: "T3".into(),
+                ..Gate::default()
+            })]),
+            ..(*world.airports[0]).clone()
+        };
+        add_airport(&mut world, baz);
+        assert_eq!(
+            problems(&world),
+            [
+                "airports[2].taxiways[0].connections[0]: unknown taxiway node `T2`",
+                "airports[2].gates[0].taxiway: unknown taxiway node `T3`",
+            ]
+        );
+    }
+
+    #[test]
+    fn procedures() {
+        let mut world = world();
+        // a SID from a runway and to a waypoint that do not exist, with a fix whose altitude
+        // limits are the wrong way round and whose speed limit is zero
+        let baz = AirportData {
+            code: "BAZ".into(),
+            sids: Arc::new([Arc::new(Procedure {
+                name: "OUT1".into(),
+                runways: Arc::new(["09".into()]),
+                waypoint: "Z".into(),
+                fixes: Arc::new([ProcedureFix {
+                    name: "OUT1A".into(),
+                    pos: Pos2::ZERO,
+                    min_altitude: Some(200.0),
+                    max_altitude: Some(100.0),
+                    max_speed: Some(0.0),
+                }]),
+            })]),
+            ..(*world.airports[0]).clone()
+        };
+        add_airport(&mut world, baz);
+        assert_eq!(
+            problems(&world),
+            [
+                "airports[2].sids[0].runways[0]: unknown runway `09`",
+                "airports[2].sids[0].waypoint: unknown waypoint `Z`",
+                "airports[2].sids[0].fixes[0].max_altitude: must not be below the minimum, at 200",
+                "airports[2].sids[0].fixes[0].max_speed: must be positive, got 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn planes() {
+        let mut world = world();
+        let mut plane = (*world.planes[0]).clone();
+        plane.motion.turning_radius = 0.0;
+        world.planes = Arc::new([Arc::new(plane)]);
+        assert_eq!(
+            problems(&world),
+            ["planes[0].motion.turning_radius: must be positive, got 0"]
+        );
+    }
+
+    #[test]
+    fn flights() {
+        let mut world = world();
+        world.flights = Some(Arc::new([Arc::new(Flight {
+            from: "FOO".into(),
+            to: "QUX".into(),
+            plane: Arc::new(["B737".into()]),
+            ..Flight::default()
+        })]));
+        assert_eq!(
+            problems(&world),
+            [
+                "flights[0].to: unknown airport `QUX`",
+                "flights[0].plane[0]: unknown plane model `B737`",
+            ]
+        );
+    }
+
+    #[test]
+    fn runways_fit_planes() {
+        let mut world = world();
+        let mut plane = (*world.planes[0]).clone();
+        plane.min_takeoff_distance = 100.0;
+        plane.min_landing_distance = 100.0;
+        world.planes = Arc::new([Arc::new(plane)]);
+        world.flights = Some(Arc::new([Arc::new(Flight {
+            from: "BAR".into(),
+            to: "FOO".into(),
+            plane: Arc::new(["A320".into()]),
+            ..Flight::default()
+        })]));
+        assert_eq!(
+            problems(&world),
+            [
+                "flights[0].plane[0]: no runway at `BAR` fits plane model `A320`",
+                "flights[0].plane[0]: no runway at `FOO` fits plane model `A320`",
+            ]
+        );
+    }
+
+    #[test]
+    fn waypoints() {
+        let mut world = world();
+        world.waypoints = Arc::new([Arc::new(Waypoint {
+            name: "A".into(),
+            pos: Pos2::ZERO,
+            connections: Arc::new(["B".into()]),
+        })]);
+        assert_eq!(
+            problems(&world),
+            ["waypoints[0].connections[0]: unknown waypoint `B`"]
+        );
+    }
+
+    #[test]
+    fn wind() {
+        let mut world = world();
+        // layers out of order, and a grid with no spacing and a row cut short
+        world.wind = Wind {
+            layers: Arc::new([
+                WindLayer {
+                    altitude: 100.0,
+                    ..WindLayer::default()
+                },
+                WindLayer {
+                    altitude: 50.0,
+                    ..WindLayer::default()
+                },
+            ]),
+            grid: Some(WindGrid {
+                origin: Pos2::ZERO,
+                spacing: 0.0,
+                columns: 2,
+                velocities: Arc::new([Pos2::ZERO; 3]),
+            }),
+            variation: None,
+        };
+        assert_eq!(
+            problems(&world),
+            [
+                "wind.layers[1].altitude: must be above the layer below, at 100",
+                "wind.grid.spacing: must be positive, got 0",
+                "wind.grid.velocities: must have a multiple of 2 entries, got 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn weather() {
+        let mut world = world();
+        // a polygon with too few points and no height, and a second cell of the same name
+        let cell = WeatherCell {
+            name: "CB".into(),
+            shape: CellShape::Polygon {
+                points: Arc::new([Pos2::ZERO, Pos2::X]),
+            },
+            floor: 1000.0,
+            ceiling: 1000.0,
+            velocity: Pos2::ZERO,
+            start: 0.0,
+            growth: 0.0,
+            mature: 100.0,
+            decay: 0.0,
+        };
+        world.weather = Arc::new([
+            Arc::new(cell.clone()),
+            Arc::new(WeatherCell {
+                shape: CellShape::Circle {
+                    centre: Pos2::ZERO,
+                    radius: 10.0,
+                },
+                ceiling: 2000.0,
+                ..cell
+            }),
+        ]);
+        assert_eq!(
+            problems(&world),
+            [
+                "weather[1].name: duplicate `CB`, first defined at weather[0].name",
+                "weather[0].shape.points: must have at least 3 entries, got 2",
+                "weather[0].ceiling: must be above the floor, at 1000",
+            ]
+        );
+    }
+
+    #[test]
+    fn airspace() {
+        let mut world = world();
+        // a schedule that ends before it starts and runs past its period
+        world.airspace = Arc::new([Arc::new(Airspace {
+            name: "R1".into(),
+            kind: AirspaceKind::Military,
+            shape: CellShape::Circle {
+                centre: Pos2::ZERO,
+                radius: 10.0,
+            },
+            floor: 0.0,
+            ceiling: 1000.0,
+            schedule: Arc::new([
+                Activation {
+                    start: 100.0,
+                    end: 50.0,
+                },
+                Activation {
+                    start: 200.0,
+                    end: 5000.0,
+                },
+            ]),
+            period: Some(3600.0),
+        })]);
+        assert_eq!(
+            problems(&world),
+            [
+                "airspace[0].schedule[0].end: must be after the start, at 100",
+                "airspace[0].schedule[1]: must be within the period of 3600",
+            ]
+        );
+    }
+
+    #[test]
+    fn terrain() {
+        let mut world = world();
+        // a grid with a row cut short, and an area with no size
+        world.terrain = Terrain {
+            grid: Some(TerrainGrid {
+                origin: Pos2::ZERO,
+                spacing: 100.0,
+                columns: 2,
+                elevations: Arc::new([0.0; 3]),
+            }),
+            areas: Arc::new([TerrainArea {
+                name: "Mast".into(),
+                shape: CellShape::Circle {
+                    centre: Pos2::ZERO,
+                    radius: 0.0,
+                },
+                elevation: 300.0,
+            }]),
+        };
+        assert_eq!(
+            problems(&world),
+            [
+                "terrain.grid.elevations: must have a multiple of 2 entries, got 3",
+                "terrain.areas[0].shape.radius: must be positive, got 0",
+            ]
+        );
+    }
+}
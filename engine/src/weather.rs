@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use glam::Vec2;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use ts_rs::TS;
+
+use crate::{util::Pos2, world_data::WorldData};
+
+/// A storm that planes route around. It forms at `start`, grows for `growth` seconds, stays at
+/// full size for `mature` seconds and dies away over `decay` seconds, drifting at `velocity` all
+/// the while.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct WeatherCell {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    /// Outline at full size, where the cell forms
+    pub shape: CellShape,
+    /// Lowest altitude the cell reaches
+    pub floor: f32,
+    /// Highest altitude the cell reaches
+    pub ceiling: f32,
+    #[serde(default)]
+    #[ts(as = "(f32, f32)")]
+    pub velocity: Vec2,
+    /// Seconds into the simulation the cell forms
+    #[serde(default)]
+    pub start: f32,
+    #[serde(default)]
+    pub growth: f32,
+    pub mature: f32,
+    #[serde(default)]
+    pub decay: f32,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub enum CellShape {
+    Circle {
+        #[ts(as = "(f32, f32)")]
+        centre: Pos2,
+        radius: f32,
+    },
+    /// Corners in order around the outline
+    Polygon {
+        #[ts(as = "Vec<(f32, f32)>")]
+        points: Arc<[Pos2]>,
+    },
+}
+
+/// A weather cell as it is at some moment.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ActiveCell {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    pub shape: CellShape,
+    pub floor: f32,
+    pub ceiling: f32,
+}
+
+impl WeatherCell {
+    /// Fraction of its full size the cell is at `time`, if it exists then.
+    #[must_use]
+    pub fn size(&self, time: f64) -> Option<f32> {
+        let age = (time - f64::from(self.start)) as f32;
+        let dying = self.growth + self.mature;
+        let size = if age < 0.0 {
+            0.0
+        } else if age < self.growth {
+            age / self.growth
+        } else if age <= dying {
+            1.0
+        } else {
+            1.0 - (age - dying) / self.decay
+        };
+        (size > 0.0).then_some(size)
+    }
+    /// The cell as it is at `time`, moved and grown or shrunk about its centre, if it exists then.
+    #[must_use]
+    pub fn at(&self, time: f64) -> Option<ActiveCell> {
+        let size = self.size(time)?;
+        let offset = self.velocity * (time - f64::from(self.start)) as f32;
+        Some(ActiveCell {
+            name: self.name.clone(),
+            shape: self.shape.transform(offset, size),
+            floor: self.floor,
+            ceiling: self.ceiling,
+        })
+    }
+}
+
+impl CellShape {
+    #[must_use]
+    pub fn centre(&self) -> Pos2 {
+        match self {
+            Self::Circle { centre, .. } => *centre,
+            Self::Polygon { points } => points.iter().sum::<Pos2>() / points.len().max(1) as f32,
+        }
+    }
+    /// The shape moved by `offset` and scaled by `scale` about its centre.
+    #[must_use]
+    pub fn transform(&self, offset: Vec2, scale: f32) -> Self {
+        match self {
+            Self::Circle { centre, radius } => Self::Circle {
+                centre: *centre + offset,
+                radius: radius * scale,
+            },
+            Self::Polygon { points } => {
+                let centre = self.centre();
+                Self::Polygon {
+                    points: points
+                        .iter()
+                        .map(|a| centre + (*a - centre) * scale + offset)
+                        .collect(),
+                }
+            }
+        }
+    }
+    #[must_use]
+    pub fn contains(&self, pos: Pos2) -> bool {
+        match self {
+            Self::Circle { centre, radius } => pos.distance(*centre) <= *radius,
+            Self::Polygon { points } => {
+                // a ray from `pos` along the x axis crosses the outline an odd number of times
+                points
+                    .iter()
+                    .circular_tuple_windows()
+                    .filter(|(a, b)| {
+                        (a.y > pos.y) != (b.y > pos.y)
+                            && pos.x < (b.x - a.x) * (pos.y - a.y) / (b.y - a.y) + a.x
+                    })
+                    .count()
+                    % 2
+                    == 1
+            }
+        }
+    }
+    /// Whether the straight line from `a` to `b` passes through the shape.
+    #[must_use]
+    pub fn crosses(&self, a: Pos2, b: Pos2) -> bool {
+        match self {
+            Self::Circle { centre, radius } => {
+                let ab = b - a;
+                let t = ((*centre - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0);
+                let nearest = if t.is_finite() { a + ab * t } else { a };
+                nearest.distance(*centre) <= *radius
+            }
+            Self::Polygon { points } => {
+                self.contains(a)
+                    || points
+                        .iter()
+                        .circular_tuple_windows()
+                        .any(|(c, d)| segments_cross(a, b, *c, *d))
+            }
+        }
+    }
+}
+
+/// Whether the line from `a` to `b` meets the one from `c` to `d`.
+fn segments_cross(a: Pos2, b: Pos2, c: Pos2, d: Pos2) -> bool {
+    let (ab, cd, ac) = (b - a, d - c, c - a);
+    let denom = ab.perp_dot(cd);
+    if denom.abs() <= f32::EPSILON {
+        return false;
+    }
+    let along_ab = ac.perp_dot(cd) / denom;
+    let along_cd = ac.perp_dot(ab) / denom;
+    (0.0..=1.0).contains(&along_ab) && (0.0..=1.0).contains(&along_cd)
+}
+
+impl ActiveCell {
+    /// Whether the cell lies across the straight line from `a` to `b` flown at `altitude`.
+    #[must_use]
+    pub fn blocks(&self, a: Pos2, b: Pos2, altitude: f32) -> bool {
+        (self.floor..=self.ceiling).contains(&altitude) && self.shape.crosses(a, b)
+    }
+}
+
+impl WorldData {
+    /// The weather cells that exist at `time`, as they are then.
+    #[must_use]
+    pub fn weather_at(&self, time: f64) -> Vec<ActiveCell> {
+        self.weather.iter().filter_map(|a| a.at(time)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifecycle() {
+        let cell = WeatherCell {
+            name: "CB".into(),
+            shape: CellShape::Circle {
+                centre: Pos2::ZERO,
+                radius: 10.0,
+            },
+            floor: 0.0,
+            ceiling: 1000.0,
+            velocity: Vec2::new(1.0, 0.0),
+            start: 100.0,
+            growth: 10.0,
+            mature: 20.0,
+            decay: 10.0,
+        };
+        let radius = |time: f64| match cell.at(time)?.shape {
+            CellShape::Circle { centre, radius } => Some((centre.x, radius)),
+            CellShape::Polygon { .. } => None,
+        };
+        assert_eq!(radius(50.0), None);
+        assert_eq!(radius(100.0), None);
+        assert_eq!(radius(105.0), Some((5.0, 5.0)));
+        assert_eq!(radius(120.0), Some((20.0, 10.0)));
+        assert_eq!(radius(135.0), Some((35.0, 5.0)));
+        assert_eq!(radius(140.0), None);
+    }
+
+    #[test]
+    fn shapes() {
+        let square = CellShape::Polygon {
+            points: Arc::new([
+                Pos2::new(0.0, 0.0),
+                Pos2::new(10.0, 0.0),
+                Pos2::new(10.0, 10.0),
+                Pos2::new(0.0, 10.0),
+            ]),
+        };
+        assert!(square.contains(Pos2::new(5.0, 5.0)));
+        assert!(!square.contains(Pos2::new(15.0, 5.0)));
+        assert!(square.crosses(Pos2::new(-5.0, 5.0), Pos2::new(15.0, 5.0)));
+        assert!(square.crosses(Pos2::new(5.0, 5.0), Pos2::new(6.0, 6.0)));
+        assert!(!square.crosses(Pos2::new(-5.0, 15.0), Pos2::new(15.0, 15.0)));
+        let half = square.transform(Pos2::new(10.0, 0.0), 0.5);
+        assert!(half.contains(Pos2::new(14.0, 6.0)));
+        assert!(!half.contains(Pos2::new(12.0, 6.0)));
+
+        let circle = CellShape::Circle {
+            centre: Pos2::ZERO,
+            radius: 10.0,
+        };
+        assert!(circle.crosses(Pos2::new(-20.0, 5.0), Pos2::new(20.0, 5.0)));
+        assert!(!circle.crosses(Pos2::new(-20.0, 15.0), Pos2::new(20.0, 15.0)));
+        assert!(!circle.crosses(Pos2::new(15.0, 0.0), Pos2::new(25.0, 0.0)));
+        assert!(circle.crosses(Pos2::ZERO, Pos2::ZERO));
+    }
+}
@@ -0,0 +1,313 @@
+use std::{f32::consts::TAU, sync::Arc};
+
+use glam::{Vec2, Vec3Swizzles};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    util::{Pos2, Pos3},
+    world_data::{empty, PlaneData, Runway, WorldData},
+};
+
+/// Velocity of the air, which carries airborne planes along with it. Calm everywhere if unset.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+#[serde(default)]
+pub struct Wind {
+    /// Wind by altitude, from the lowest layer up. It is interpolated between layers, and the same
+    /// as the nearest layer above the highest or below the lowest.
+    pub layers: Arc<[WindLayer]>,
+    /// Local wind, added to that of the layers
+    pub grid: Option<WindGrid>,
+    /// How the whole field changes over time
+    pub variation: Option<WindVariation>,
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Self {
+            layers: empty(),
+            grid: None,
+            variation: None,
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct WindLayer {
+    pub altitude: f32,
+    #[ts(as = "(f32, f32)")]
+    pub velocity: Vec2,
+}
+
+/// Wind at points spaced evenly over a rectangle, in rows from `origin` along the y axis, each of
+/// `columns` points along the x axis.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct WindGrid {
+    #[ts(as = "(f32, f32)")]
+    pub origin: Pos2,
+    /// Distance between neighbouring points
+    pub spacing: f32,
+    #[ts(type = "number")]
+    pub columns: usize,
+    #[ts(as = "Vec<(f32, f32)>")]
+    pub velocities: Arc<[Vec2]>,
+}
+
+/// Makes the wind veer back and forth and strengthen and weaken, over and over.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct WindVariation {
+    /// Seconds before the wind is back to how it started
+    pub period: f32,
+    /// Furthest the wind turns either way, in radians
+    pub veer: f32,
+    /// Furthest the wind speed strays either way, as a fraction of it
+    pub gust: f32,
+}
+
+impl Wind {
+    /// Velocity of the air at `pos`, `time` seconds into the simulation.
+    #[must_use]
+    pub fn at(&self, pos: Pos3, time: f64) -> Vec2 {
+        let wind = self.layered(pos.z) + self.grid.as_ref().map_or(Vec2::ZERO, |a| a.at(pos.xy()));
+        self.variation.map_or(wind, |a| a.apply(wind, time))
+    }
+    fn layered(&self, altitude: f32) -> Vec2 {
+        let i = self.layers.partition_point(|a| a.altitude <= altitude);
+        match (
+            i.checked_sub(1).map(|j| self.layers[j]),
+            self.layers.get(i).copied(),
+        ) {
+            (Some(below), Some(above)) => below.velocity.lerp(
+                above.velocity,
+                (altitude - below.altitude) / (above.altitude - below.altitude),
+            ),
+            (Some(layer), None) | (None, Some(layer)) => layer.velocity,
+            (None, None) => Vec2::ZERO,
+        }
+    }
+}
+
+impl WindGrid {
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.velocities.len().checked_div(self.columns).unwrap_or(0)
+    }
+    /// Wind at `pos`, interpolated between the four points around it. None outside the grid.
+    #[expect(clippy::cast_sign_loss)]
+    fn at(&self, pos: Pos2) -> Vec2 {
+        let (columns, rows) = (self.columns, self.rows());
+        let cell = (pos - self.origin) / self.spacing;
+        if !cell.is_finite()
+            || rows == 0
+            || cell.min_element() < 0.0
+            || cell.x > (columns - 1) as f32
+            || cell.y > (rows - 1) as f32
+        {
+            return Vec2::ZERO;
+        }
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        let (x1, y1) = ((x + 1).min(columns - 1), (y + 1).min(rows - 1));
+        let v = |x: usize, y: usize| self.velocities[y * columns + x];
+        let t = cell - cell.floor();
+        v(x, y)
+            .lerp(v(x1, y), t.x)
+            .lerp(v(x, y1).lerp(v(x1, y1), t.x), t.y)
+    }
+}
+
+impl WindVariation {
+    fn apply(self, wind: Vec2, time: f64) -> Vec2 {
+        let phase = TAU * (time / f64::from(self.period)).fract() as f32;
+        let (sin, cos) = phase.sin_cos();
+        Vec2::from_angle(self.veer * sin).rotate(wind) * self.gust.mul_add(cos, 1.0)
+    }
+}
+
+impl WorldData {
+    /// Of `runways`, those `plane` takes off from or lands on into the wind at `time`: the ones
+    /// with the most headwind, give or take `margin`, of those whose crosswind it can take.
+    pub fn runways_into_wind<'a, I: IntoIterator<Item = &'a Arc<Runway>>>(
+        &self,
+        plane: &PlaneData,
+        runways: I,
+        time: f64,
+        margin: f32,
+    ) -> Vec<&'a Arc<Runway>> {
+        let runways = runways
+            .into_iter()
+            .filter_map(|r| {
+                let (headwind, crosswind) = r.wind_components(self.wind.at(r.start3(), time));
+                plane
+                    .max_crosswind
+                    .is_none_or(|a| crosswind <= a)
+                    .then_some((r, headwind))
+            })
+            .collect::<Vec<_>>();
+        let Some(most) = runways.iter().map(|a| a.1).max_by(f32::total_cmp) else {
+            return vec![];
+        };
+        runways
+            .into_iter()
+            .filter(|a| a.1 >= most - margin)
+            .map(|a| a.0)
+            .collect()
+    }
+}
+
+impl Runway {
+    /// Components of `wind` against the direction of the runway and across it. Headwind is
+    /// negative for a tailwind, and crosswind is never negative.
+    #[must_use]
+    pub fn wind_components(&self, wind: Vec2) -> (f32, f32) {
+        let dir = self.ray().vec.normalize_or_zero();
+        (-wind.dot(dir), wind.perp_dot(dir).abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assertables::*;
+
+    use super::*;
+
+    #[test]
+    fn layers_and_grid() {
+        let wind = Wind {
+            layers: Arc::new([
+                WindLayer {
+                    altitude: 0.0,
+                    velocity: Vec2::new(1.0, 0.0),
+                },
+                WindLayer {
+                    altitude: 100.0,
+                    velocity: Vec2::new(3.0, 0.0),
+                },
+            ]),
+            grid: Some(WindGrid {
+                origin: Pos2::new(0.0, 0.0),
+                spacing: 10.0,
+                columns: 2,
+                velocities: Arc::new([
+                    Vec2::ZERO,
+                    Vec2::new(0.0, 2.0),
+                    Vec2::ZERO,
+                    Vec2::new(0.0, 4.0),
+                ]),
+            }),
+            variation: None,
+        };
+        let at = |x: f32, y: f32, z: f32| wind.at(Pos3::new(x, y, z), 0.0);
+        assert_eq!(at(-5.0, 0.0, -50.0), Vec2::new(1.0, 0.0));
+        assert_eq!(at(-5.0, 0.0, 50.0), Vec2::new(2.0, 0.0));
+        assert_eq!(at(-5.0, 0.0, 500.0), Vec2::new(3.0, 0.0));
+        assert_eq!(at(5.0, 5.0, 0.0), Vec2::new(1.0, 1.5));
+        assert_eq!(at(10.0, 10.0, 0.0), Vec2::new(1.0, 4.0));
+        assert_eq!(at(10.1, 10.0, 0.0), Vec2::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn runways_into_wind() {
+        let runway = |name: &str, end: Pos2| {
+            Arc::new(Runway {
+                name: name.into(),
+                end,
+                ..Runway::default()
+            })
+        };
+        let runways = [
+            runway("N", Pos2::new(0.0, 100.0)),
+            runway("S", Pos2::new(0.0, -100.0)),
+            runway("E", Pos2::new(100.0, 0.0)),
+        ];
+        let mut plane = PlaneData::default();
+        let names = |wd: &WorldData, plane: &PlaneData| {
+            wd.runways_into_wind(plane, &runways, 0.0, 0.5)
+                .iter()
+                .map(|a| a.name.as_str().to_owned())
+                .collect::<Vec<_>>()
+        };
+        let mut wd = WorldData::default();
+        assert_eq!(names(&wd, &plane), ["N", "S", "E"]);
+
+        // mostly across the north-south runways, and a little against N
+        wd.wind.layers = Arc::new([WindLayer {
+            altitude: 0.0,
+            velocity: Vec2::new(3.0, -1.0),
+        }]);
+        assert_eq!(names(&wd, &plane), ["N"]);
+        // a tailwind is better than too much crosswind
+        plane.max_crosswind = Some(2.0);
+        assert_eq!(names(&wd, &plane), ["E"]);
+        plane.max_crosswind = Some(0.5);
+        assert!(names(&wd, &plane).is_empty());
+    }
+
+    #[test]
+    fn variation() {
+        let wind = Wind {
+            layers: Arc::new([WindLayer {
+                altitude: 0.0,
+                velocity: Vec2::new(2.0, 0.0),
+            }]),
+            grid: None,
+            variation: Some(WindVariation {
+                period: 100.0,
+                veer: std::f32::consts::FRAC_PI_2,
+                gust: 0.5,
+            }),
+        };
+        let at = |time: f64| wind.at(Pos3::ZERO, time);
+        assert_lt!(at(0.0).distance(Vec2::new(3.0, 0.0)), 1e-4);
+        assert_lt!(at(25.0).distance(Vec2::new(0.0, 2.0)), 1e-4);
+        assert_lt!(at(50.0).distance(Vec2::new(1.0, 0.0)), 1e-4);
+        assert_lt!(at(175.0).distance(Vec2::new(0.0, -2.0)), 1e-4);
+    }
+}
@@ -1,10 +1,11 @@
 use std::{
     cmp::Ordering,
-    collections::{HashMap, VecDeque},
+    collections::VecDeque,
     path::PathBuf,
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
+use dubins_paths::f32::DubinsPath;
 use eyre::{eyre, Result};
 use glam::Vec2;
 use itertools::Itertools;
@@ -13,14 +14,22 @@ use smol_str::SmolStr;
 use tracing::{trace, warn};
 use ts_rs::TS;
 
-use crate::util::{
-    pos::Pos2Angle, ray::Ray, AirportCode, Class, FlightCode, PlaneModelId, Pos2, Pos3, WaypointId,
+use crate::{
+    airspace::Airspace,
+    procedure::Procedure,
+    route::{RouteCache, WaypointGraph},
+    terrain::Terrain,
+    util::{
+        angle::Angle, index::Index, pos::Pos2Angle, ray::Ray, AirportCode, Class, FlightCode,
+        PlaneModelId, Pos2, Pos3, WaypointId,
+    },
+    weather::WeatherCell,
+    wind::Wind,
 };
 
 #[derive(
     Clone,
     Debug,
-    Default,
     Serialize,
     Deserialize,
     rkyv::Serialize,
@@ -31,42 +40,158 @@ use crate::util::{
 )]
 #[ts(export)]
 pub struct WorldData {
+    /// Lists of classes, each ordered from smallest to largest. A plane fits a runway of its own
+    /// class or of a larger one in the same list.
     #[ts(as = "Arc<[Arc<[String]>]>")]
     pub classes: Arc<[Arc<[Class]>]>,
     pub airports: Arc<[Arc<AirportData>]>,
     pub flights: Option<Arc<[Arc<Flight>]>>,
     pub planes: Arc<[Arc<PlaneData>]>,
     pub waypoints: Arc<[Arc<Waypoint>]>,
+    #[serde(default)]
+    pub wind: Wind,
+    #[serde(default = "empty")]
+    pub weather: Arc<[Arc<WeatherCell>]>,
+    /// Prohibited, military and temporarily restricted areas that planes keep out of
+    #[serde(default = "empty")]
+    pub airspace: Arc<[Arc<Airspace>]>,
+    #[serde(default)]
+    pub terrain: Terrain,
+    /// Lookup tables for [`Self::airport`], [`Self::waypoint`] and [`Self::plane_model`], and the
+    /// waypoint graph with its route cache and the taxiway graphs, built on first use
+    #[serde(skip)]
+    #[rkyv(with = rkyv::with::Skip)]
+    #[ts(skip)]
+    pub index: WorldIndex,
+}
+
+#[derive(Debug, Default)]
+pub struct WorldIndex(OnceLock<Box<WorldIndexInner>>);
+
+#[derive(Debug)]
+struct WorldIndexInner {
+    airports: Index<AirportCode>,
+    waypoints: Index<WaypointId>,
+    planes: Index<PlaneModelId>,
+    /// The waypoint list `graph` was built from, to tell when it has been replaced since
+    graph_source: Arc<[Arc<Waypoint>]>,
+    graph: WaypointGraph,
+    routes: RouteCache,
+    /// Taxiway graph of each airport, in the order of [`WorldData::airports`]
+    taxi_graphs: Vec<WaypointGraph>,
+}
+
+/// Clones start with an empty index, as their fields may be replaced independently.
+impl Clone for WorldIndex {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// The index is derived from the rest of the world, so it never makes two worlds differ.
+impl PartialEq for WorldIndex {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
 }
 
 impl WorldData {
+    fn index(&self) -> &WorldIndexInner {
+        self.index.0.get_or_init(|| {
+            Box::new(WorldIndexInner {
+                airports: Index::new(&self.airports, |a| &a.code),
+                waypoints: Index::new(&self.waypoints, |a| &a.name),
+                planes: Index::new(&self.planes, |a| &a.id),
+                graph_source: Arc::clone(&self.waypoints),
+                graph: WaypointGraph::new(&self.waypoints),
+                routes: RouteCache::default(),
+                taxi_graphs: self
+                    .airports
+                    .iter()
+                    .map(|a| WaypointGraph::new(&a.taxiways))
+                    .collect(),
+            })
+        })
+    }
+    /// Points to taxi through at `airport` from `from` to `to`, along its taxiways between the
+    /// nodes nearest to either. Both ends are included. The taxiway graphs of [`Self::airports`]
+    /// are built once with the index.
+    #[must_use]
+    pub fn taxi_route(&self, airport: &AirportData, from: Pos2, to: Pos2) -> Option<Vec<Pos2>> {
+        let index = self.index();
+        let uncached;
+        let graph = match index
+            .airports
+            .get(&self.airports, &airport.code, |a| &a.code)
+        {
+            Some(i) if Arc::ptr_eq(&self.airports[i].taxiways, &airport.taxiways) => {
+                &index.taxi_graphs[i]
+            }
+            _ => {
+                uncached = WaypointGraph::new(&airport.taxiways);
+                &uncached
+            }
+        };
+        airport.taxi_route(graph, from, to)
+    }
     #[must_use]
     pub fn cmp_class(&self, c1: &Class, c2: &Class) -> Option<Ordering> {
+        if c1 == c2 {
+            return Some(Ordering::Equal);
+        }
         for class_list in &*self.classes {
-            let Some(pos1) = class_list.iter().find(|a| *a == c1) else {
+            let Some(pos1) = class_list.iter().position(|a| a == c1) else {
                 continue;
             };
-            let Some(pos2) = class_list.iter().find(|a| *a == c2) else {
+            let Some(pos2) = class_list.iter().position(|a| a == c2) else {
                 continue;
             };
-            return Some(pos1.cmp(pos2));
+            return Some(pos1.cmp(&pos2));
         }
         None
     }
+    fn fits_class(&self, plane: &PlaneData, class: &Class) -> bool {
+        self.cmp_class(&plane.class, class)
+            .is_some_and(Ordering::is_le)
+    }
+    #[must_use]
+    pub fn can_take_off(&self, plane: &PlaneData, runway: &Runway) -> bool {
+        self.fits_class(plane, &runway.class) && runway.len() >= plane.min_takeoff_distance
+    }
+    #[must_use]
+    pub fn can_land(&self, plane: &PlaneData, runway: &Runway) -> bool {
+        self.fits_class(plane, &runway.class) && runway.len() >= plane.min_landing_distance
+    }
+    #[must_use]
+    pub fn can_park(&self, plane: &PlaneData, gate: &Gate) -> bool {
+        self.fits_class(plane, &gate.class)
+    }
     #[must_use]
     pub fn airport(&self, code: &AirportCode) -> Option<&Arc<AirportData>> {
-        self.airports.iter().find(|a| a.code == *code)
+        let i = self
+            .index()
+            .airports
+            .get(&self.airports, code, |a| &a.code)?;
+        Some(&self.airports[i])
     }
     #[must_use]
     pub fn waypoint(&self, name: &WaypointId) -> Option<&Arc<Waypoint>> {
-        self.waypoints.iter().find(|a| a.name == *name)
+        let i = self
+            .index()
+            .waypoints
+            .get(&self.waypoints, name, |a| &a.name)?;
+        Some(&self.waypoints[i])
+    }
+    #[must_use]
+    pub fn plane_model(&self, id: &PlaneModelId) -> Option<&Arc<PlaneData>> {
+        let i = self.index().planes.get(&self.planes, id, |a| &a.id)?;
+        Some(&self.planes[i])
     }
 }
 
 #[derive(
     Clone,
     Debug,
-    Default,
     Serialize,
     Deserialize,
     rkyv::Serialize,
@@ -82,6 +207,61 @@ pub struct AirportData {
     #[ts(as = "String")]
     pub code: AirportCode,
     pub runways: Arc<[Arc<Runway>]>,
+    /// Where arrivals wait for a runway. Planes hold at the start of the first runway's approach if
+    /// there are none.
+    #[serde(default = "empty")]
+    pub holding_fixes: Arc<[Arc<HoldingFix>]>,
+    /// Nodes of the taxiway network, joined by their connections. Planes spawn on and vanish from
+    /// the runways if there are none.
+    #[serde(default = "empty")]
+    pub taxiways: Arc<[Arc<Waypoint>]>,
+    #[serde(default = "empty")]
+    pub gates: Arc<[Arc<Gate>]>,
+    /// Departures from the runways to the airways
+    #[serde(default = "empty")]
+    pub sids: Arc<[Arc<Procedure>]>,
+    /// Arrivals from the airways to the runways. Arrivals leave the airways at the start of one if
+    /// there are any.
+    #[serde(default = "empty")]
+    pub stars: Arc<[Arc<Procedure>]>,
+}
+
+/// Empty lists from [`Default`] share one static allocation, which rkyv refuses to archive as two
+/// different types. Each list made here has an allocation of its own.
+pub(crate) fn empty<T>() -> Arc<[T]> {
+    Arc::from(Vec::new())
+}
+
+impl Default for WorldData {
+    fn default() -> Self {
+        Self {
+            classes: empty(),
+            airports: empty(),
+            flights: None,
+            planes: empty(),
+            waypoints: empty(),
+            wind: Wind::default(),
+            weather: empty(),
+            airspace: empty(),
+            terrain: Terrain::default(),
+            index: WorldIndex::default(),
+        }
+    }
+}
+
+impl Default for AirportData {
+    fn default() -> Self {
+        Self {
+            name: SmolStr::default(),
+            code: AirportCode::default(),
+            runways: empty(),
+            holding_fixes: empty(),
+            taxiways: empty(),
+            gates: empty(),
+            sids: empty(),
+            stars: empty(),
+        }
+    }
 }
 
 impl AirportData {
@@ -94,6 +274,44 @@ impl AirportData {
             / self.runways.len() as f32
             / 2.0
     }
+    /// The holding fix nearest to `pos`.
+    #[must_use]
+    pub fn holding_fix(&self, pos: Pos2) -> Option<Arc<HoldingFix>> {
+        if let Some(fix) = self
+            .holding_fixes
+            .iter()
+            .min_by(|a, b| a.end.distance(pos).total_cmp(&b.end.distance(pos)))
+        {
+            return Some(Arc::clone(fix));
+        }
+        let gate = self.runways.first()?.approach_gate();
+        Some(Arc::new(HoldingFix {
+            name: self.code.clone(),
+            start: gate.0 - gate.1.vec() * self.runways[0].len(),
+            end: gate.0,
+            left_turns: false,
+        }))
+    }
+    #[must_use]
+    pub fn taxiway(&self, name: &WaypointId) -> Option<&Arc<Waypoint>> {
+        self.taxiways.iter().find(|a| a.name == *name)
+    }
+    /// Whether planes park at gates and taxi to and from them.
+    #[must_use]
+    pub fn has_ground(&self) -> bool {
+        !self.taxiways.is_empty() && !self.gates.is_empty()
+    }
+    /// [`WorldData::taxi_route`] along `graph`, built from the taxiways.
+    fn taxi_route(&self, graph: &WaypointGraph, from: Pos2, to: Pos2) -> Option<Vec<Pos2>> {
+        let path = graph.shortest_path(graph.nearest(from)?, graph.nearest(to)?)?;
+        Some(
+            std::iter::once(from)
+                .chain(path.into_iter().map(|i| self.taxiways[i].pos))
+                .chain(std::iter::once(to))
+                .dedup()
+                .collect(),
+        )
+    }
 }
 
 #[derive(
@@ -119,6 +337,10 @@ pub struct Runway {
     pub altitude: f32,
     #[ts(as = "String")]
     pub class: Class,
+    /// Flown by planes that go around. Planes climb out straight ahead for one runway length if
+    /// unset.
+    #[serde(default)]
+    pub missed_approach: Option<MissedApproach>,
 }
 
 impl Runway {
@@ -138,6 +360,118 @@ impl Runway {
     pub fn ray(&self) -> Ray<Vec2> {
         Ray::new(self.start, self.end)
     }
+    /// Distance from `pos` to the nearest point of the runway.
+    #[must_use]
+    pub fn distance(&self, pos: Pos2) -> f32 {
+        let vec = self.end - self.start;
+        let t = ((pos - self.start).dot(vec) / vec.length_squared()).clamp(0.0, 1.0);
+        pos.distance(self.start + vec * t)
+    }
+    /// Where a landing plane lines up with the runway, one runway length before its start.
+    #[must_use]
+    pub fn approach_gate(&self) -> Pos2Angle {
+        Pos2Angle(
+            self.start - self.ray().vec,
+            Angle((self.end - self.start).to_angle()),
+        )
+    }
+    /// Points flown through after going around, and the height above the runway to climb to.
+    #[must_use]
+    pub fn missed_approach(&self, default_height: f32) -> (Vec<Pos2>, f32) {
+        self.missed_approach.as_ref().map_or_else(
+            || (vec![self.end + self.ray().vec], default_height),
+            |a| (a.path.to_vec(), a.height),
+        )
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct MissedApproach {
+    /// Points to fly through after the end of the runway, in order
+    #[ts(as = "Vec<(f32, f32)>")]
+    pub path: Arc<[Pos2]>,
+    /// Height above the runway to climb to
+    pub height: f32,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct HoldingFix {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    /// Start of the inbound leg
+    #[ts(as = "(f32, f32)")]
+    pub start: Pos2,
+    /// The fix itself, at the end of the inbound leg
+    #[ts(as = "(f32, f32)")]
+    pub end: Pos2,
+    /// Turn left at each end of the racetrack instead of right
+    #[serde(default)]
+    pub left_turns: bool,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    rkyv::Archive,
+    TS,
+    PartialEq,
+)]
+#[ts(export)]
+pub struct Gate {
+    #[ts(as = "String")]
+    pub name: SmolStr,
+    #[ts(as = "(f32, f32)")]
+    pub pos: Pos2,
+    /// Taxiway node planes are pushed back onto, and enter the gate from
+    #[ts(as = "String")]
+    pub taxiway: WaypointId,
+    /// Largest class of plane the gate fits
+    #[ts(as = "String")]
+    pub class: Class,
+    /// Airlines whose planes are parked here before anywhere else
+    #[serde(default = "empty")]
+    #[ts(as = "Vec<String>")]
+    pub airlines: Arc<[SmolStr]>,
+}
+
+impl Default for Gate {
+    fn default() -> Self {
+        Self {
+            name: SmolStr::default(),
+            pos: Pos2::default(),
+            taxiway: WaypointId::default(),
+            class: Class::default(),
+            airlines: empty(),
+        }
+    }
 }
 
 #[derive(
@@ -170,17 +504,13 @@ pub struct Flight {
 impl Flight {
     pub fn from(&self, wd: &WorldData) -> Result<Arc<AirportData>> {
         let out = wd
-            .airports
-            .iter()
-            .find(|a| a.code == self.from)
+            .airport(&self.from)
             .ok_or_else(|| eyre!("No airport `{}`", self.from))?;
         Ok(Arc::clone(out))
     }
     pub fn to(&self, wd: &WorldData) -> Result<Arc<AirportData>> {
         let out = wd
-            .airports
-            .iter()
-            .find(|a| a.code == self.to)
+            .airport(&self.to)
             .ok_or_else(|| eyre!("No airport `{}`", self.to))?;
         Ok(Arc::clone(out))
     }
@@ -188,9 +518,7 @@ impl Flight {
         self.plane
             .iter()
             .map(|p| {
-                wd.planes
-                    .iter()
-                    .find(|m| m.id == *p)
+                wd.plane_model(p)
                     .ok_or_else(|| eyre!("No plane model `{p}`"))
             })
             .map_ok(Arc::clone)
@@ -221,6 +549,15 @@ pub struct PlaneData {
     #[ts(as = "String")]
     pub class: Class,
     pub motion: ModelMotion,
+    /// Shortest runway the model can take off from
+    #[serde(default)]
+    pub min_takeoff_distance: f32,
+    /// Shortest runway the model can land on
+    #[serde(default)]
+    pub min_landing_distance: f32,
+    /// Strongest crosswind the model can take off or land in. Unlimited if unset.
+    #[serde(default)]
+    pub max_crosswind: Option<f32>,
     #[rkyv(with = rkyv::with::Map<rkyv::with::AsString>)]
     pub icon: Option<PathBuf>,
 }
@@ -268,67 +605,299 @@ pub struct Waypoint {
     pub connections: Arc<[WaypointId]>,
 }
 
+/// How many of the nearest waypoints are considered for entering and leaving the airways.
+const ROUTE_CANDIDATES: usize = 3;
+
 impl WorldData {
-    #[tracing::instrument(skip(self))]
-    pub fn find_waypoint_route(&self, from: Pos2Angle, to: Pos2) -> VecDeque<Arc<Waypoint>> {
-        let Some((from_waypoint, _)) = self
-            .waypoints
-            .iter()
-            .map(|a| (a, a.pos.distance(from.0)))
-            .sorted_by(|(_, a), (_, b)| a.total_cmp(b))
-            .next()
-        else {
-            return VecDeque::new();
+    /// Airway route for a plane leaving `from` to land at `to`. The entry and exit waypoints are
+    /// picked among those near either end to minimise the whole flight, including the turns from
+    /// the departure heading onto the route and from the route onto an approach gate. If `to` has
+    /// STARs, the exit is instead the start of one of them, flown on to its approach gate.
+    pub fn find_route(
+        &self,
+        from: Pos2Angle,
+        motion: &ModelMotion,
+        to: &AirportData,
+    ) -> VecDeque<Arc<Waypoint>> {
+        self.find_route_via(from, &[], motion, to, None::<fn(Pos2, Pos2) -> bool>)
+            .1
+    }
+    /// [`Self::find_route`] without flying any straight line between two positions for which
+    /// `blocked` holds.
+    pub fn find_route_avoiding<F: Fn(Pos2, Pos2) -> bool>(
+        &self,
+        from: Pos2Angle,
+        motion: &ModelMotion,
+        to: &AirportData,
+        blocked: F,
+    ) -> VecDeque<Arc<Waypoint>> {
+        self.find_route_via(from, &[], motion, to, Some(blocked)).1
+    }
+    /// [`Self::find_route_avoiding`] for a plane taking off, which joins the airways at the end of
+    /// whichever of `sids` makes for the shortest flight, if any can be flown. The SID is returned
+    /// with the route, which starts with its fixes. Nothing is avoided if `blocked` is `None`.
+    pub fn find_departure_route<F: Fn(Pos2, Pos2) -> bool>(
+        &self,
+        from: Pos2Angle,
+        sids: &[&Arc<Procedure>],
+        motion: &ModelMotion,
+        to: &AirportData,
+        blocked: Option<F>,
+    ) -> (Option<Arc<Procedure>>, VecDeque<Arc<Waypoint>>) {
+        let (sid, route) = self.find_route_via(from, sids, motion, to, blocked);
+        let Some(sid) = sid.map(|i| Arc::clone(sids[i])) else {
+            return (None, route);
         };
-        let Some((to_waypoint, _)) = self
-            .waypoints
+        let route = sid.waypoints().chain(route).collect();
+        (Some(sid), route)
+    }
+    /// Finds the route, entering the airways at the end of one of `sids` if there are any that can
+    /// be flown, and leaving them at the start of one of the STARs of `to` if it has any. The index
+    /// of the SID entered by is returned with the route. Without `blocked`, every route is looked
+    /// up in the cache.
+    #[tracing::instrument(skip(self, sids, motion, to, blocked), fields(to = %to.code))]
+    fn find_route_via<F: Fn(Pos2, Pos2) -> bool>(
+        &self,
+        from: Pos2Angle,
+        sids: &[&Arc<Procedure>],
+        motion: &ModelMotion,
+        to: &AirportData,
+        blocked: Option<F>,
+    ) -> (Option<usize>, VecDeque<Arc<Waypoint>>) {
+        let index = self.index();
+        let uncached;
+        let (graph, routes) = if Arc::ptr_eq(&index.graph_source, &self.waypoints) {
+            (&index.graph, &index.routes)
+        } else {
+            uncached = (WaypointGraph::new(&self.waypoints), RouteCache::default());
+            (&uncached.0, &uncached.1)
+        };
+        // routes through blocked connections are not cached, as they only hold for now
+        let blocked_edges = blocked
+            .as_ref()
+            .map(|f| graph.blocked_edges(f))
+            .unwrap_or_default();
+        let blocked = move |a: Pos2, b: Pos2| blocked.as_ref().is_some_and(|f| f(a, b));
+        let find = |entry: usize, exit: usize| {
+            if blocked_edges.is_empty() {
+                routes.get_or_find(graph, entry, exit)
+            } else {
+                graph
+                    .shortest_path_avoiding(entry, exit, &blocked_edges)
+                    .map(Arc::from)
+            }
+        };
+        let turn_cost = |a: Pos2Angle, b: Pos2Angle| turn_cost(a, b, motion);
+        let lookup = |name: &WaypointId| index.waypoints.get(&self.waypoints, name, |a| &a.name);
+
+        let mut entries = sids
+            .iter()
+            .enumerate()
+            .filter_map(|(sid_i, sid)| {
+                let i = lookup(&sid.waypoint)?;
+                let pos = self.waypoints[i].pos;
+                let first = sid.fixes.first().map_or(pos, |a| a.pos);
+                sid.flyable(from.0, pos, &blocked).then(|| {
+                    let cost = turn_cost(from, Pos2Angle(first, towards(from.0, first)))
+                        + sid.length(first, pos);
+                    (i, cost, Some(sid_i))
+                })
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            entries = graph
+                .nearest_k(from.0, ROUTE_CANDIDATES)
+                .into_iter()
+                .filter(|i| !blocked(from.0, self.waypoints[*i].pos))
+                .map(|i| {
+                    let pos = self.waypoints[i].pos;
+                    (
+                        i,
+                        turn_cost(from, Pos2Angle(pos, towards(from.0, pos))),
+                        None,
+                    )
+                })
+                .collect();
+        }
+        let mut exits = self.star_exits(motion, to, &blocked);
+        if exits.is_empty() {
+            exits = to
+                .runways
+                .iter()
+                .map(|a| a.approach_gate())
+                .flat_map(|gate| {
+                    graph
+                        .nearest_k(gate.0, ROUTE_CANDIDATES)
+                        .into_iter()
+                        .map(move |i| (i, gate))
+                })
+                .filter(|(i, gate)| !blocked(self.waypoints[*i].pos, gate.0))
+                .map(|(i, gate)| {
+                    let pos = self.waypoints[i].pos;
+                    (i, turn_cost(Pos2Angle(pos, towards(pos, gate.0)), gate))
+                })
+                .collect();
+        }
+        let exits = exits
+            .into_iter()
+            .into_grouping_map()
+            .min_by(|_, a, b| a.total_cmp(b));
+
+        let Some((sid, route, cost)) = entries
             .iter()
-            .map(|a| (a, a.pos.distance(to)))
-            .sorted_by(|(_, a), (_, b)| a.total_cmp(b))
-            .next()
+            .cartesian_product(exits.iter().sorted_by_key(|(i, _)| **i))
+            .filter_map(|((entry, entry_cost, sid), (exit, exit_cost))| {
+                let route = find(*entry, *exit)?;
+                let cost = entry_cost + graph.length(&route) + exit_cost;
+                Some((*sid, route, cost))
+            })
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
         else {
-            return VecDeque::new();
+            if !graph.is_empty() {
+                warn!("Cannot find path");
+            }
+            return (None, VecDeque::new());
         };
-        trace!(?from_waypoint, ?to_waypoint);
+        let total_path = route
+            .iter()
+            .map(|i| Arc::clone(&self.waypoints[*i]))
+            .collect();
+        trace!(?total_path, cost, "Found path");
+        (sid, total_path)
+    }
+    /// Waypoints to leave the airways at onto the STARs of `to` that can be flown, each with the
+    /// cost of flying the STAR from it and turning onto the approach it ends at.
+    fn star_exits<F: Fn(Pos2, Pos2) -> bool>(
+        &self,
+        motion: &ModelMotion,
+        to: &AirportData,
+        blocked: &F,
+    ) -> Vec<(usize, f32)> {
+        let index = self.index();
+        to.stars
+            .iter()
+            .filter_map(|star| {
+                let i = index
+                    .waypoints
+                    .get(&self.waypoints, &star.waypoint, |a| &a.name)?;
+                Some((star, i))
+            })
+            .flat_map(|(star, i)| {
+                let pos = self.waypoints[i].pos;
+                to.runways
+                    .iter()
+                    .filter(|runway| star.serves(runway))
+                    .map(|runway| runway.approach_gate())
+                    .filter(move |gate| star.flyable(pos, gate.0, blocked))
+                    .map(move |gate| {
+                        let points = star.path(pos, gate.0).collect::<Vec<_>>();
+                        let heading = match points[..] {
+                            [.., before, last, _] => Pos2Angle(last, towards(before, last)),
+                            _ => Pos2Angle(pos, towards(pos, gate.0)),
+                        };
+                        let length = points[..points.len() - 1]
+                            .iter()
+                            .tuple_windows()
+                            .map(|(a, b)| a.distance(*b))
+                            .sum::<f32>();
+                        (i, length + turn_cost(heading, gate, motion))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Length of the shortest path from `a` to `b` for a plane turning as tightly as `motion` allows.
+fn turn_cost(a: Pos2Angle, b: Pos2Angle, motion: &ModelMotion) -> f32 {
+    DubinsPath::shortest_from(a.into(), b.into(), motion.turning_radius)
+        .map_or(f32::INFINITY, |p| p.length())
+}
+
+fn towards(a: Pos2, b: Pos2) -> Angle {
+    Angle((b - a).to_angle())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
 
-        let h = |w: &Arc<Waypoint>| w.pos.distance(to_waypoint.pos);
-        let mut came_from = HashMap::<&WaypointId, &Arc<Waypoint>>::new();
-        let mut g_score = HashMap::from([(&from_waypoint.name, 0.0)]);
-        let mut f_score = HashMap::from([(&from_waypoint.name, h(from_waypoint))]);
+    use super::*;
+    use crate::fixtures::{self, world};
 
-        while let Some((current_name, _)) = f_score
+    #[test]
+    fn route_follows_headings() {
+        let waypoint = |name: &str, x: f32, y: f32, connections: &[&str]| {
+            Arc::new(Waypoint {
+                name: name.into(),
+                pos: Pos2::new(x, y),
+                connections: connections.iter().map(|a| WaypointId::from(*a)).collect(),
+            })
+        };
+        // departing north from FOO and landing north at BAR, whose approach gate is at (1000, -50).
+        // BEHIND and NORTH are the nearest to either end but need the plane to turn around
+        let world = WorldData {
+            waypoints: Arc::new([
+                waypoint("BEHIND", 0.0, -50.0, &["NORTH", "SOUTH"]),
+                waypoint("AHEAD", 0.0, 200.0, &["NORTH", "SOUTH"]),
+                waypoint("NORTH", 1000.0, 150.0, &["BEHIND", "AHEAD"]),
+                waypoint("SOUTH", 1000.0, -300.0, &["BEHIND", "AHEAD"]),
+            ]),
+            ..world()
+        };
+        let motion = ModelMotion {
+            turning_radius: 100.0,
+            ..world.planes[0].motion
+        };
+        let from = Pos2Angle(Pos2::new(0.0, 50.0), Angle(FRAC_PI_2));
+        let route = world
+            .find_route(from, &motion, world.airport(&"BAR".into()).unwrap())
             .iter()
-            .map(|(a, b)| (*a, *b))
-            .min_by(|(_, v1), (_, v2)| v1.total_cmp(v2))
-        {
-            let mut current = self.waypoint(current_name).unwrap();
-            if current == to_waypoint {
-                let mut total_path = VecDeque::new();
-                while let Some(new_current) = came_from.get(&current.name) {
-                    current = new_current;
-                    total_path.push_front(Arc::clone(current));
-                }
-                trace!(?total_path, "Found path");
-                total_path.pop_front();
-                return total_path;
-            }
-            f_score.remove(&current.name);
-
-            for neighbour_name in current.connections.iter() {
-                let Some(neighbour) = self.waypoint(neighbour_name) else {
-                    continue;
-                };
-                let tent_g = *g_score.get(&current.name).unwrap_or(&f32::INFINITY)
-                    + current.pos.distance(neighbour.pos);
-                if tent_g < *g_score.get(&neighbour.name).unwrap_or(&f32::INFINITY) {
-                    came_from.insert(&neighbour.name, current);
-                    g_score.insert(&neighbour.name, tent_g);
-                    f_score.insert(&neighbour.name, tent_g + h(neighbour));
-                }
-            }
-        }
+            .map(|a| a.name.as_str().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(route, ["AHEAD", "SOUTH"]);
+    }
+
+    #[test]
+    fn taxi_route() {
+        let node = |name: &str, x: f32, connections: &[&str]| {
+            fixtures::waypoint(name, Pos2::new(x, -10.0), connections)
+        };
+        let mut foo = (*fixtures::airport("FOO", 0.0)).clone();
+        foo.taxiways = Arc::new([node("A", 0.0, &["B"]), node("B", 20.0, &["A"])]);
+        let world = WorldData {
+            airports: Arc::new([Arc::new(foo.clone())]),
+            ..world()
+        };
+        let (from, to) = (Pos2::new(0.0, 0.0), Pos2::new(20.0, -20.0));
+        assert_eq!(
+            world.taxi_route(&world.airports[0], from, to),
+            Some(vec![
+                from,
+                Pos2::new(0.0, -10.0),
+                Pos2::new(20.0, -10.0),
+                to
+            ])
+        );
 
-        warn!("Cannot find path");
-        VecDeque::new()
+        // taxiways the index wasn't built from have their own graph
+        foo.taxiways = Arc::new([node("A", 0.0, &[]), node("B", 20.0, &[])]);
+        assert_eq!(world.taxi_route(&foo, from, to), None);
+    }
+
+    #[test]
+    fn cmp_class() {
+        let world = WorldData {
+            classes: Arc::new([
+                Arc::new(["small".into(), "medium".into(), "large".into()]),
+                Arc::new(["glider".into()]),
+            ]),
+            ..WorldData::default()
+        };
+        let cmp = |a: &str, b: &str| world.cmp_class(&a.into(), &b.into());
+        assert_eq!(cmp("small", "large"), Some(Ordering::Less));
+        assert_eq!(cmp("large", "medium"), Some(Ordering::Greater));
+        assert_eq!(cmp("glider", "glider"), Some(Ordering::Equal));
+        assert_eq!(cmp("glider", "small"), None);
+        assert_eq!(cmp("", ""), Some(Ordering::Equal));
     }
 }
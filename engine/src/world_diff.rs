@@ -0,0 +1,131 @@
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+use crate::{
+    util::{AirportCode, FlightCode, PlaneModelId, WaypointId},
+    world_data::{Runway, WorldData},
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Diff<K> {
+    pub added: Vec<K>,
+    pub removed: Vec<K>,
+    pub changed: Vec<K>,
+}
+
+impl<K: Clone + Eq + Hash> Diff<K> {
+    fn new<V: PartialEq, I: IntoIterator<Item = (K, V)>, J: IntoIterator<Item = (K, V)>>(
+        old: I,
+        new: J,
+    ) -> Self {
+        let old = old.into_iter().collect::<Vec<_>>();
+        let new = new.into_iter().collect::<Vec<_>>();
+        let old_map = old.iter().map(|(k, v)| (k, v)).collect::<HashMap<_, _>>();
+        let new_map = new.iter().map(|(k, v)| (k, v)).collect::<HashMap<_, _>>();
+
+        let mut added = vec![];
+        let mut changed = vec![];
+        for (k, v) in &new {
+            match old_map.get(k) {
+                None => added.push(k.clone()),
+                Some(old_v) if *old_v != v => changed.push(k.clone()),
+                Some(_) => {}
+            }
+        }
+        let removed = old
+            .iter()
+            .filter(|(k, _)| !new_map.contains_key(k))
+            .map(|(k, _)| k.clone())
+            .collect();
+        Self {
+            added,
+            removed,
+            changed,
+        }
+    }
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct WorldDiff {
+    pub airports: Diff<AirportCode>,
+    pub runways: Diff<(AirportCode, SmolStr)>,
+    pub waypoints: Diff<WaypointId>,
+    pub planes: Diff<PlaneModelId>,
+    pub flights: Diff<FlightCode>,
+    /// Whether the wind changed
+    pub wind: bool,
+    pub weather: Diff<SmolStr>,
+    pub airspace: Diff<SmolStr>,
+    /// Whether the terrain changed
+    pub terrain: bool,
+}
+
+impl WorldDiff {
+    #[must_use]
+    pub fn new(old: &WorldData, new: &WorldData) -> Self {
+        Self {
+            airports: Diff::new(
+                old.airports.iter().map(|a| (a.code.clone(), a)),
+                new.airports.iter().map(|a| (a.code.clone(), a)),
+            ),
+            runways: Diff::new(runways(old), runways(new)),
+            waypoints: Diff::new(
+                old.waypoints.iter().map(|a| (a.name.clone(), a)),
+                new.waypoints.iter().map(|a| (a.name.clone(), a)),
+            ),
+            planes: Diff::new(
+                old.planes.iter().map(|a| (a.id.clone(), a)),
+                new.planes.iter().map(|a| (a.id.clone(), a)),
+            ),
+            flights: Diff::new(
+                old.flights
+                    .iter()
+                    .flat_map(|a| a.iter())
+                    .map(|a| (a.code.clone(), a)),
+                new.flights
+                    .iter()
+                    .flat_map(|a| a.iter())
+                    .map(|a| (a.code.clone(), a)),
+            ),
+            wind: old.wind != new.wind,
+            weather: Diff::new(
+                old.weather.iter().map(|a| (a.name.clone(), a)),
+                new.weather.iter().map(|a| (a.name.clone(), a)),
+            ),
+            airspace: Diff::new(
+                old.airspace.iter().map(|a| (a.name.clone(), a)),
+                new.airspace.iter().map(|a| (a.name.clone(), a)),
+            ),
+            terrain: old.terrain != new.terrain,
+        }
+    }
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+            && self.runways.is_empty()
+            && self.waypoints.is_empty()
+            && self.planes.is_empty()
+            && self.flights.is_empty()
+            && !self.wind
+            && self.weather.is_empty()
+            && self.airspace.is_empty()
+            && !self.terrain
+    }
+}
+
+fn runways(wd: &WorldData) -> Vec<((AirportCode, SmolStr), &Arc<Runway>)> {
+    wd.airports
+        .iter()
+        .flat_map(|a| {
+            a.runways
+                .iter()
+                .map(|r| ((a.code.clone(), r.name.clone()), r))
+        })
+        .collect()
+}
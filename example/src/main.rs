@@ -15,11 +15,13 @@ async fn main() -> Result<()> {
         plane_spawn_chance: 0.1,
         cruising_altitude_plus: 100.0,
         cruising_altitude_minus: 100.0,
+        history_len: 360,
+        history_interval: 10,
         ..Config::default()
     };
-    let engine = air_traffic_simulator::Engine::new(world_data, engine_config);
+    let engine = air_traffic_simulator::Engine::new(world_data, engine_config)?;
 
-    air_traffic_simulator::run_server(engine, None).await?;
+    air_traffic_simulator::run_server(engine, None, None).await?;
 
     Ok(())
 }
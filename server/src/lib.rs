@@ -3,12 +3,17 @@ use std::process::Command;
 use std::sync::Arc;
 
 use engine::{
+    config::Config,
     engine::Engine,
+    save,
+    state::airport::Airport,
     util::{AirportCode, AirportStateId, PlaneStateId},
+    world_data::WorldData,
 };
 use eyre::Result;
+use serde::Deserialize;
 use socketioxide::{
-    extract::{AckSender, Data, SocketRef, State},
+    extract::{AckSender, Data, SocketRef, State, TryData},
     SocketIo,
 };
 use tokio::{
@@ -68,7 +73,21 @@ fn build_client(client_config: Option<&str>) -> Result<tempfile::TempDir> {
     Ok(dir2)
 }
 
-async fn websocket_connect(socket: SocketRef) {
+/// The token a socket has to present in its handshake to use the admin events. Without one the
+/// admin events are disabled.
+#[derive(Clone)]
+struct AdminToken(Option<Arc<str>>);
+
+#[derive(Deserialize)]
+struct Auth {
+    admin_token: String,
+}
+
+async fn websocket_connect(
+    socket: SocketRef,
+    TryData(auth): TryData<Auth>,
+    State(AdminToken(admin_token)): State<AdminToken>,
+) {
     info!("Socket.IO connected: {:?} {:?}", socket.ns(), socket.id);
 
     socket.on(
@@ -111,6 +130,16 @@ async fn websocket_connect(socket: SocketRef) {
         },
     );
 
+    socket.on(
+        "airport_gate_utilisation",
+        |ack: AckSender, Data(id): Data<AirportStateId>, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&engine.state.airport(&id).and_then(Airport::gate_utilisation))
+                .inspect_err(|e| error!(ev="airport_gate_utilisation", "{e:#}"));
+        },
+    );
+
     socket.on(
         "world_data",
         |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
@@ -121,6 +150,16 @@ async fn websocket_connect(socket: SocketRef) {
         },
     );
 
+    socket.on(
+        "stats",
+        |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&engine.state.stats)
+                .inspect_err(|e| error!(ev = "stats", "{e:#}"));
+        },
+    );
+
     socket.on(
         "engine_config",
         |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
@@ -130,14 +169,155 @@ async fn websocket_connect(socket: SocketRef) {
                 .inspect_err(|e| error!(ev = "engine_config", "{e:#}"));
         },
     );
+
+    on_monitors(&socket);
+    on_history(&socket);
+    if admin_token
+        .as_deref()
+        .is_some_and(|token| auth.is_ok_and(|auth| *auth.admin_token == *token))
+    {
+        info!("Socket.IO admin connected: {:?}", socket.id);
+        on_admin(&socket);
+    }
 }
 
+fn on_monitors(socket: &SocketRef) {
+    socket.on(
+        "conflicts",
+        |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&engine.state.conflicts)
+                .inspect_err(|e| error!(ev = "conflicts", "{e:#}"));
+        },
+    );
+
+    socket.on(
+        "infringements",
+        |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&engine.state.infringements)
+                .inspect_err(|e| error!(ev = "infringements", "{e:#}"));
+        },
+    );
+
+    socket.on(
+        "terrain_warnings",
+        |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&engine.state.terrain_warnings)
+                .inspect_err(|e| error!(ev = "terrain_warnings", "{e:#}"));
+        },
+    );
+
+    socket.on(
+        "weather",
+        |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&engine.world.weather_at(engine.state.clock.elapsed))
+                .inspect_err(|e| error!(ev = "weather", "{e:#}"));
+        },
+    );
+}
+
+fn on_history(socket: &SocketRef) {
+    socket.on(
+        "history",
+        |ack: AckSender, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let engine = engine_arc.read().await;
+            let _ = ack
+                .send(&(
+                    engine.history.ticks().collect::<Vec<_>>(),
+                    engine.state.clock.tick,
+                ))
+                .inspect_err(|e| error!(ev = "history", "{e:#}"));
+        },
+    );
+}
+
+fn on_admin(socket: &SocketRef) {
+    socket.on(
+        "rewind",
+        |ack: AckSender,
+         io: SocketIo,
+         Data(tick): Data<u64>,
+         engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let mut engine = engine_arc.write().await;
+            let rewound = engine
+                .rewind(tick)
+                .inspect_err(|e| error!(ev = "rewind", "{e:#}"))
+                .ok()
+                .map(|removed| (removed, engine.state.coord_state(), engine.state.clock));
+            drop(engine);
+            let _ = ack
+                .send(&rewound.as_ref().map(|(_, _, clock)| clock.tick))
+                .inspect_err(|e| error!(ev = "rewind", "{e:#}"));
+            let Some(state) = rewound else { return };
+            let _ = io
+                .emit("state", &state)
+                .await
+                .inspect_err(|e| error!(ev = "state", "{e:#}"));
+        },
+    );
+
+    socket.on(
+        "admin_reload_world",
+        |ack: AckSender,
+         io: SocketIo,
+         Data(world): Data<WorldData>,
+         engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let mut engine = engine_arc.write().await;
+            let result = engine.reload_world(world).map_err(|e| format!("{e:#}"));
+            drop(engine);
+            let _ = ack
+                .send(&result)
+                .inspect_err(|e| error!(ev = "admin_reload_world", "{e:#}"));
+            if result.is_err() {
+                return;
+            }
+            let _ = io
+                .emit("world_data_changed", &())
+                .await
+                .inspect_err(|e| error!(ev = "world_data_changed", "{e:#}"));
+        },
+    );
+
+    socket.on(
+        "admin_set_config",
+        |ack: AckSender, Data(config): Data<Config>, engine_arc: State<Arc<RwLock<Engine>>>| async move {
+            let mut engine = engine_arc.write().await;
+            // the paths are files on the server, so only whoever started it gets to pick them
+            let result = if config.save_path != engine.config.save_path
+                || config.journal_path != engine.config.journal_path
+            {
+                Err("The save and journal paths can't be changed while running")
+            } else {
+                engine.set_config(config);
+                Ok(&engine.config)
+            };
+            let _ = ack
+                .send(&result)
+                .inspect_err(|e| error!(ev = "admin_set_config", "{e:#}"));
+        },
+    );
+}
+
+/// Serves the engine to clients. Sockets that connect with `{ admin_token }` matching
+/// `admin_token` can also rewind the simulation, reload the world and change the config.
 #[tracing::instrument(skip_all)]
 #[allow(clippy::allow_attributes, unused_variables)]
-pub async fn run_server(engine: Engine, client_config: Option<&str>) -> Result<()> {
+pub async fn run_server(
+    engine: Engine,
+    client_config: Option<&str>,
+    admin_token: Option<&str>,
+) -> Result<()> {
     let engine_arc = Arc::new(RwLock::new(engine));
     let (layer, io) = SocketIo::builder()
         .with_state(Arc::clone(&engine_arc))
+        .with_state(AdminToken(admin_token.map(Arc::from)))
         .build_layer();
     io.ns("/", websocket_connect);
 
@@ -164,11 +344,32 @@ pub async fn run_server(engine: Engine, client_config: Option<&str>) -> Result<(
             let start = Instant::now();
             let mut engine = engine_arc.write().await;
             let (removed, state) = engine.tick();
+            let clock = engine.state.clock;
+            let conflicts = engine.state.conflicts.clone();
+            let infringements = engine.state.infringements.clone();
+            let terrain_warnings = engine.state.terrain_warnings.clone();
+            let weather = engine.world.weather_at(clock.elapsed);
             drop(engine);
             let _ = io
-                .emit("state", &(removed, state))
+                .emit("state", &(removed, state, clock))
                 .await
                 .inspect_err(|e| error!(ev = "state", "{e:#}"));
+            let _ = io
+                .emit("conflicts", &conflicts)
+                .await
+                .inspect_err(|e| error!(ev = "conflicts", "{e:#}"));
+            let _ = io
+                .emit("infringements", &infringements)
+                .await
+                .inspect_err(|e| error!(ev = "infringements", "{e:#}"));
+            let _ = io
+                .emit("terrain_warnings", &terrain_warnings)
+                .await
+                .inspect_err(|e| error!(ev = "terrain_warnings", "{e:#}"));
+            let _ = io
+                .emit("weather", &weather)
+                .await
+                .inspect_err(|e| error!(ev = "weather", "{e:#}"));
 
             info!(delta=?start.elapsed(), "tick");
             tokio::time::sleep(Duration::from_secs(1).saturating_sub(start.elapsed())).await;
@@ -185,12 +386,12 @@ pub async fn run_server(engine: Engine, client_config: Option<&str>) -> Result<(
                         info!("No save path configured");
                         return Ok(true);
                     };
-                    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&*engine)?;
+                    let bytes = save::to_bytes(&engine)?;
                     drop(engine);
                     async_fs::write(save_path, bytes).await?;
 
                     info!(delta=?start.elapsed(), "save");
-                    tokio::time::sleep(Duration::from_secs(60).saturating_sub(start.elapsed()))
+                    tokio::time::sleep(Duration::from_mins(1).saturating_sub(start.elapsed()))
                         .await;
                     Result::<_>::Ok(false)
                 })